@@ -0,0 +1,115 @@
+use std::ops::Range;
+
+use crate::Scroll;
+use crate::memory::{CACHED_PAGES, PAGE_LEN, PagedMemory};
+use crate::ui::hexdump::HEXDUMP_WIDTH;
+
+/// Largest `snapshot-mem`, so that none of its pages are evicted
+pub const SNAPSHOT_MAX_LEN: usize = CACHED_PAGES * PAGE_LEN;
+
+/// Side by side comparison of two memory snapshots, or of a snapshot with the live memory of its
+/// range
+#[derive(Debug, Clone)]
+pub struct MemoryDiff {
+    pub left: String,
+    /// `None` compares with `live`
+    pub right: Option<String>,
+    /// Memory of the range of `left` read when the diff was opened, and again on every stop
+    pub live: Option<PagedMemory>,
+    /// Runs of differing bytes by offset, once both sides are read
+    pub differences: Option<Vec<Range<usize>>>,
+    /// Selected run of `differences`
+    pub current: usize,
+    pub scroll: Scroll,
+    /// Bytes per row of each side, from the last draw
+    pub width: usize,
+}
+
+impl MemoryDiff {
+    pub fn new(left: String, right: Option<String>, live: Option<PagedMemory>) -> Self {
+        Self {
+            left,
+            right,
+            live,
+            differences: None,
+            current: 0,
+            scroll: Scroll::default(),
+            width: HEXDUMP_WIDTH,
+        }
+    }
+
+    /// Name of the right side, for the title
+    pub fn right_name(&self) -> &str {
+        self.right.as_deref().unwrap_or("live")
+    }
+
+    /// Select the next run of differences, or the previous one, wrapping around. The offset of
+    /// its first byte
+    pub fn next_difference(&mut self, forward: bool) -> Option<usize> {
+        let differences = self.differences.as_ref().filter(|d| !d.is_empty())?;
+        let len = differences.len();
+        self.current =
+            if forward { (self.current + 1) % len } else { (self.current + len - 1) % len };
+        Some(differences[self.current].start)
+    }
+
+    /// Whether the byte at `offset` differs, and if its run is the selected one
+    pub fn at(&self, offset: usize) -> Option<bool> {
+        let differences = self.differences.as_ref()?;
+        let index = differences.partition_point(|run| run.end <= offset);
+        let run = differences.get(index)?;
+        (run.start <= offset).then_some(index == self.current)
+    }
+}
+
+/// Runs of offsets where `left` and `right` differ, comparing the bytes both of them cover. An
+/// unreadable byte differs from a readable one
+pub fn differences(left: &PagedMemory, right: &PagedMemory) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = vec![];
+    for offset in 0..left.len.min(right.len) {
+        if left.get(offset) == right.get(offset) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(run) if run.end == offset => run.end += 1,
+            _ => runs.push(offset..offset + 1),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_differences() {
+        let mut a = vec![0u8; 0x20];
+        let mut b = a.clone();
+        b[2] = 1;
+        b[3] = 1;
+        b[0x10] = 1;
+        a.push(0xff);
+        let left = PagedMemory::from_bytes(0x1000, &a);
+        let right = PagedMemory::from_bytes(0x2000, &b);
+        assert_eq!(differences(&left, &right), vec![2..4, 0x10..0x11]);
+
+        let mut right = PagedMemory::new(0x2000, 0x20);
+        right.set_unreadable(0);
+        assert_eq!(differences(&left, &right), vec![0..0x20]);
+    }
+
+    #[test]
+    fn test_next_difference() {
+        let mut diff = MemoryDiff::new("a".to_string(), None, None);
+        assert_eq!(diff.next_difference(true), None);
+        diff.differences = Some(vec![2..4, 0x10..0x11]);
+        assert_eq!(diff.next_difference(true), Some(0x10));
+        assert_eq!(diff.next_difference(true), Some(2));
+        assert_eq!(diff.at(3), Some(true));
+        assert_eq!(diff.at(0x10), Some(false));
+        assert_eq!(diff.at(4), None);
+        assert_eq!(diff.next_difference(false), Some(0x10));
+        assert_eq!(diff.right_name(), "live");
+    }
+}
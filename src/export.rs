@@ -0,0 +1,318 @@
+use std::fmt::Write;
+
+/// Bytes per line of the C and Rust arrays
+const ARRAY_WIDTH: usize = 12;
+
+/// Data bytes per Intel HEX and S-record record
+const RECORD_LEN: usize = 16;
+
+/// Bytes of the S-record header name, which with the address and the checksum fit the byte count
+const SREC_NAME_LEN: usize = 0xff - 3;
+
+/// Columns of a base64 line, as MIME wraps it
+const BASE64_WIDTH: usize = 76;
+
+/// How the save popup of the hexdump writes the bytes, cycled with Tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Raw,
+    /// `const uint8_t name[N] = { .. };`
+    C,
+    /// `pub const NAME: [u8; N] = [..];`
+    Rust,
+    IntelHex,
+    /// Motorola S-record
+    SRecord,
+    Base64,
+    /// `xxd` text dump, at the target addresses
+    Xxd,
+}
+
+impl ExportFormat {
+    pub fn next(self) -> Self {
+        match self {
+            ExportFormat::Raw => ExportFormat::C,
+            ExportFormat::C => ExportFormat::Rust,
+            ExportFormat::Rust => ExportFormat::IntelHex,
+            ExportFormat::IntelHex => ExportFormat::SRecord,
+            ExportFormat::SRecord => ExportFormat::Base64,
+            ExportFormat::Base64 => ExportFormat::Xxd,
+            ExportFormat::Xxd => ExportFormat::Raw,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Raw => "raw",
+            ExportFormat::C => "C array",
+            ExportFormat::Rust => "Rust array",
+            ExportFormat::IntelHex => "Intel HEX",
+            ExportFormat::SRecord => "S-record",
+            ExportFormat::Base64 => "base64",
+            ExportFormat::Xxd => "xxd",
+        }
+    }
+
+    /// File contents of the `bytes` read from `addr`. `name` names the C and Rust arrays
+    pub fn export(self, name: &str, addr: u64, bytes: &[u8]) -> Result<Vec<u8>, String> {
+        let text = match self {
+            ExportFormat::Raw => return Ok(bytes.to_vec()),
+            ExportFormat::C => c_array(name, addr, bytes),
+            ExportFormat::Rust => rust_array(name, addr, bytes),
+            ExportFormat::IntelHex => intel_hex(addr, bytes)?,
+            ExportFormat::SRecord => srecord(name, addr, bytes)?,
+            ExportFormat::Base64 => base64(bytes),
+            ExportFormat::Xxd => xxd(addr, bytes),
+        };
+        Ok(text.into_bytes())
+    }
+}
+
+/// Identifier of the arrays from a file stem: other characters become `_`, and it never starts
+/// with a digit
+pub fn identifier(stem: &str) -> String {
+    let mut ident: String =
+        stem.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+/// `0x..` bytes, [`ARRAY_WIDTH`] per indented line, each ending with a comma
+fn array_rows(bytes: &[u8]) -> String {
+    let mut rows = String::new();
+    for chunk in bytes.chunks(ARRAY_WIDTH) {
+        let row: Vec<String> = chunk.iter().map(|b| format!("0x{b:02x},")).collect();
+        let _ = writeln!(rows, "    {}", row.join(" "));
+    }
+    rows
+}
+
+fn c_array(name: &str, addr: u64, bytes: &[u8]) -> String {
+    format!(
+        "/* 0x{:x} bytes from 0x{addr:x} */\nconst uint8_t {name}[{}] = {{\n{}}};\n",
+        bytes.len(),
+        bytes.len(),
+        array_rows(bytes)
+    )
+}
+
+fn rust_array(name: &str, addr: u64, bytes: &[u8]) -> String {
+    format!(
+        "/// 0x{:x} bytes from 0x{addr:x}\npub const {}: [u8; {}] = [\n{}];\n",
+        bytes.len(),
+        name.to_uppercase(),
+        bytes.len(),
+        array_rows(bytes)
+    )
+}
+
+/// Intel HEX record of `kind`: the byte count, the 16 bit address, the kind, the data and the
+/// two's complement of their sum
+fn ihex_record(kind: u8, addr: u16, data: &[u8]) -> String {
+    let [high, low] = addr.to_be_bytes();
+    let mut record = vec![data.len() as u8, high, low, kind];
+    record.extend_from_slice(data);
+    let sum = record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    record.push(sum.wrapping_neg());
+    format!(":{}\n", hex::encode_upper(record))
+}
+
+/// Data records at their load address, with an extended linear address record each time its
+/// upper 16 bits change
+fn intel_hex(addr: u64, bytes: &[u8]) -> Result<String, String> {
+    let end = end_addr(addr, bytes)?;
+    if end > 1 << 32 {
+        return Err(format!("0x{addr:x}..0x{end:x} is past the 32 bit addresses of Intel HEX"));
+    }
+    let mut text = String::new();
+    let mut upper = None;
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let at = addr + offset as u64;
+        if upper != Some(at >> 16) {
+            upper = Some(at >> 16);
+            text.push_str(&ihex_record(4, 0, &((at >> 16) as u16).to_be_bytes()));
+        }
+        // records never cross a 64K segment
+        let segment_left = 0x1_0000 - (at & 0xffff) as usize;
+        let len = RECORD_LEN.min(segment_left).min(bytes.len() - offset);
+        text.push_str(&ihex_record(0, at as u16, &bytes[offset..offset + len]));
+        offset += len;
+    }
+    text.push_str(&ihex_record(1, 0, &[]));
+    Ok(text)
+}
+
+/// S-record of `kind` with an address of `addr_len` bytes: the byte count, the address, the data
+/// and the ones' complement of their sum
+fn srec_record(kind: u8, addr: u64, addr_len: usize, data: &[u8]) -> String {
+    let mut record = vec![(addr_len + data.len() + 1) as u8];
+    record.extend_from_slice(&addr.to_be_bytes()[8 - addr_len..]);
+    record.extend_from_slice(data);
+    let sum = record.iter().fold(0u8, |sum, b| sum.wrapping_add(*b));
+    record.push(!sum);
+    format!("S{kind}{}\n", hex::encode_upper(record))
+}
+
+/// Header with `name`, data records with the narrowest addresses that hold the load address, and
+/// the matching termination record
+fn srecord(name: &str, addr: u64, bytes: &[u8]) -> Result<String, String> {
+    let end = end_addr(addr, bytes)?;
+    let (data, termination, addr_len) = match end {
+        end if end <= 1 << 16 => (1, 9, 2),
+        end if end <= 1 << 24 => (2, 8, 3),
+        end if end <= 1 << 32 => (3, 7, 4),
+        _ => {
+            return Err(format!("0x{addr:x}..0x{end:x} is past the 32 bit addresses of S-records"));
+        }
+    };
+    let name = &name.as_bytes()[..name.len().min(SREC_NAME_LEN)];
+    let mut text = srec_record(0, 0, 2, name);
+    for (index, chunk) in bytes.chunks(RECORD_LEN).enumerate() {
+        let at = addr + (index * RECORD_LEN) as u64;
+        text.push_str(&srec_record(data, at, addr_len, chunk));
+    }
+    text.push_str(&srec_record(termination, addr, addr_len, &[]));
+    Ok(text)
+}
+
+/// Address past the last of `bytes`, an error when it wraps around
+fn end_addr(addr: u64, bytes: &[u8]) -> Result<u64, String> {
+    addr.checked_add(bytes.len() as u64)
+        .ok_or_else(|| format!("0x{addr:x}+0x{:x} wraps around the address space", bytes.len()))
+}
+
+/// Standard base64 with padding, wrapped at [`BASE64_WIDTH`]
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, b)| n | u32::from(*b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    let mut text = String::new();
+    for line in encoded.as_bytes().chunks(BASE64_WIDTH) {
+        text.push_str(std::str::from_utf8(line).unwrap());
+        text.push('\n');
+    }
+    text
+}
+
+/// Rows of 16 bytes as `xxd -o addr` prints them: the address, pairs of bytes and the ascii
+fn xxd(addr: u64, bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for (index, chunk) in bytes.chunks(16).enumerate() {
+        let pairs: Vec<String> = chunk.chunks(2).map(hex::encode).collect();
+        let ascii: String = chunk
+            .iter()
+            .map(|b| if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' })
+            .collect();
+        let at = addr + (index * 16) as u64;
+        let _ = writeln!(text, "{at:08x}: {:<39}  {ascii}", pairs.join(" "));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(format: ExportFormat, addr: u64, bytes: &[u8]) -> String {
+        String::from_utf8(format.export("fw", addr, bytes).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_identifier() {
+        assert_eq!(identifier("boot-rom.v2"), "boot_rom_v2");
+        assert_eq!(identifier("0x1000"), "_0x1000");
+        assert_eq!(identifier(""), "_");
+    }
+
+    #[test]
+    fn test_arrays() {
+        let bytes: Vec<u8> = (0..14).collect();
+        assert_eq!(
+            export(ExportFormat::C, 0x8000, &bytes),
+            "/* 0xe bytes from 0x8000 */
+const uint8_t fw[14] = {
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b,
+    0x0c, 0x0d,
+};
+"
+        );
+        assert_eq!(
+            export(ExportFormat::Rust, 0x8000, &[0xde, 0xad]),
+            "/// 0x2 bytes from 0x8000\npub const FW: [u8; 2] = [\n    0xde, 0xad,\n];\n"
+        );
+    }
+
+    #[test]
+    fn test_intel_hex() {
+        let bytes: Vec<u8> = (0..0x14).collect();
+        assert_eq!(
+            export(ExportFormat::IntelHex, 0x0800_fff8, &bytes),
+            ":020000040800F2
+:08FFF8000001020304050607E5
+:020000040801F1
+:0C00000008090A0B0C0D0E0F1011121352
+:00000001FF
+"
+        );
+        assert!(ExportFormat::IntelHex.export("fw", 0xffff_fff0, &[0; 0x20]).is_err());
+        assert!(ExportFormat::IntelHex.export("fw", u64::MAX - 1, &[0; 4]).is_err());
+    }
+
+    #[test]
+    fn test_srecord() {
+        assert_eq!(
+            export(ExportFormat::SRecord, 0x1000, &[0x01, 0x02, 0x03]),
+            "S005000066771D\nS1061000010203E3\nS9031000EC\n"
+        );
+        let text = export(ExportFormat::SRecord, 0x0800_0000, &[0xaa; 17]);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[1], "S31508000000AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA42");
+        assert_eq!(lines[2], "S30608000010AA37");
+        assert_eq!(lines[3], "S70508000000F2");
+        // 24 bit addresses
+        let text = export(ExportFormat::SRecord, 0x20000, &[0]);
+        assert_eq!(text.lines().nth(1), Some("S20502000000F8"));
+
+        assert!(ExportFormat::SRecord.export("fw", u64::MAX - 1, &[0; 4]).is_err());
+        // the header holds at most 252 bytes of the name
+        let name = "n".repeat(300);
+        let text =
+            String::from_utf8(ExportFormat::SRecord.export(&name, 0, &[0]).unwrap()).unwrap();
+        let header = text.lines().next().unwrap();
+        assert!(header.starts_with("S0FF0000"));
+        assert_eq!(header.len(), 4 + 0xff * 2);
+    }
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(export(ExportFormat::Base64, 0, b""), "");
+        assert_eq!(export(ExportFormat::Base64, 0, b"f"), "Zg==\n");
+        assert_eq!(export(ExportFormat::Base64, 0, b"fo"), "Zm8=\n");
+        assert_eq!(export(ExportFormat::Base64, 0, b"foobar"), "Zm9vYmFy\n");
+        let text = export(ExportFormat::Base64, 0, &[0; 60]);
+        assert_eq!(text.lines().map(str::len).collect::<Vec<_>>(), [76, 4]);
+    }
+
+    #[test]
+    fn test_xxd() {
+        assert_eq!(
+            export(ExportFormat::Xxd, 0x401000, b"hello, world\n\0\x01\x02AB"),
+            "00401000: 6865 6c6c 6f2c 2077 6f72 6c64 0a00 0102  hello, world....
+00401010: 4142                                     AB
+"
+        );
+    }
+}
@@ -0,0 +1,470 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::parse_number;
+
+/// Bits `lsb..lsb + width` of a status register, a flag when it is a single bit without names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitField {
+    pub name: String,
+    pub lsb: u32,
+    pub width: u32,
+    /// Names of the values of the field, such as the modes of the cpsr
+    pub values: Vec<(u64, String)>,
+}
+
+impl BitField {
+    fn new(name: &str, lsb: u32, width: u32) -> Self {
+        Self { name: name.to_string(), lsb, width, values: vec![] }
+    }
+
+    fn with_values(name: &str, lsb: u32, width: u32, values: &[(u64, &str)]) -> Self {
+        let values = values.iter().map(|(v, n)| (*v, (*n).to_string())).collect();
+        Self { name: name.to_string(), lsb, width, values }
+    }
+
+    fn mask(&self) -> u64 {
+        if self.width >= 64 { u64::MAX } else { ((1 << self.width) - 1) << self.lsb }
+    }
+
+    fn is_flag(&self) -> bool {
+        self.width == 1 && self.values.is_empty()
+    }
+}
+
+/// Field of a register value, as the Registers pane shows it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decoded {
+    /// Flag that is set, or that was set at the previous stop
+    Flag { name: String, set: bool, changed: bool },
+    /// `name=value` of a wider field
+    Field { name: String, value: String, changed: bool },
+}
+
+/// Bit fields of a status register
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FlagsLayout {
+    pub fields: Vec<BitField>,
+}
+
+impl FlagsLayout {
+    /// Fields of `val`, with those whose bits differ from `previous` marked changed. Cleared
+    /// flags are only kept when they changed
+    pub fn decode(&self, val: u64, previous: Option<u64>) -> Vec<Decoded> {
+        let diff = previous.map_or(0, |previous| previous ^ val);
+        let mut decoded = vec![];
+        for field in &self.fields {
+            let changed = diff & field.mask() != 0;
+            let bits = (val & field.mask()) >> field.lsb;
+            if field.is_flag() {
+                if bits == 1 || changed {
+                    decoded.push(Decoded::Flag {
+                        name: field.name.clone(),
+                        set: bits == 1,
+                        changed,
+                    });
+                }
+                continue;
+            }
+            let value = match field.values.iter().find(|(v, _)| *v == bits) {
+                Some((_, name)) => name.clone(),
+                None if bits < 10 => bits.to_string(),
+                None => format!("0x{bits:x}"),
+            };
+            decoded.push(Decoded::Field { name: field.name.clone(), value, changed });
+        }
+        decoded
+    }
+}
+
+fn flags(fields: &[(&str, u32)]) -> Vec<BitField> {
+    fields.iter().map(|(name, bit)| BitField::new(name, *bit, 1)).collect()
+}
+
+fn eflags() -> FlagsLayout {
+    let mut fields = flags(&[
+        ("CF", 0),
+        ("PF", 2),
+        ("AF", 4),
+        ("ZF", 6),
+        ("SF", 7),
+        ("TF", 8),
+        ("IF", 9),
+        ("DF", 10),
+        ("OF", 11),
+        ("NT", 14),
+        ("RF", 16),
+        ("VM", 17),
+        ("AC", 18),
+        ("VIF", 19),
+        ("VIP", 20),
+        ("ID", 21),
+    ]);
+    fields.push(BitField::new("IOPL", 12, 2));
+    FlagsLayout { fields }
+}
+
+/// Program status of AArch32, the `cpsr` of a 32 bit ARM
+fn arm_cpsr() -> FlagsLayout {
+    let mut fields = flags(&[
+        ("N", 31),
+        ("Z", 30),
+        ("C", 29),
+        ("V", 28),
+        ("Q", 27),
+        ("J", 24),
+        ("E", 9),
+        ("A", 8),
+        ("I", 7),
+        ("F", 6),
+        ("T", 5),
+    ]);
+    fields.push(BitField::new("GE", 16, 4));
+    fields.push(BitField::with_values(
+        "mode",
+        0,
+        5,
+        &[
+            (0x10, "USR"),
+            (0x11, "FIQ"),
+            (0x12, "IRQ"),
+            (0x13, "SVC"),
+            (0x16, "MON"),
+            (0x17, "ABT"),
+            (0x1a, "HYP"),
+            (0x1b, "UND"),
+            (0x1f, "SYS"),
+        ],
+    ));
+    FlagsLayout { fields }
+}
+
+/// PSTATE of AArch64, which gdb names `cpsr`
+fn aarch64_cpsr() -> FlagsLayout {
+    let mut fields = flags(&[
+        ("N", 31),
+        ("Z", 30),
+        ("C", 29),
+        ("V", 28),
+        ("SS", 21),
+        ("IL", 20),
+        ("D", 9),
+        ("A", 8),
+        ("I", 7),
+        ("F", 6),
+        ("nRW", 4),
+    ]);
+    fields.push(BitField::with_values(
+        "mode",
+        0,
+        4,
+        &[
+            (0x0, "EL0t"),
+            (0x4, "EL1t"),
+            (0x5, "EL1h"),
+            (0x8, "EL2t"),
+            (0x9, "EL2h"),
+            (0xc, "EL3t"),
+            (0xd, "EL3h"),
+        ],
+    ));
+    FlagsLayout { fields }
+}
+
+fn nzcv() -> FlagsLayout {
+    FlagsLayout { fields: flags(&[("N", 31), ("Z", 30), ("C", 29), ("V", 28)]) }
+}
+
+/// Program status of the Cortex-M, with the number of the exception being handled
+fn xpsr() -> FlagsLayout {
+    let mut fields = flags(&[("N", 31), ("Z", 30), ("C", 29), ("V", 28), ("Q", 27), ("T", 24)]);
+    fields.push(BitField::new("GE", 16, 4));
+    fields.push(BitField::with_values(
+        "exception",
+        0,
+        9,
+        &[
+            (0, "Thread"),
+            (2, "NMI"),
+            (3, "HardFault"),
+            (4, "MemManage"),
+            (5, "BusFault"),
+            (6, "UsageFault"),
+            (7, "SecureFault"),
+            (11, "SVCall"),
+            (12, "DebugMonitor"),
+            (14, "PendSV"),
+            (15, "SysTick"),
+        ],
+    ));
+    FlagsLayout { fields }
+}
+
+/// Status and control of the VFP
+fn fpscr() -> FlagsLayout {
+    let mut fields = flags(&[
+        ("N", 31),
+        ("Z", 30),
+        ("C", 29),
+        ("V", 28),
+        ("QC", 27),
+        ("AHP", 26),
+        ("DN", 25),
+        ("FZ", 24),
+        ("IDC", 7),
+        ("IXC", 4),
+        ("UFC", 3),
+        ("OFC", 2),
+        ("DZC", 1),
+        ("IOC", 0),
+    ]);
+    fields.push(BitField::with_values(
+        "RMode",
+        22,
+        2,
+        &[(0, "RN"), (1, "RP"), (2, "RM"), (3, "RZ")],
+    ));
+    FlagsLayout { fields }
+}
+
+fn mstatus() -> FlagsLayout {
+    let mut fields = flags(&[
+        ("SIE", 1),
+        ("MIE", 3),
+        ("SPIE", 5),
+        ("MPIE", 7),
+        ("SPP", 8),
+        ("MPRV", 17),
+        ("SUM", 18),
+        ("MXR", 19),
+        ("TVM", 20),
+        ("TW", 21),
+        ("TSR", 22),
+    ]);
+    let context = [(0, "Off"), (1, "Initial"), (2, "Clean"), (3, "Dirty")];
+    fields.push(BitField::with_values("MPP", 11, 2, &[(0, "U"), (1, "S"), (3, "M")]));
+    fields.push(BitField::with_values("FS", 13, 2, &context));
+    fields.push(BitField::with_values("XS", 15, 2, &context));
+    FlagsLayout { fields }
+}
+
+/// Trap cause of RISC-V, interrupts have the top bit of the register set
+fn mcause(ptr_64: bool) -> FlagsLayout {
+    let xlen = if ptr_64 { 64 } else { 32 };
+    let interrupt = 1u64 << (xlen - 1);
+    let mut values: Vec<(u64, &str)> = vec![
+        (0, "instruction address misaligned"),
+        (1, "instruction access fault"),
+        (2, "illegal instruction"),
+        (3, "breakpoint"),
+        (4, "load address misaligned"),
+        (5, "load access fault"),
+        (6, "store address misaligned"),
+        (7, "store access fault"),
+        (8, "ecall from U-mode"),
+        (9, "ecall from S-mode"),
+        (11, "ecall from M-mode"),
+        (12, "instruction page fault"),
+        (13, "load page fault"),
+        (15, "store page fault"),
+    ];
+    values.extend([
+        (interrupt | 1, "supervisor software interrupt"),
+        (interrupt | 3, "machine software interrupt"),
+        (interrupt | 5, "supervisor timer interrupt"),
+        (interrupt | 7, "machine timer interrupt"),
+        (interrupt | 9, "supervisor external interrupt"),
+        (interrupt | 11, "machine external interrupt"),
+    ]);
+    FlagsLayout { fields: vec![BitField::with_values("cause", 0, xlen, &values)] }
+}
+
+/// Bit fields of the status registers known to heretek, by name. `cpsr` differs between AArch32
+/// and AArch64
+fn builtin_layouts(ptr_64: bool) -> HashMap<&'static str, FlagsLayout> {
+    HashMap::from([
+        ("eflags", eflags()),
+        ("rflags", eflags()),
+        ("cpsr", if ptr_64 { aarch64_cpsr() } else { arm_cpsr() }),
+        ("nzcv", nzcv()),
+        ("xpsr", xpsr()),
+        ("fpscr", fpscr()),
+        ("mstatus", mstatus()),
+        ("mcause", mcause(ptr_64)),
+    ])
+}
+
+static BUILTIN_LAYOUTS_32: LazyLock<HashMap<&str, FlagsLayout>> =
+    LazyLock::new(|| builtin_layouts(false));
+
+static BUILTIN_LAYOUTS_64: LazyLock<HashMap<&str, FlagsLayout>> =
+    LazyLock::new(|| builtin_layouts(true));
+
+/// Bit fields of the status register `name` known to heretek, built once
+pub fn builtin_layout(name: &str, ptr_64: bool) -> Option<&'static FlagsLayout> {
+    if ptr_64 { BUILTIN_LAYOUTS_64.get(name) } else { BUILTIN_LAYOUTS_32.get(name) }
+}
+
+static RE_TDESC_TYPE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<(flags|struct|enum)\s([^>]*)>(.*?)</(?:flags|struct|enum)>"#).unwrap()
+});
+
+static RE_TDESC_ITEM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<(field|evalue|reg)\s([^>]*?)/?>"#).unwrap());
+
+static RE_ATTR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"([\w-]+)="([^"]*)""#).unwrap());
+
+fn attrs(tag: &str) -> HashMap<&str, &str> {
+    RE_ATTR
+        .captures_iter(tag)
+        .map(|caps| (caps.get(1).unwrap().as_str(), caps.get(2).unwrap().as_str()))
+        .collect()
+}
+
+/// Bit fields of the registers of the target description XML printed by `maint print
+/// xml-tdesc`: the `<flags>` and bit field `<struct>` types, with the names of their `<enum>`
+/// fields, by the name of the `<reg>` of that type. Fields past bit 63 of the registers heretek
+/// decodes are dropped
+pub fn parse_tdesc(xml: &str) -> HashMap<String, FlagsLayout> {
+    let xml = xml.replace(r#"\""#, "\"");
+    let mut enums: HashMap<String, Vec<(u64, String)>> = HashMap::new();
+    let mut types: Vec<(String, Vec<(String, u32, u32, Option<String>)>)> = vec![];
+    for caps in RE_TDESC_TYPE.captures_iter(&xml) {
+        let Some(id) = attrs(&caps[2]).get("id").map(|id| (*id).to_string()) else {
+            continue;
+        };
+        let items = RE_TDESC_ITEM.captures_iter(&caps[3]);
+        if &caps[1] == "enum" {
+            let values = items
+                .filter_map(|item| {
+                    let attrs = attrs(item.get(2)?.as_str());
+                    Some((parse_number(attrs.get("value")?)?, (*attrs.get("name")?).to_string()))
+                })
+                .collect();
+            enums.insert(id, values);
+            continue;
+        }
+        // the fields of a struct without bits are not a register layout
+        let fields: Vec<_> = items
+            .filter_map(|item| {
+                let attrs = attrs(item.get(2)?.as_str());
+                let name = attrs.get("name").filter(|name| !name.is_empty())?;
+                let start = attrs.get("start")?.parse::<u32>().ok()?;
+                let end = attrs.get("end")?.parse::<u32>().ok()?;
+                let type_ = attrs.get("type").map(|t| (*t).to_string());
+                Some(((*name).to_string(), start, end, type_))
+            })
+            .collect();
+        if !fields.is_empty() {
+            types.push((id, fields));
+        }
+    }
+
+    let mut layouts = HashMap::new();
+    for item in RE_TDESC_ITEM.captures_iter(&xml).filter(|item| &item[1] == "reg") {
+        let attrs = attrs(&item[2]);
+        let (Some(name), Some(type_)) = (attrs.get("name"), attrs.get("type")) else {
+            continue;
+        };
+        let Some((_, fields)) = types.iter().find(|(id, _)| id == type_) else {
+            continue;
+        };
+        let fields = fields
+            .iter()
+            .filter(|(_, start, end, _)| start <= end && *end < 64)
+            .map(|(name, start, end, type_)| BitField {
+                name: name.clone(),
+                lsb: *start,
+                width: end - start + 1,
+                values: type_.as_ref().and_then(|t| enums.get(t)).cloned().unwrap_or_default(),
+            })
+            .collect();
+        layouts.insert((*name).to_string(), FlagsLayout { fields });
+    }
+    layouts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(decoded: &[Decoded]) -> Vec<String> {
+        decoded
+            .iter()
+            .map(|d| match d {
+                Decoded::Flag { name, set: true, .. } => name.clone(),
+                Decoded::Flag { name, set: false, .. } => format!("-{name}"),
+                Decoded::Field { name, value, .. } => format!("{name}={value}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_eflags() {
+        let layout = builtin_layout("eflags", true).unwrap();
+        assert_eq!(text(&layout.decode(0x246, None)), ["PF", "ZF", "IF", "IOPL=0"]);
+
+        // ZF was cleared and CF set since the previous stop
+        let decoded = layout.decode(0x207, Some(0x246));
+        assert_eq!(text(&decoded), ["CF", "PF", "-ZF", "IF", "IOPL=0"]);
+        assert_eq!(decoded[0], Decoded::Flag { name: "CF".to_string(), set: true, changed: true });
+        assert_eq!(decoded[1], Decoded::Flag { name: "PF".to_string(), set: true, changed: false });
+    }
+
+    #[test]
+    fn test_decode_builtin() {
+        let cpsr = builtin_layout("cpsr", false).unwrap();
+        assert_eq!(text(&cpsr.decode(0x6000_0193, None)), ["Z", "C", "A", "I", "GE=0", "mode=SVC"]);
+        let pstate = builtin_layout("cpsr", true).unwrap();
+        assert_eq!(text(&pstate.decode(0x8000_03c5, None)), ["N", "D", "A", "I", "F", "mode=EL1h"]);
+        let xpsr = builtin_layout("xpsr", false).unwrap();
+        assert_eq!(text(&xpsr.decode(0x0100_0003, None)), ["T", "GE=0", "exception=HardFault"]);
+        assert_eq!(text(&xpsr.decode(0x0100_0020, None)), ["T", "GE=0", "exception=0x20"]);
+
+        let mcause = builtin_layout("mcause", true).unwrap();
+        assert_eq!(text(&mcause.decode(5, None)), ["cause=load access fault"]);
+        let timer = 0x8000_0000_0000_0007;
+        assert_eq!(text(&mcause.decode(timer, None)), ["cause=machine timer interrupt"]);
+        let mstatus = builtin_layout("mstatus", false).unwrap();
+        assert_eq!(
+            text(&mstatus.decode(0x1888, None)),
+            ["MIE", "MPIE", "MPP=M", "FS=Off", "XS=Off"]
+        );
+        assert!(builtin_layout("rax", true).is_none());
+    }
+
+    #[test]
+    fn test_parse_tdesc() {
+        let xml = r#"<?xml version="1.0"?>
+<!DOCTYPE target SYSTEM "gdb-target.dtd">
+<target>
+  <architecture>riscv:rv32</architecture>
+  <feature name="org.gnu.gdb.riscv.csr">
+    <enum id="priv" size="4">
+      <evalue name="U" value="0"/>
+      <evalue name="M" value="3"/>
+    </enum>
+    <flags id=\"status\" size=\"4\">
+      <field name=\"\" start=\"23\" end=\"30\"/>
+      <field name=\"MIE\" start=\"3\" end=\"3\"/>
+      <field name=\"MPP\" start=\"11\" end=\"12\" type=\"priv\"/>
+      <field name=\"WIDE\" start=\"60\" end=\"64\"/>
+      <field name=\"HIGH\" start=\"64\" end=\"64\"/>
+    </flags>
+    <struct id="pair" size="8">
+      <field name="a" type="uint32"/>
+    </struct>
+    <reg name="mstatus" bitsize="32" regnum="833" type="status"/>
+    <reg name="pc" bitsize="32" type="code_ptr"/>
+  </feature>
+</target>
+"#;
+        let layouts = parse_tdesc(xml);
+        assert_eq!(layouts.len(), 1);
+        let layout = &layouts["mstatus"];
+        assert_eq!(layout.fields.len(), 2);
+        assert_eq!(text(&layout.decode(0x1808, None)), ["MIE", "MPP=M"]);
+    }
+}
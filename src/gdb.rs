@@ -8,9 +8,17 @@ use stream_output::stream_output;
 mod exec_result;
 use exec_result::exec_result;
 
+mod notify;
+use notify::notify;
+
 use log::{debug, trace, warn};
 
-use crate::mi::{MIResponse, data_read_sp_bytes, parse_key_value_pairs, parse_mi_response};
+use crate::mi::{
+    Frame, MIResponse, break_list, data_read_sp_bytes, parse_frame, parse_key_value_pairs,
+    parse_mi_response, parse_watchpoint_hit, stack_list_arguments, stack_list_frames,
+    stack_list_variables, thread_info, var_delete, var_update,
+};
+use crate::stack::Growth;
 use crate::{PtrSize, State, Written};
 
 pub fn gdb_interact(gdb_stdout: BufReader<Box<dyn Read + Send>>, state: Arc<Mutex<State>>) {
@@ -26,6 +34,8 @@ pub fn gdb_interact(gdb_stdout: BufReader<Box<dyn Read + Send>>, state: Arc<Mute
             MIResponse::AsyncRecord(reason, kv) => {
                 if reason == "stopped" {
                     async_record_stopped(&mut state, kv);
+                } else if reason == "running" {
+                    async_record_running(&mut state, kv);
                 }
             }
             MIResponse::ExecResult(status, kv) => {
@@ -37,7 +47,22 @@ pub fn gdb_interact(gdb_stdout: BufReader<Box<dyn Read + Send>>, state: Arc<Mute
             MIResponse::Unknown(s) => {
                 unknown(&mut state, s);
             }
-            MIResponse::Notify(..) => (),
+            MIResponse::Notify(event, kv) => {
+                notify(&mut state, event, kv);
+            }
+        }
+    }
+}
+
+/// Mark the threads that resumed, "all" in all-stop mode
+fn async_record_running(state: &mut State, kv: &HashMap<String, String>) {
+    let Some(id) = kv.get("thread-id") else {
+        return;
+    };
+    for thread in &mut state.threads {
+        if id == "all" || &thread.id == id {
+            thread.state = "running".to_string();
+            thread.frame = None;
         }
     }
 }
@@ -71,8 +96,14 @@ fn async_record_stopped(state: &mut State, kv: &HashMap<String, String>) {
     if let Some(val) = kv.get("thread-id") {
         state.async_result.push_str(&format!(", thread-id={val}"));
     }
+    // The stopping thread becomes the selected thread, along with its inferior
+    if let Some(id) = kv.get("thread-id") {
+        state.current_thread_id = Some(id.clone());
+        state.follow_thread_inferior();
+    }
+
     // query the size of the arch
-    if state.ptr_size == PtrSize::Auto {
+    if state.view.ptr_size == PtrSize::Auto {
         // sizeof ptr in arch
         state.next_write.push("-data-evaluate-expression \"sizeof(long)\"".to_string());
         state.written.push_back(Written::SizeOfVoidStar);
@@ -87,68 +118,126 @@ fn async_record_stopped(state: &mut State, kv: &HashMap<String, String>) {
     // TODO: We only need to do this once
     // Get source language
     state.next_write.push(r#"-interpreter-exec console "show language""#.to_string());
+
+    state.next_write.push(thread_info());
+
+    // Nothing to read once the inferior is gone
+    if !kv.get("reason").is_some_and(|r| r.starts_with("exited")) {
+        state.refresh_hexdump();
+        state.refresh_memory_diff();
+    }
+
+    // bare-metal targets tell where their stack is with linker symbols
+    if !state.view.stack_model.symbols_read {
+        state.request_stack_symbols();
+    }
+
+    if let Some(hit) = parse_watchpoint_hit(kv) {
+        debug!("watchpoint hit: {hit:?}");
+        state.watchpoint_hits.push(hit);
+        // refresh the hit counts
+        state.next_write.push(break_list());
+    } else if kv.get("reason").is_some_and(|r| r == "watchpoint-scope") {
+        // gdb deleted the watchpoint once its frame was left
+        state.next_write.push(break_list());
+    }
+
+    // Extract source location directly from the stopped event
+    let frame = kv.get("frame").map(|f| parse_frame(f)).unwrap_or_default();
+    refresh_frame(state, &frame);
+}
+
+/// Query the registers, stack, asm and backtrace of the selected thread, and show the
+/// source of its `frame`. Used after a stop, and after selecting another thread
+pub fn refresh_frame(state: &mut State, frame: &Frame) {
+    state.follow_thread_inferior();
+    // Innermost frame after a stop, but `up`/`frame N` from the cli also report a frame
+    state.bt_selected = frame.level as usize;
+    state.bt_cursor = state.bt_selected;
+    read_selected_frame(state, frame);
+
+    // bt
+    state.next_write.push(stack_list_frames());
+    state.next_write.push(stack_list_arguments());
+}
+
+/// Query the registers, stack, asm and locals of the selected `frame`, and show its source
+pub fn read_selected_frame(state: &mut State, frame: &Frame) {
+    // Stack slots of the previously selected thread or frame would otherwise linger
+    state.view.stack.clear();
+    state.view.smashed.clear();
+    state.stack_cfa = None;
+    state.stack_goto = None;
+
     state.next_write.push("-data-list-register-names".to_string());
     // When a breakpoint is hit, query for register values
     state.next_write.push("-data-list-register-values x".to_string());
     // get a list of changed registers
     state.next_write.push("-data-list-changed-registers".to_string());
-    // bt
-    state.next_write.push("-stack-list-frames".to_string());
 
-    // Extract source location directly from the stopped event
-    if let (Some(fullname), Some(line)) = (kv.get("fullname"), kv.get("line")) {
-        debug!("Source location from stopped event: {fullname}:{line}");
-
-        if let Ok(line_num) = line.parse::<u32>() {
-            let file_changed = state.current_source_file.as_ref() != Some(fullname);
-
-            state.current_source_file = Some(fullname.clone());
-            state.current_source_line = Some(line_num);
-
-            // Try to read the source file and store lines
-            if let Ok(content) = std::fs::read_to_string(std::path::Path::new(fullname)) {
-                state.source_lines =
-                    content.lines().map(std::string::ToString::to_string).collect();
-                debug!("Read {} lines from source file", state.source_lines.len());
-            } else {
-                warn!("Could not read source file: {fullname}");
-                state.source_lines.clear();
-            }
+    // A new stop moves the pane cursors back to the current location
+    state.asm_cursor = None;
 
-            if file_changed {
-                state.next_write.push(r#"-interpreter-exec console "show language""#.to_string());
-            }
-        }
-    } else if let (Some(file), Some(line)) = (kv.get("file"), kv.get("line")) {
-        // Fallback to 'file' if 'fullname' is not available
-        debug!("Source location from stopped event (fallback): {file}:{line}");
-
-        if let Ok(line_num) = line.parse::<u32>() {
-            let file_changed = state.current_source_file.as_ref() != Some(file);
-
-            state.current_source_file = Some(file.clone());
-            state.current_source_line = Some(line_num);
-
-            // Try to read the source file and store lines
-            if let Ok(content) = std::fs::read_to_string(std::path::Path::new(file)) {
-                state.source_lines =
-                    content.lines().map(std::string::ToString::to_string).collect();
-                debug!("Read {} lines from source file", state.source_lines.len());
-            } else {
-                warn!("Could not read source file: {file}");
-                state.source_lines.clear();
-            }
+    refresh_var_objects(state, frame);
 
-            if file_changed {
-                state.next_write.push(r#"-interpreter-exec console "show language""#.to_string());
-            }
-        }
+    let (Some(path), Some(line_num)) =
+        (frame.fullname.as_ref().or(frame.file.as_ref()), frame.line)
+    else {
+        debug!("No source location information in frame");
+        state.view.current_source_file = None;
+        state.view.current_source_line = None;
+        state.view.source_lines.clear();
+        return;
+    };
+    debug!("Source location from frame: {path}:{line_num}");
+
+    let file_changed = state.view.current_source_file.as_ref() != Some(path);
+
+    state.view.current_source_file = Some(path.clone());
+    state.view.current_source_line = Some(line_num);
+    state.source_cursor = (line_num as usize).saturating_sub(1);
+
+    // Try to read the source file and store lines
+    if let Ok(content) = std::fs::read_to_string(std::path::Path::new(path)) {
+        state.view.source_lines = content.lines().map(std::string::ToString::to_string).collect();
+        debug!("Read {} lines from source file", state.view.source_lines.len());
     } else {
-        debug!("No source location information in stopped event");
-        state.current_source_file = None;
-        state.current_source_line = None;
-        state.source_lines.clear();
+        warn!("Could not read source file: {path}");
+        state.view.source_lines.clear();
+    }
+
+    if file_changed {
+        state.next_write.push(r#"-interpreter-exec console "show language""#.to_string());
+    }
+}
+
+/// Variable objects of the locals are bound to the frame they were created in. Keep them while
+/// the same frame stays selected, so `-var-update` can report what changed since the last stop.
+/// The expressions are floating, and follow the selected frame
+fn refresh_var_objects(state: &mut State, frame: &Frame) {
+    let key = format!(
+        "{}/{}/{}",
+        state.current_thread_id.as_deref().unwrap_or_default(),
+        frame.level,
+        frame.func.as_deref().unwrap_or_default()
+    );
+    if state.view.locals_frame.as_ref() != Some(&key) {
+        for local in state.view.locals.iter().filter(|l| l.depth == 0) {
+            state.next_write.push(var_delete(&local.var.name));
+        }
+        state.view.locals.clear();
+        state.locals_selected = 0;
+        state.view.locals_frame = Some(key);
+    }
+
+    let created = state.view.expressions.iter().any(|e| e.resolved.is_some() && e.error.is_none());
+    if !state.view.locals.is_empty() || created {
+        state.next_write.push(var_update());
+    }
+    for index in 0..state.view.expressions.len() {
+        state.create_expression(index);
     }
+    state.next_write.push(stack_list_variables());
 }
 
 fn read_memory(memory: &String) -> (HashMap<String, String>, String) {
@@ -160,10 +249,14 @@ fn read_memory(memory: &String) -> (HashMap<String, String>, String) {
     (data, begin)
 }
 
-fn dump_sp_bytes(state: &mut State, size: u64, amt: u64) {
-    let mut curr_offset = 0;
+/// Request `amt` slots of `size` bytes from the stack pointer of the stack model, after the
+/// `first` ones
+pub fn dump_sp_bytes(state: &mut State, size: u64, first: u64, amt: u64) {
+    let mut curr_offset = first * size;
+    let up = state.view.stack_model.growth == Growth::Up;
     for _ in 0..amt {
-        state.next_write.push(data_read_sp_bytes(curr_offset, size));
+        let cmd = data_read_sp_bytes(&state.view.stack_model.sp, up, curr_offset, size);
+        state.next_write.push(cmd);
         state.written.push_back(Written::Stack(None));
         curr_offset += size;
     }
@@ -211,6 +304,98 @@ mod tests {
         State::new(args)
     }
 
+    #[test]
+    fn test_stopped_watchpoint_trigger() {
+        let mut state = create_test_state();
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(
+            r#"*stopped,reason="watchpoint-trigger",wpt={number="2",exp="counter"},value={old="1",new="2"},frame={addr="0x0000000000401136",func="bump",args=[],file="test.c",fullname="/nonexistent/test.c",line="5"},thread-id="1",stopped-threads="all""#,
+        ) else {
+            panic!("Expected AsyncRecord");
+        };
+        async_record_stopped(&mut state, &kv);
+
+        assert_eq!(state.watchpoint_hits.len(), 1);
+        assert_eq!(state.watchpoint_hits[0].old.as_deref(), Some("1"));
+        assert_eq!(state.watchpoint_hits[0].new.as_deref(), Some("2"));
+        assert_eq!(state.watchpoint_hits[0].func.as_deref(), Some("bump"));
+        assert!(state.next_write.contains(&break_list()));
+        // the source location is read from the frame tuple
+        assert_eq!(state.view.current_source_line, Some(5));
+        assert_eq!(state.source_cursor, 4);
+    }
+
+    #[test]
+    fn test_stopped_refreshes_hexdump() {
+        let mut state = create_test_state();
+        state.view.hexdump = Some(crate::memory::PagedMemory::from_bytes(0x1000, &[0; 16]));
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(
+            r#"*stopped,reason="end-stepping-range",frame={addr="0x0000000000401136",func="main",args=[]},thread-id="1",stopped-threads="all""#,
+        ) else {
+            panic!("Expected AsyncRecord");
+        };
+        async_record_stopped(&mut state, &kv);
+        assert!(state.next_write.contains(&"-data-read-memory-bytes 0x1000+0x00 16".to_string()));
+        assert!(matches!(state.written.front(), Some(Written::MemoryRefresh(0x1000))));
+
+        let mut state = create_test_state();
+        state.view.hexdump = Some(crate::memory::PagedMemory::from_bytes(0x1000, &[0; 16]));
+        let MIResponse::AsyncRecord(_, kv) =
+            parse_mi_response(r#"*stopped,reason="exited-normally""#)
+        else {
+            panic!("Expected AsyncRecord");
+        };
+        async_record_stopped(&mut state, &kv);
+        assert!(!state.written.iter().any(|w| matches!(w, Written::MemoryRefresh(0x1000))));
+    }
+
+    #[test]
+    fn test_running_marks_threads() {
+        let mut state = create_test_state();
+        state.threads = crate::mi::parse_threads(
+            r#"[{id="1",target-id="process 1",frame={level="0",addr="0x0000000000401136"},state="stopped"},{id="2",target-id="process 2",frame={level="0",addr="0x0000000000401150"},state="stopped"}]"#,
+        );
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(r#"*running,thread-id="2""#) else {
+            panic!("Expected AsyncRecord");
+        };
+        async_record_running(&mut state, &kv);
+        assert_eq!(state.threads[0].state, "stopped");
+        assert_eq!(state.threads[1].state, "running");
+        assert_eq!(state.threads[1].frame, None);
+
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(r#"*running,thread-id="all""#)
+        else {
+            panic!("Expected AsyncRecord");
+        };
+        async_record_running(&mut state, &kv);
+        assert!(state.threads.iter().all(|t| t.state == "running"));
+    }
+
+    #[test]
+    fn test_locals_kept_within_frame() {
+        let mut state = create_test_state();
+        let main = parse_frame(r#"{level="0",addr="0x0000000000401136",func="main"}"#);
+        read_selected_frame(&mut state, &main);
+        assert!(state.next_write.contains(&"-stack-list-variables --simple-values".to_string()));
+        state.view.locals.push(crate::Local {
+            var: crate::mi::VarObj { name: "local0".to_string(), ..Default::default() },
+            ..Default::default()
+        });
+
+        // stepping within main updates the same variable objects
+        state.next_write.clear();
+        let main = parse_frame(r#"{level="0",addr="0x000000000040113a",func="main"}"#);
+        read_selected_frame(&mut state, &main);
+        assert!(state.next_write.contains(&"-var-update --all-values *".to_string()));
+        assert_eq!(state.view.locals.len(), 1);
+
+        // another frame deletes them
+        state.next_write.clear();
+        let add = parse_frame(r#"{level="0",addr="0x0000000000401100",func="add"}"#);
+        read_selected_frame(&mut state, &add);
+        assert!(state.next_write.contains(&"-var-delete local0".to_string()));
+        assert!(state.view.locals.is_empty());
+    }
+
     #[test]
     fn test_unknown_prompt() {
         let mut state = create_test_state();
@@ -237,4 +422,26 @@ mod tests {
         assert_eq!(state.output.len(), 0);
         assert_eq!(state.stream_output_prompt, "(gdb) ");
     }
+
+    #[test]
+    fn test_stopped_source_location_from_frame() {
+        let mut state = create_test_state();
+        let line = r#"*stopped,reason="end-stepping-range",frame={addr="0x0000000000401136",func="main",args=[],file="test.c",fullname="/nonexistent/test.c",line="7",arch="i386:x86-64"},thread-id="1",stopped-threads="all",core="0""#;
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(line) else {
+            panic!("Expected AsyncRecord");
+        };
+        async_record_stopped(&mut state, &kv);
+        assert_eq!(state.view.current_source_file.as_deref(), Some("/nonexistent/test.c"));
+        assert_eq!(state.view.current_source_line, Some(7));
+
+        // without a frame there is no source location
+        let MIResponse::AsyncRecord(_, kv) =
+            parse_mi_response(r#"*stopped,reason="signal-received",signal-name="SIGINT""#)
+        else {
+            panic!("Expected AsyncRecord");
+        };
+        async_record_stopped(&mut state, &kv);
+        assert_eq!(state.view.current_source_file, None);
+        assert_eq!(state.view.current_source_line, None);
+    }
 }
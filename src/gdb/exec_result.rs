@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 
 use recv::asm_insns::recv_exec_result_asm_insns;
-use recv::result_memory::recv_exec_result_memory;
+use recv::result_memory::{
+    recv_exec_error_find_chunk, recv_exec_error_load_chunk, recv_exec_error_load_preview,
+    recv_exec_error_memory_page, recv_exec_error_memory_write, recv_exec_error_snapshot_page,
+    recv_exec_result_memory,
+};
 
-use crate::State;
 use crate::mi::Mapping;
+use crate::{State, Written};
 
 mod running;
 use running::exec_result_running;
@@ -13,10 +17,22 @@ mod done;
 use done::exec_result_done;
 
 mod recv;
+use recv::breakpoints::{recv_exec_result_bkpt, recv_exec_result_breakpoint_table};
 use recv::changed_registers::recv_exec_result_changed_registers;
+use recv::frame_cfa::recv_exec_error_frame_cfa;
+use recv::frame_layout::recv_exec_result_frame_locals;
 use recv::register_names::recv_exec_result_register_names;
 use recv::register_values::recv_exec_results_register_values;
-use recv::value::recv_exec_result_value;
+use recv::target_description::recv_exec_error_target_description;
+use recv::threads::{recv_exec_result_new_thread_id, recv_exec_result_threads};
+use recv::type_layout::recv_exec_error_type_layout;
+use recv::value::{
+    recv_exec_error_register_assign, recv_exec_error_stack_canary, recv_exec_result_value,
+};
+use recv::var_objects::{
+    recv_exec_error_expression, recv_exec_result_changelist, recv_exec_result_var_children,
+    recv_exec_result_var_create, recv_exec_result_variables,
+};
 
 pub fn exec_result(
     state: &mut State,
@@ -33,22 +49,80 @@ pub fn exec_result(
     } else if status == "error" {
         // assume this is from us, pop off an unexpected
         // if we can
-        let _removed = state.written.pop_front();
+        let removed = state.written.pop_front();
         // trace!("ERROR: {:02x?}", removed);
+        match removed {
+            Some(Written::ExpressionCreate(name)) => {
+                recv_exec_error_expression(state, &name, kv.get("msg"));
+            }
+            Some(Written::MemoryWrite(addr)) => {
+                recv_exec_error_memory_write(state, addr, kv.get("msg"));
+            }
+            Some(Written::Memory(addr) | Written::MemoryRefresh(addr)) => {
+                recv_exec_error_memory_page(state, addr);
+            }
+            Some(Written::FindChunk(id)) => {
+                recv_exec_error_find_chunk(state, id);
+            }
+            Some(Written::LoadPreview) => {
+                recv_exec_error_load_preview(state);
+            }
+            Some(Written::LoadChunk(_)) => {
+                recv_exec_error_load_chunk(state, kv.get("msg"));
+            }
+            Some(Written::TypeLayout(overlay)) => {
+                recv_exec_error_type_layout(state, &overlay.name, kv.get("msg"));
+            }
+            Some(Written::FrameCfa(level)) => {
+                recv_exec_error_frame_cfa(state, level, kv.get("msg"));
+            }
+            Some(Written::RegisterAssign(name)) => {
+                recv_exec_error_register_assign(state, &name, kv.get("msg"));
+            }
+            Some(Written::StackCanary) => {
+                recv_exec_error_stack_canary(state, kv.get("msg"));
+            }
+            Some(Written::TargetDescription) => {
+                recv_exec_error_target_description(state, kv.get("msg"));
+            }
+            Some(Written::SnapshotPage(name, addr)) => {
+                recv_exec_error_snapshot_page(state, name.as_deref(), addr);
+            }
+            _ => (),
+        }
     }
 
     // Parse the key-value pairs
-    if let Some(value) = kv.get("value") {
+    if let Some(children) = kv.get("children") {
+        recv_exec_result_var_children(state, children);
+    } else if kv.contains_key("name") && kv.contains_key("numchild") {
+        // `-var-create` also reports a "value"
+        recv_exec_result_var_create(state, kv);
+    } else if let Some(value) = kv.get("value") {
         recv_exec_result_value(state, value);
     } else if let Some(register_names) = kv.get("register-names") {
-        recv_exec_result_register_names(register_names, &mut state.register_names);
+        recv_exec_result_register_names(register_names, &mut state.view.register_names);
     } else if let Some(changed_registers) = kv.get("changed-registers") {
-        recv_exec_result_changed_registers(changed_registers, &mut state.register_changed);
+        recv_exec_result_changed_registers(changed_registers, &mut state.view.register_changed);
     } else if let Some(register_values) = kv.get("register-values") {
         recv_exec_results_register_values(register_values, state);
     } else if let Some(memory) = kv.get("memory") {
         recv_exec_result_memory(state, memory);
     } else if let Some(asm) = kv.get("asm_insns") {
         recv_exec_result_asm_insns(state, asm);
+    } else if let Some(bkpt) = kv.get("bkpt") {
+        recv_exec_result_bkpt(state, bkpt);
+    } else if let Some(table) = kv.get("BreakpointTable") {
+        recv_exec_result_breakpoint_table(state, table);
+    } else if let Some(threads) = kv.get("threads") {
+        recv_exec_result_threads(state, threads, kv.get("current-thread-id"));
+    } else if let Some(id) = kv.get("new-thread-id") {
+        recv_exec_result_new_thread_id(state, id, kv);
+    } else if let Some(locals) = kv.get("locals") {
+        recv_exec_result_frame_locals(state, locals);
+    } else if let Some(variables) = kv.get("variables") {
+        recv_exec_result_variables(state, variables);
+    } else if let Some(changelist) = kv.get("changelist") {
+        recv_exec_result_changelist(state, changelist);
     }
 }
@@ -2,12 +2,16 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 use crate::mi::{
-    Mapping, match_inner_items, parse_key_value_pairs, parse_memory_mappings_new,
-    parse_memory_mappings_old,
+    Mapping, match_inner_items, parse_frame, parse_key_value_pairs, parse_memory_mappings_new,
+    parse_memory_mappings_old, parse_stack_args,
 };
-use crate::{Bt, State};
+use crate::{Bt, State, Written};
 
+use super::recv::frame_cfa::recv_exec_result_frame_cfa;
+use super::recv::frame_layout::{recv_exec_result_frame_info, recv_exec_result_frame_vars};
 use super::recv::symbols::recv_exec_result_symbols;
+use super::recv::target_description::recv_exec_result_target_description;
+use super::recv::type_layout::recv_exec_result_type_layout;
 
 pub fn exec_result_done(
     state: &mut State,
@@ -20,24 +24,38 @@ pub fn exec_result_done(
     exec_result_done_memory_map(state, current_map);
     exec_result_done_symbols(state, current_symbols);
 
+    // result from -data-write-memory-bytes, a failed write is an ^error instead
+    if kv.is_empty() && matches!(state.written.front(), Some(Written::MemoryWrite(_))) {
+        state.written.pop_front();
+    }
+    if kv.is_empty()
+        && let Some(Written::LoadChunk(len)) = state.written.front()
+    {
+        let len = *len;
+        state.written.pop_front();
+        if let Some(written) =
+            state.view.memory_load.as_mut().and_then(|load| load.written.as_mut())
+        {
+            *written += len;
+        }
+        state.write_memory_load_chunk();
+    }
+
     // result from -stack-list-frames
     // ^done,stack=[frame={level="0",addr="0x0000555555804a50",func="main",arch="i386:x86-64"},frame={level="1",addr="0x00007ffff7ca1488",func="??",from="/usr/lib/libc.so.6",arch="i386:x86-64"},frame={level="2",addr="0x00007ffff7ca154c",func="__libc_start_main",from="/usr/lib/libc.so.6",arch="i386:x86-64"},frame={level="3",addr="0x00005555557bdcc5",func="_start",arch="i386:x86-64"}]
     if kv.contains_key("stack") {
-        state.bt.clear();
+        state.view.bt.clear();
         for capture in match_inner_items(kv.get("stack").unwrap()) {
-            let cap_str = &capture[0];
-            let cap_str = &cap_str[1..cap_str.len() - 1].to_string();
-            let key_values = parse_key_value_pairs(cap_str);
-            let mut bt = Bt::default();
-            for (key, val) in key_values {
-                if key == "addr" {
-                    let val = val.strip_prefix("0x").unwrap();
-                    bt.location = u64::from_str_radix(val, 16).unwrap();
-                } else if key == "func" {
-                    bt.function = Some(val);
-                }
+            state.view.bt.push(Bt { frame: parse_frame(&capture[0]), args: vec![] });
+        }
+        state.request_frame_layouts();
+    } else if let Some(stack_args) = kv.get("stack-args") {
+        // result from -stack-list-arguments
+        // ^done,stack-args=[frame={level="0",args=[{name="argc",type="int",value="1"}]}]
+        for (level, args) in parse_stack_args(stack_args) {
+            if let Some(bt) = state.view.bt.get_mut(level as usize) {
+                bt.args = args;
             }
-            state.bt.push(bt);
         }
     } else if kv.contains_key("matches") {
         state.completions.clear();
@@ -61,21 +79,37 @@ fn exec_result_done_memory_map(state: &mut State, current_map: &mut (Option<Mapp
             Mapping::Old => parse_memory_mappings_old(&current_map.1),
             Mapping::New => parse_memory_mappings_new(&current_map.1),
         };
-        state.memory_map = Some(m);
+        state.view.memory_map = Some(m);
         *current_map = (None, String::new());
+        state.update_smashed_stack_slots();
 
         // If we haven't resolved a filepath yet, assume the 1st
         // filepath in the mapping is the main text file
-        if state.filepath.is_none() {
-            state.filepath = Some(PathBuf::from(
-                state.memory_map.as_ref().unwrap()[0].path.clone().unwrap_or_default(),
+        if state.view.filepath.is_none() {
+            state.view.filepath = Some(PathBuf::from(
+                state.view.memory_map.as_ref().unwrap()[0].path.clone().unwrap_or_default(),
             ));
         }
     }
 }
 
 fn exec_result_done_symbols(state: &mut State, current_symbols: &mut String) {
-    if !current_symbols.is_empty() {
+    if let Some(Written::TypeLayout(_)) = state.written.front() {
+        recv_exec_result_type_layout(state, current_symbols);
+        current_symbols.clear();
+    } else if let Some(Written::FrameCfa(_)) = state.written.front() {
+        recv_exec_result_frame_cfa(state, current_symbols);
+        current_symbols.clear();
+    } else if let Some(Written::FrameInfo(_)) = state.written.front() {
+        recv_exec_result_frame_info(state, current_symbols);
+        current_symbols.clear();
+    } else if let Some(Written::FrameVars(..)) = state.written.front() {
+        recv_exec_result_frame_vars(state, current_symbols);
+        current_symbols.clear();
+    } else if let Some(Written::TargetDescription) = state.written.front() {
+        recv_exec_result_target_description(state, current_symbols);
+        current_symbols.clear();
+    } else if !current_symbols.is_empty() {
         recv_exec_result_symbols(state, current_symbols);
         current_symbols.clear();
     }
@@ -110,11 +144,24 @@ mod tests {
 
         exec_result_done(&mut state, &kv, &mut current_map, &mut current_symbols);
 
-        assert_eq!(state.bt.len(), 2);
-        assert_eq!(state.bt[0].location, 0x0000555555804a50);
-        assert_eq!(state.bt[0].function, Some("main".to_string()));
-        assert_eq!(state.bt[1].location, 0x00007ffff7ca1488);
-        assert_eq!(state.bt[1].function, Some("??".to_string()));
+        assert_eq!(state.view.bt.len(), 2);
+        assert_eq!(state.view.bt[0].frame.addr, 0x0000555555804a50);
+        assert_eq!(state.view.bt[0].frame.func, Some("main".to_string()));
+        assert_eq!(state.view.bt[1].frame.addr, 0x00007ffff7ca1488);
+        assert_eq!(state.view.bt[1].frame.func, Some("??".to_string()));
+        assert_eq!(state.view.bt[1].frame.level, 1);
+
+        // arguments arrive afterwards and are matched up by level
+        let mut kv = HashMap::new();
+        kv.insert(
+            "stack-args".to_string(),
+            r#"[frame={level="0",args=[{name="argc",type="int",value="1"}]},frame={level="1",args=[]}]"#
+                .to_string(),
+        );
+        exec_result_done(&mut state, &kv, &mut current_map, &mut current_symbols);
+        assert_eq!(state.view.bt[0].args.len(), 1);
+        assert_eq!(state.view.bt[0].args[0].name, "argc");
+        assert!(state.view.bt[1].args.is_empty());
     }
 
     #[test]
@@ -145,8 +192,8 @@ mod tests {
 
         exec_result_done(&mut state, &kv, &mut current_map, &mut current_symbols);
 
-        assert!(state.memory_map.is_some());
-        assert_eq!(state.filepath, Some(PathBuf::from("/path/to/binary")));
+        assert!(state.view.memory_map.is_some());
+        assert_eq!(state.view.filepath, Some(PathBuf::from("/path/to/binary")));
         assert_eq!(current_map.0, None);
         assert_eq!(current_map.1, "");
     }
@@ -163,8 +210,8 @@ mod tests {
 
         exec_result_done(&mut state, &kv, &mut current_map, &mut current_symbols);
 
-        assert!(state.memory_map.is_some());
-        assert_eq!(state.filepath, Some(PathBuf::from("/path/to/binary")));
+        assert!(state.view.memory_map.is_some());
+        assert_eq!(state.view.filepath, Some(PathBuf::from("/path/to/binary")));
         assert_eq!(current_map.0, None);
         assert_eq!(current_map.1, "");
     }
@@ -195,4 +242,32 @@ mod tests {
         assert_eq!(state.symbols.len(), 0);
         assert_eq!(current_symbols, "");
     }
+
+    #[test]
+    fn test_exec_result_done_memory_load() {
+        let path = std::env::temp_dir().join("heretek_test_memory_load.bin");
+        std::fs::write(&path, vec![0x41; crate::memory::LOAD_CHUNK + 2]).unwrap();
+        let mut state = create_test_state();
+        let input = format!("{} (0x1000 + 0x10)", path.display());
+        state.prepare_memory_load(&input).unwrap();
+        assert_eq!(state.next_write, vec!["-data-read-memory-bytes 0x1010+0x00 128"]);
+        state.next_write.clear();
+        state.written.clear();
+
+        state.start_memory_load();
+        let kv = HashMap::new();
+        let mut current_map = (None, String::new());
+        let mut current_symbols = String::new();
+        exec_result_done(&mut state, &kv, &mut current_map, &mut current_symbols);
+        assert_eq!(
+            state.view.memory_load.as_ref().unwrap().written,
+            Some(crate::memory::LOAD_CHUNK)
+        );
+        assert_eq!(state.next_write[1], "-data-write-memory-bytes 0x2010 4141");
+        exec_result_done(&mut state, &kv, &mut current_map, &mut current_symbols);
+        assert!(state.view.memory_load.is_none());
+        assert!(state.written.is_empty());
+        assert!(state.output.last().unwrap().starts_with("h> loaded 0x1002 bytes of "));
+        std::fs::remove_file(path).unwrap();
+    }
 }
@@ -10,14 +10,14 @@ pub fn recv_exec_result_asm_insns(state: &mut State, asm: &String) {
     let last_written = state.written.pop_front().unwrap();
     // TODO: change to match
     if let Written::AsmAtPc = last_written {
-        state.asm = parse_asm_insns_values(asm).clone();
+        state.view.asm = parse_asm_insns_values(asm).clone();
     }
     if let Written::SymbolDisassembly(name) = &last_written {
         state.symbol_asm = parse_asm_insns_values(asm).clone();
         state.symbol_asm_name = name.clone();
     }
     if let Written::SymbolAtAddrRegister((base_reg, _n)) = &last_written {
-        for RegisterStorage { name: _, register, deref } in &mut state.registers {
+        for RegisterStorage { name: _, register, deref } in &mut state.view.registers {
             if let Some(reg) = register
                 && reg.number == *base_reg
             {
@@ -37,9 +37,27 @@ pub fn recv_exec_result_asm_insns(state: &mut State, asm: &String) {
             }
         }
     }
+    if let Written::SymbolAtAddrTelescope = last_written
+        && let Some((_, deref)) = state.view.inspector_telescope.as_mut()
+    {
+        let new_asms = parse_asm_insns_values(asm);
+        if !new_asms.is_empty() {
+            if let Some(func_name) = &new_asms[0].func_name {
+                deref.final_assembly = format!(
+                    "{}+{} ({})",
+                    func_name.to_owned(),
+                    new_asms[0].offset,
+                    new_asms[0].inst
+                );
+            } else {
+                deref.final_assembly = new_asms[0].inst.clone();
+            }
+        }
+        return;
+    }
     if let Written::SymbolAtAddrStack(deref) = last_written {
         let key = u64::from_str_radix(&deref, 16).unwrap();
-        if let Some(deref) = state.stack.get_mut(&key) {
+        if let Some(deref) = state.view.stack.get_mut(&key) {
             let new_asms = parse_asm_insns_values(asm);
             if !new_asms.is_empty() {
                 // Try and show func_name, otherwise asm
@@ -83,7 +101,7 @@ mod tests {
 
         recv_exec_result_asm_insns(&mut state, &asm);
 
-        assert!(state.asm.is_empty());
+        assert!(state.view.asm.is_empty());
     }
 
     #[test]
@@ -95,9 +113,9 @@ mod tests {
 
         recv_exec_result_asm_insns(&mut state, &asm);
 
-        assert_eq!(state.asm.len(), 2);
-        assert_eq!(state.asm[0].address, 0x401000);
-        assert_eq!(state.asm[0].inst, "push rbp");
+        assert_eq!(state.view.asm.len(), 2);
+        assert_eq!(state.view.asm[0].address, 0x401000);
+        assert_eq!(state.view.asm[0].inst, "push rbp");
         assert!(state.written.is_empty());
     }
 
@@ -144,13 +162,13 @@ mod tests {
             error: None,
         };
         let reg_storage = RegisterStorage::new("rax".to_string(), Some(reg), Deref::new());
-        state.registers.push(reg_storage);
+        state.view.registers.push(reg_storage);
 
         state.written.push_back(Written::SymbolAtAddrRegister((reg_num.to_string(), addr)));
 
         recv_exec_result_asm_insns(&mut state, &asm_input.to_string());
 
-        assert_eq!(state.registers[0].deref.final_assembly, expected);
+        assert_eq!(state.view.registers[0].deref.final_assembly, expected);
     }
 
     #[rstest]
@@ -167,12 +185,12 @@ mod tests {
     ) {
         let mut state = create_test_state();
 
-        state.stack.insert(stack_addr, Deref::new());
+        state.view.stack.insert(stack_addr, Deref::new());
         state.written.push_back(Written::SymbolAtAddrStack(format!("{stack_addr:x}")));
 
         recv_exec_result_asm_insns(&mut state, &asm_input.to_string());
 
-        let deref = state.stack.get(&stack_addr).unwrap();
+        let deref = state.view.stack.get(&stack_addr).unwrap();
         assert_eq!(deref.final_assembly, expected);
     }
 
@@ -192,7 +210,7 @@ mod tests {
             error: None,
         };
         let reg_storage = RegisterStorage::new("rcx".to_string(), Some(reg), Deref::new());
-        state.registers.push(reg_storage);
+        state.view.registers.push(reg_storage);
 
         state.written.push_back(Written::SymbolAtAddrRegister(("2".to_string(), 0x401000)));
 
@@ -200,6 +218,6 @@ mod tests {
 
         recv_exec_result_asm_insns(&mut state, &asm);
 
-        assert_eq!(state.registers[0].deref.final_assembly, "");
+        assert_eq!(state.view.registers[0].deref.final_assembly, "");
     }
 }
@@ -0,0 +1,66 @@
+use crate::State;
+use crate::mi::{parse_breakpoint, parse_breakpoint_table};
+
+/// `MIResponse::ExecResult`, key: "bkpt"
+///
+/// Result of a `-break-insert`, which unlike the cli `break` doesn't emit `=breakpoint-created`
+pub fn recv_exec_result_bkpt(state: &mut State, bkpt: &str) {
+    if let Some(bkpt) = parse_breakpoint(bkpt) {
+        state.upsert_breakpoint(bkpt);
+    }
+}
+
+/// `MIResponse::ExecResult`, key: "`BreakpointTable`"
+pub fn recv_exec_result_breakpoint_table(state: &mut State, table: &str) {
+    state.breakpoints = parse_breakpoint_table(table);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_bkpt_upserts_by_number() {
+        let mut state = create_test_state();
+        recv_exec_result_bkpt(
+            &mut state,
+            r#"{number="3",type="breakpoint",disp="keep",enabled="y",addr="0x401000",line="4"}"#,
+        );
+        recv_exec_result_bkpt(
+            &mut state,
+            r#"{number="3",type="breakpoint",disp="keep",enabled="y",addr="0x401000",line="4",cond="i == 2"}"#,
+        );
+        assert_eq!(state.breakpoints.len(), 1);
+        assert_eq!(state.breakpoints[0].cond.as_deref(), Some("i == 2"));
+    }
+
+    #[test]
+    fn test_breakpoint_table_replaces() {
+        let mut state = create_test_state();
+        recv_exec_result_bkpt(&mut state, r#"{number="9",addr="0x401000"}"#);
+        recv_exec_result_breakpoint_table(
+            &mut state,
+            r#"{nr_rows="1",nr_cols="6",hdr=[],body=[bkpt={number="1",enabled="y",addr="0x402000"}]}"#,
+        );
+        assert_eq!(state.breakpoints.len(), 1);
+        assert_eq!(state.breakpoints[0].number, "1");
+
+        recv_exec_result_breakpoint_table(
+            &mut state,
+            r#"{nr_rows="0",nr_cols="6",hdr=[],body=[]}"#,
+        );
+        assert!(state.breakpoints.is_empty());
+    }
+}
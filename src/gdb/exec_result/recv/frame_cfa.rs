@@ -0,0 +1,80 @@
+use crate::stack::parse_frame_cfa;
+use crate::{State, Written};
+
+/// `MIResponse::ExecResult`, status: "done"
+///
+/// `info frame` of the frame to scroll the Stack pane to, accumulated from the console stream
+pub fn recv_exec_result_frame_cfa(state: &mut State, accumulated_output: &str) {
+    let Some(Written::FrameCfa(level)) = state.written.pop_front() else {
+        return;
+    };
+    match parse_frame_cfa(accumulated_output) {
+        Some(cfa) => state.scroll_stack_to(cfa, level),
+        None => state.output.push(format!("h> no frame address of frame #{level}")),
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// No frame at that level
+pub fn recv_exec_error_frame_cfa(state: &mut State, level: usize, msg: Option<&String>) {
+    let msg = msg.map_or("unknown error", String::as_str);
+    state.output.push(format!("h> no frame #{level}: {msg}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deref::Deref;
+    use crate::mi::Register;
+    use crate::register::RegisterStorage;
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_recv_exec_result_frame_cfa() {
+        let mut state = create_test_state();
+        let register = Register {
+            number: "7".to_string(),
+            value: Some("0x7fffffffe000".to_string()),
+            v2_int128: None,
+            v8_int32: None,
+            v4_int64: None,
+            v8_float: None,
+            v16_int8: None,
+            v4_int32: None,
+            error: None,
+        };
+        state.view.registers =
+            vec![RegisterStorage::new("rsp".to_string(), Some(register), Deref::new())];
+        state.stack_slots = 32;
+        state.stack_viewport_height = 10;
+        state.stack_depth = crate::stack::StackDepth::Slots(100);
+
+        state.written.push_back(Written::FrameCfa(1));
+        recv_exec_result_frame_cfa(&mut state, "Stack level 1, frame at 0x7fffffffe200:\n");
+        assert!(state.written.front().is_some_and(|w| matches!(w, Written::Stack(None))));
+        assert_eq!(state.stack_goto, Some(0x7fffffffe200));
+        assert_eq!(state.stack_cfa, Some((0x7fffffffe200, 1)));
+        // up to a page past the frame, within the depth
+        assert_eq!(state.stack_slots, 96);
+
+        state.written.clear();
+        state.written.push_back(Written::FrameCfa(2));
+        recv_exec_result_frame_cfa(&mut state, "Stack level 2, frame at 0x7fffffffe400:\n");
+        assert_eq!(
+            state.output.last().unwrap(),
+            "h> frame #2 at 0x7fffffffe400 is past the 100 slots read, see stack-depth"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::mi::{frame_var_addrs, parse_variables};
+use crate::stack::{parse_info_frame, parse_var_addrs};
+use crate::{State, Written};
+
+/// `MIResponse::ExecResult`, status: "done"
+///
+/// `info frame` of a frame of the backtrace, accumulated from the console stream
+pub fn recv_exec_result_frame_info(state: &mut State, accumulated_output: &str) {
+    let Some(Written::FrameInfo(level)) = state.written.pop_front() else {
+        return;
+    };
+    if let Some(mut layout) = parse_info_frame(level, accumulated_output) {
+        if let Some(bt) = state.view.bt.get(level) {
+            layout.func = bt.frame.func.clone();
+            layout.has_source = bt.frame.file.is_some();
+        }
+        state.view.frame_layouts.retain(|l| l.level != level);
+        state.view.frame_layouts.push(layout);
+        state.view.frame_layouts.sort_by_key(|l| l.level);
+        state.update_smashed_stack_slots();
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "done"
+///
+/// Locals of a frame of the backtrace, which with its arguments are asked for their addresses
+pub fn recv_exec_result_frame_locals(state: &mut State, locals: &str) {
+    let Some(Written::FrameLocals(level)) = state.written.front() else {
+        return;
+    };
+    let level = *level;
+    state.written.pop_front();
+    let args = state.view.bt.get(level).map(|bt| bt.args.as_slice()).unwrap_or_default();
+    let names: Vec<String> =
+        args.iter().chain(&parse_variables(locals)).map(|var| var.name.clone()).collect();
+    if names.is_empty() {
+        return;
+    }
+    state.next_write.push(frame_var_addrs(level, &names));
+    state.written.push_back(Written::FrameVars(level, names));
+}
+
+/// `MIResponse::ExecResult`, status: "done"
+///
+/// Addresses of the variables of a frame, accumulated from the console stream
+pub fn recv_exec_result_frame_vars(state: &mut State, accumulated_output: &str) {
+    let Some(Written::FrameVars(level, names)) = state.written.pop_front() else {
+        return;
+    };
+    let vars = parse_var_addrs(&names, accumulated_output);
+    if let Some(layout) = state.view.frame_layouts.iter_mut().find(|l| l.level == level) {
+        layout.vars = vars;
+    }
+    state.update_smashed_stack_slots();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_recv_exec_result_frame_layout() {
+        let mut state = create_test_state();
+        state.written.push_back(Written::FrameInfo(0));
+        state.written.push_back(Written::FrameLocals(0));
+        let output = "Stack level 0, frame at 0x7fffffffe0f0:
+ rip = 0x401136 in main (main.c:12); saved rip = 0x7ffff7dd6d90
+ Saved registers:
+  rbp at 0x7fffffffe0e0, rip at 0x7fffffffe0e8
+";
+        recv_exec_result_frame_info(&mut state, output);
+        assert_eq!(state.view.frame_layouts[0].cfa, 0x7fffffffe0f0);
+
+        recv_exec_result_frame_locals(
+            &mut state,
+            r#"[{name="buf",type="char [16]"},{name="i",type="int",value="0"}]"#,
+        );
+        assert_eq!(
+            state.next_write.last().unwrap(),
+            r#"-interpreter-exec console "frame apply level 0 -q -s printf \"%p %p\\n\", &buf, &i""#
+        );
+        recv_exec_result_frame_vars(&mut state, "0x7fffffffe0c0 0x7fffffffe0dc\n");
+        assert!(state.written.is_empty());
+        assert_eq!(
+            state.view.frame_layouts[0].vars,
+            [("buf".to_string(), 0x7fffffffe0c0), ("i".to_string(), 0x7fffffffe0dc)]
+        );
+    }
+}
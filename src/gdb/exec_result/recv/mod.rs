@@ -1,7 +1,14 @@
 pub mod asm_insns;
+pub mod breakpoints;
 pub mod changed_registers;
+pub mod frame_cfa;
+pub mod frame_layout;
 pub mod register_names;
 pub mod register_values;
 pub mod result_memory;
 pub mod symbols;
+pub mod target_description;
+pub mod threads;
+pub mod type_layout;
 pub mod value;
+pub mod var_objects;
@@ -1,13 +1,11 @@
 use log::trace;
 
 use crate::deref::Deref;
-use crate::gdb::dump_sp_bytes;
 use crate::mi::{
     INSTRUCTION_LEN, data_disassemble, data_disassemble_pc, data_read_memory_bytes, join_registers,
     parse_register_values, read_pc_value,
 };
 use crate::register::RegisterStorage;
-use crate::ui::SAVED_STACK;
 use crate::{PtrSize, State, Written};
 
 /// `MIResponse::ExecResult`, key: "register-values"
@@ -15,13 +13,20 @@ use crate::{PtrSize, State, Written};
 /// This is the first time we see the register-values, so this is the actual
 /// value for them (not any deref values)
 pub fn recv_exec_results_register_values(register_values: &String, state: &mut State) {
+    let refresh = match state.written.front() {
+        Some(Written::RegisterRefresh { stack }) => Some(*stack),
+        _ => None,
+    };
+    if refresh.is_some() {
+        state.written.pop_front();
+    }
     // parse the response and save it
     let registers_local = parse_register_values(register_values);
     for r in registers_local.iter().flatten() {
         if r.is_set()
             && let Some(val) = &r.value
         {
-            if state.ptr_size == PtrSize::Size32 {
+            if state.view.ptr_size == PtrSize::Size32 {
                 // TODO: this should be able to expect
                 if let Ok(val_u32) = u32::from_str_radix(&val[2..], 16) {
                     // NOTE: This is already in the right endian
@@ -30,10 +35,11 @@ pub fn recv_exec_results_register_values(register_values: &String, state: &mut S
                         // If this is a code location, go ahead and try
                         // to request the asm at that spot
                         let mut asked_for_code = false;
-                        if let Some(memory_map) = state.memory_map.as_ref() {
+                        if let Some(memory_map) = state.view.memory_map.as_ref() {
                             for b in memory_map {
-                                let is_path =
-                                    b.is_path(state.filepath.as_ref().unwrap().to_str().unwrap());
+                                let is_path = b.is_path(
+                                    state.view.filepath.as_ref().unwrap().to_str().unwrap(),
+                                );
                                 if b.contains(u64::from(val_u32)) && (is_path || b.is_exec()) {
                                     state
                                         .next_write
@@ -65,10 +71,11 @@ pub fn recv_exec_results_register_values(register_values: &String, state: &mut S
                         // If this is a code location, go ahead and try
                         // to request the asm at that spot
                         let mut asked_for_code = false;
-                        if let Some(memory_map) = state.memory_map.as_ref() {
+                        if let Some(memory_map) = state.view.memory_map.as_ref() {
                             for b in memory_map {
-                                let is_path =
-                                    b.is_path(state.filepath.as_ref().unwrap().to_str().unwrap());
+                                let is_path = b.is_path(
+                                    state.view.filepath.as_ref().unwrap().to_str().unwrap(),
+                                );
                                 if b.contains(val_u64) && (is_path || b.is_exec()) {
                                     state
                                         .next_write
@@ -93,12 +100,21 @@ pub fn recv_exec_results_register_values(register_values: &String, state: &mut S
             }
         }
     }
-    let registers_new = join_registers(&state.register_names, &registers_local);
+    let registers_new = join_registers(&state.view.register_names, &registers_local);
     let registers_new: Vec<RegisterStorage> = registers_new
         .iter()
         .map(|(a, b)| RegisterStorage::new(a.clone(), b.clone(), Deref::new()))
         .collect();
-    state.registers = registers_new.clone();
+    state.view.registers = registers_new.clone();
+
+    // after an assignment, only what the register moves is read again
+    if let Some(stack) = refresh {
+        if stack {
+            state.view.stack.clear();
+            state.read_stack();
+        }
+        return;
+    }
 
     // assuming we have a valid $pc, get the bytes
     trace!("requesting pc bytes");
@@ -107,11 +123,7 @@ pub fn recv_exec_results_register_values(register_values: &String, state: &mut S
 
     // assuming we have a valid Stack ($sp), get the bytes
     trace!("requesting stack");
-    if state.ptr_size == PtrSize::Size32 {
-        dump_sp_bytes(state, 4, u64::from(SAVED_STACK));
-    } else {
-        dump_sp_bytes(state, 8, u64::from(SAVED_STACK));
-    }
+    state.read_stack();
 
     // update current asm at pc
     trace!("updating pc asm");
@@ -130,7 +142,7 @@ mod tests {
     fn create_test_state(ptr_size: PtrSize) -> State {
         let args = Args { gdb_path: None, remote: None, ptr_size, cmds: None, log_path: None };
         let mut state = State::new(args);
-        state.register_names = vec!["rax".to_string(), "rbx".to_string()];
+        state.view.register_names = vec!["rax".to_string(), "rbx".to_string()];
         state
     }
 
@@ -166,8 +178,8 @@ mod tests {
         #[case] is_code: bool,
     ) {
         let mut state = create_test_state(ptr_size);
-        state.filepath = Some(PathBuf::from("/usr/bin/test"));
-        state.memory_map = Some(create_memory_map("/usr/bin/test"));
+        state.view.filepath = Some(PathBuf::from("/usr/bin/test"));
+        state.view.memory_map = Some(create_memory_map("/usr/bin/test"));
 
         let register_values = format!(r#"[{{number="0",value="{addr}"}}]"#);
 
@@ -200,7 +212,7 @@ mod tests {
     #[test]
     fn test_register_values_null_pointer() {
         let mut state = create_test_state(PtrSize::Size64);
-        state.filepath = Some(PathBuf::from("/usr/bin/test"));
+        state.view.filepath = Some(PathBuf::from("/usr/bin/test"));
 
         let register_values = r#"[{number="0",value="0x0"}]"#.to_string();
 
@@ -212,17 +224,35 @@ mod tests {
         assert!(state.next_write.iter().any(|w| w.contains("$pc")));
     }
 
+    #[rstest]
+    #[case(false, false)]
+    #[case(true, true)]
+    fn test_register_values_refresh(#[case] stack: bool, #[case] reads_stack: bool) {
+        let mut state = create_test_state(PtrSize::Size64);
+        state.view.stack.insert(0x7fffffffe000, Deref::new());
+        state.written.push_back(Written::RegisterRefresh { stack });
+        let register_values = r#"[{number="0",value="0x1000"}]"#.to_string();
+
+        recv_exec_results_register_values(&register_values, &mut state);
+
+        assert_eq!(state.view.registers.len(), 1);
+        assert!(matches!(state.written.front(), Some(Written::RegisterValue((_, 0x1000)))));
+        assert!(!state.next_write.iter().any(|w| w.contains("$pc")));
+        assert_eq!(state.next_write.iter().any(|w| w.contains("$sp")), reads_stack);
+        assert_eq!(state.view.stack.is_empty(), reads_stack);
+    }
+
     #[test]
     fn test_register_values_no_memory_map() {
         let mut state = create_test_state(PtrSize::Size64);
-        state.filepath = Some(PathBuf::from("/usr/bin/test"));
-        state.memory_map = None;
+        state.view.filepath = Some(PathBuf::from("/usr/bin/test"));
+        state.view.memory_map = None;
 
         let register_values = r#"[{number="0",value="0x450000"}]"#.to_string();
 
         recv_exec_results_register_values(&register_values, &mut state);
 
-        assert!(!state.registers.is_empty());
+        assert!(!state.view.registers.is_empty());
     }
 
     #[rstest]
@@ -239,23 +269,23 @@ mod tests {
             .any(|w| matches!(w, Written::RegisterValue(_) | Written::SymbolAtAddrRegister(_)));
         assert!(!has_register_memory_request);
         assert!(!state.next_write.is_empty());
-        assert!(!state.registers.is_empty());
+        assert!(!state.view.registers.is_empty());
     }
 
     #[test]
     fn test_register_values_multiple_registers() {
         let mut state = create_test_state(PtrSize::Size64);
-        state.filepath = Some(PathBuf::from("/usr/bin/test"));
-        state.memory_map = Some(create_memory_map("/usr/bin/test"));
+        state.view.filepath = Some(PathBuf::from("/usr/bin/test"));
+        state.view.memory_map = Some(create_memory_map("/usr/bin/test"));
 
         let register_values =
             r#"[{number="0",value="0x450000"},{number="1",value="0x460000"}]"#.to_string();
 
         recv_exec_results_register_values(&register_values, &mut state);
 
-        assert_eq!(state.registers.len(), 2);
-        assert_eq!(state.registers[0].name, "rax");
-        assert_eq!(state.registers[1].name, "rbx");
+        assert_eq!(state.view.registers.len(), 2);
+        assert_eq!(state.view.registers[0].name, "rax");
+        assert_eq!(state.view.registers[1].name, "rbx");
     }
 
     #[test]
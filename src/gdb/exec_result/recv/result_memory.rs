@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use deku::ctx::Endian;
 use log::{debug, error};
 
 use crate::deref::Deref;
 use crate::gdb::read_memory;
+use crate::memory::{PAGE_LEN, Page};
 use crate::mi::{INSTRUCTION_LEN, data_disassemble, data_read_memory_bytes};
 use crate::register::RegisterStorage;
+use crate::ui::hexdump::display_index_of_row;
 use crate::{PtrSize, State, Written};
 
 /// `MIResponse::ExecResult`, key: "memory"
@@ -19,16 +21,16 @@ pub fn recv_exec_result_memory(state: &mut State, memory: &String) {
     match last_written {
         Written::RegisterValue((base_reg, _begin)) => {
             debug!("new register val for {base_reg}");
-            let thirty = state.ptr_size == PtrSize::Size32;
+            let thirty = state.view.ptr_size == PtrSize::Size32;
 
             let (data, _) = read_memory(memory);
-            for RegisterStorage { name: _, register, deref } in &mut state.registers {
+            for RegisterStorage { name: _, register, deref } in &mut state.view.registers {
                 if let Some(reg) = register
                     && reg.number == base_reg
                 {
                     let (val, len) = if thirty {
                         let outer = if let Ok(val) = u32::from_str_radix(&data["contents"], 16) {
-                            if state.endian.unwrap() == Endian::Big {
+                            if state.view.endian.unwrap() == Endian::Big {
                                 val.to_le()
                             } else {
                                 val.to_be()
@@ -41,7 +43,7 @@ pub fn recv_exec_result_memory(state: &mut State, memory: &String) {
                         (u64::from(outer), 4)
                     } else {
                         let mut val = u64::from_str_radix(&data["contents"], 16).unwrap();
-                        if state.endian.unwrap() == Endian::Big {
+                        if state.view.endian.unwrap() == Endian::Big {
                             val = val.to_le();
                         } else {
                             val = val.to_be();
@@ -53,10 +55,11 @@ pub fn recv_exec_result_memory(state: &mut State, memory: &String) {
                         // If this is a code location, go ahead and try
                         // to request the asm at that spot
                         let mut is_code = false;
-                        if let Some(mm) = &state.memory_map {
+                        if let Some(mm) = &state.view.memory_map {
                             for r in mm {
-                                let is_path =
-                                    r.is_path(state.filepath.as_ref().unwrap().to_str().unwrap());
+                                let is_path = r.is_path(
+                                    state.view.filepath.as_ref().unwrap().to_str().unwrap(),
+                                );
                                 if r.contains(val) && (is_path || r.is_exec()) {
                                     // send a search for a symbol!
                                     // TODO: 32-bit?
@@ -119,73 +122,318 @@ pub fn recv_exec_result_memory(state: &mut State, memory: &String) {
 
             update_stack(data, state, begin);
         }
-        Written::Memory => {
+        Written::Telescope => {
+            let (data, _) = read_memory(memory);
+            debug!("telescope: {data:02x?}");
+
+            update_telescope(data, state);
+        }
+        Written::Memory(addr) => {
             let (data, begin) = read_memory(memory);
             debug!("memory: ({begin:02x?}, {data:02x?}");
             let hex = hex::decode(&data["contents"]).unwrap();
-            state.hexdump = Some((u64::from_str_radix(&begin, 16).unwrap(), hex));
+            insert_hexdump_page(state, addr, hex);
+        }
+        Written::MemoryRefresh(addr) => {
+            let (data, begin) = read_memory(memory);
+            debug!("memory refresh: ({begin:02x?}, {data:02x?}");
+            let hex = hex::decode(&data["contents"]).unwrap();
+            refresh_hexdump_page(state, addr, hex);
+        }
+        Written::SnapshotPage(name, addr) => {
+            let (data, _) = read_memory(memory);
+            let hex = hex::decode(&data["contents"]).unwrap();
+            insert_snapshot_page(state, name.as_deref(), addr, Some(hex));
+        }
+        Written::LoadPreview => {
+            let (data, _) = read_memory(memory);
+            let hex = hex::decode(&data["contents"]).unwrap();
+            if let Some(load) = &mut state.view.memory_load {
+                load.preview = Page::Loaded(hex);
+            }
+        }
+        Written::FindChunk(id) => {
+            let (data, begin) = read_memory(memory);
+            let hex = hex::decode(&data["contents"]).unwrap();
+            if let Some(job) = state.find_job.as_mut().filter(|job| job.id == id) {
+                job.add_chunk(u64::from_str_radix(&begin, 16).unwrap(), &hex);
+                state.finish_find();
+            }
+        }
+        Written::MemoryWriteCheck(edits) => {
+            let (data, begin) = read_memory(memory);
+            debug!("memory write check: ({begin:02x?}, {data:02x?}");
+            let hex = hex::decode(&data["contents"]).unwrap();
+            let base = u64::from_str_radix(&begin, 16).unwrap();
+            check_memory_write(state, base, &hex, &edits);
         }
         _ => {
             error!("unexpected Written: {last_written:?}");
         }
     }
 }
-fn update_stack(data: HashMap<String, String>, state: &mut State, begin: String) {
-    // TODO: this is insane and should be cached
-    let (val, len) = if state.ptr_size == PtrSize::Size32 {
-        let mut val = u32::from_str_radix(&data["contents"], 16).unwrap();
-        if state.endian.unwrap() == Endian::Big {
-            val = val.to_le();
-        } else {
-            val = val.to_be();
-        }
+/// Add a page read for the hexdump. Once the last page of a range read in full is in, its zero
+/// rows collapse, so the row at the top is scrolled to again
+fn insert_hexdump_page(state: &mut State, addr: u64, hex: Vec<u8>) {
+    let geometry = state.hexdump_geometry();
+    let Some(memory) = &mut state.view.hexdump else {
+        return;
+    };
+    let Some(index) = memory.page_at(addr) else {
+        return;
+    };
+    let complete = memory.is_complete();
+    memory.insert(index, hex);
+    if memory.is_eager() && !complete && memory.is_complete() {
+        let index = display_index_of_row(memory, geometry, state.view.hexdump_scroll.scroll);
+        state.view.hexdump_scroll.set(index);
+    }
+    if let Some(search) = &mut state.view.hexdump_search {
+        search.update_page(memory, index);
+    }
+}
+
+/// Replace a page of the hexdump with the bytes read at a stop, marking the bytes that changed.
+/// The cursor, scroll and pending edits are kept
+fn refresh_hexdump_page(state: &mut State, addr: u64, hex: Vec<u8>) {
+    let Some(memory) = &mut state.view.hexdump else {
+        return;
+    };
+    let Some(index) = memory.page_at(addr) else {
+        return;
+    };
+    if let Some(Page::Loaded(old)) = memory.page(index) {
+        let offset = index * PAGE_LEN;
+        state.view.hexdump_changed.extend(
+            hex.iter()
+                .zip(old)
+                .enumerate()
+                .filter(|(_, (new, old))| new != old)
+                .map(|(i, _)| offset + i),
+        );
+    }
+    memory.insert(index, hex);
+    if let Some(search) = &mut state.view.hexdump_search {
+        search.update_page(memory, index);
+    }
+}
 
-        (u64::from(val), 4)
+/// Add a page read for a snapshot, or for the live side of the memory diff, `None` when it is
+/// unreadable. The memory diff compares the sides once they are both read
+fn insert_snapshot_page(state: &mut State, name: Option<&str>, addr: u64, hex: Option<Vec<u8>>) {
+    let Some(memory) = state.snapshot_mut(name) else {
+        return;
+    };
+    let Some(index) = memory.page_at(addr) else {
+        return;
+    };
+    match hex {
+        Some(hex) => memory.insert(index, hex),
+        None => memory.set_unreadable(index),
+    }
+    state.update_memory_diff();
+}
+
+/// Compare the bytes read back from `base` after a write with the written `edits`, by address.
+/// The hexdump is only updated when it still shows `base`, it may have been reopened elsewhere
+fn check_memory_write(state: &mut State, base: u64, hex: &[u8], edits: &BTreeMap<u64, u8>) {
+    let failed: Vec<u64> = edits
+        .iter()
+        .filter(|&(&addr, byte)| {
+            let read = addr.checked_sub(base).and_then(|i| hex.get(usize::try_from(i).ok()?));
+            read != Some(byte)
+        })
+        .map(|(&addr, _)| addr)
+        .collect();
+    if let Some(memory) = &mut state.view.hexdump
+        && let Some(start) = memory.offset_of(base)
+    {
+        state.view.hexdump_failed.extend(failed.iter().filter_map(|&addr| memory.offset_of(addr)));
+        memory.write(start, hex);
+    }
+    if let Some(first) = failed.first() {
+        state.output.push(format!(
+            "h> {} of {} written bytes did not stick, first at 0x{first:x}",
+            failed.len(),
+            edits.len(),
+        ));
     } else {
-        let mut val = u64::from_str_radix(&data["contents"], 16).unwrap();
-        if state.endian.unwrap() == Endian::Big {
-            val = val.to_le();
-        } else {
-            val = val.to_be();
-        }
+        state.output.push(format!("h> wrote {} bytes at 0x{base:x}", edits.len()));
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Unreadable page of the hexdump, such as a guard page
+pub fn recv_exec_error_memory_page(state: &mut State, addr: u64) {
+    if let Some(memory) = &mut state.view.hexdump
+        && let Some(index) = memory.page_at(addr)
+    {
+        memory.set_unreadable(index);
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Unreadable page of a snapshot, or of the live side of the memory diff
+pub fn recv_exec_error_snapshot_page(state: &mut State, name: Option<&str>, addr: u64) {
+    insert_snapshot_page(state, name, addr, None);
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Unreadable bytes where a file load is about to be written
+pub fn recv_exec_error_load_preview(state: &mut State) {
+    if let Some(load) = &mut state.view.memory_load {
+        load.preview = Page::Unreadable;
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Failed write of a chunk of a file load, the rest of the file is not written
+pub fn recv_exec_error_load_chunk(state: &mut State, msg: Option<&String>) {
+    let Some(load) = state.view.memory_load.take() else {
+        return;
+    };
+    let msg = msg.map_or("unknown error", String::as_str);
+    let written = load.written.unwrap_or(0);
+    state.output.push(format!(
+        "h> load of {} failed at 0x{:x} after 0x{written:x} bytes: {msg}",
+        load.path,
+        load.addr + written as u64
+    ));
+    state.refresh_hexdump();
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Failed `-data-write-memory-bytes`, such as an unmapped address
+pub fn recv_exec_error_memory_write(state: &mut State, addr: u64, msg: Option<&String>) {
+    let msg = msg.map_or("unknown error", String::as_str);
+    state.output.push(format!("h> write to 0x{addr:x} failed: {msg}"));
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Unreadable chunk of a `find` command, such as a guard page
+pub fn recv_exec_error_find_chunk(state: &mut State, id: usize) {
+    if let Some(job) = state.find_job.as_mut().filter(|job| job.id == id) {
+        job.pending = job.pending.saturating_sub(1);
+        state.finish_find();
+    }
+}
+
+/// Pointer sized word of a deref chain read from `begin`, with its length. `None` until the
+/// endian is known, or when fewer bytes were read
+fn read_word(state: &State, data: &HashMap<String, String>) -> Option<(u64, u64)> {
+    let endian = state.view.endian?;
+    let len = if state.view.ptr_size == PtrSize::Size32 { 4 } else { 8 };
+    let bytes = hex::decode(data.get("contents")?).ok()?;
+    let mut word = [0; 8];
+    match endian {
+        Endian::Little => word[..len].copy_from_slice(bytes.get(..len)?),
+        Endian::Big => word[8 - len..].copy_from_slice(bytes.get(..len)?),
+    }
+    let val = match endian {
+        Endian::Little => u64::from_le_bytes(word),
+        Endian::Big => u64::from_be_bytes(word),
+    };
+    Some((val, len as u64))
+}
+
+/// Address right after the word read for a deref chain, to continue reading a string
+fn next_word_addr(data: &HashMap<String, String>, len: u64) -> Option<u64> {
+    let begin = data.get("begin")?.strip_prefix("0x")?;
+    u64::from_str_radix(begin, 16).ok()?.checked_add(len)
+}
+
+/// `val` points into the binary or executable memory, and is read as asm
+fn is_code(state: &State, val: u64) -> bool {
+    let path = state.view.filepath.as_ref().and_then(|p| p.to_str());
+    state.view.memory_map.as_ref().is_some_and(|mm| {
+        mm.iter().any(|r| r.contains(val) && (path.is_some_and(|p| r.is_path(p)) || r.is_exec()))
+    })
+}
+
+/// Every byte of `val` is text, so the string goes on in the next word
+fn is_string_word(val: u64) -> bool {
+    val > 0xff
+        && val
+            .to_le_bytes()
+            .iter()
+            .all(|a| a.is_ascii_alphabetic() || a.is_ascii_graphic() || a.is_ascii_whitespace())
+}
+
+/// Same deref chain as [`update_stack`], for the pointer telescoped from the inspector
+fn update_telescope(data: HashMap<String, String>, state: &mut State) {
+    let Some((val, len)) = read_word(state, &data) else {
+        error!("telescope: could not read a word from {data:02x?}");
+        return;
+    };
+
+    let Some((_, deref)) = state.view.inspector_telescope.as_mut() else {
+        return;
+    };
+    if !deref.try_push(val) || val == 0 {
+        return;
+    }
+
+    // If this is a code location, go ahead and try
+    // to request the asm at that spot
+    if is_code(state, val) {
+        debug!("telescope deref: trying to read as asm: {val:02x}");
+        state.next_write.push(data_disassemble(val as usize, INSTRUCTION_LEN));
+        state.written.push_back(Written::SymbolAtAddrTelescope);
+        return;
+    }
 
-        (val, 8)
+    // all string? Request the next
+    if is_string_word(val)
+        && let Some(addr) = next_word_addr(&data, len)
+    {
+        state.next_write.push(data_read_memory_bytes(addr, 0, len));
+        state.written.push_back(Written::Telescope);
+        return;
+    }
+
+    debug!("telescope deref: trying to read as data: {val:02x}");
+    state.next_write.push(data_read_memory_bytes(val, 0, len));
+    state.written.push_back(Written::Telescope);
+}
+
+fn update_stack(data: HashMap<String, String>, state: &mut State, begin: String) {
+    let Some((val, len)) = read_word(state, &data) else {
+        error!("stack: could not read a word from {data:02x?}");
+        return;
     };
 
     // Begin is always correct endian
-    let key = u64::from_str_radix(&begin, 16).unwrap();
-    let deref = state.stack.entry(key).or_insert(Deref::new());
+    let Ok(key) = u64::from_str_radix(&begin, 16) else {
+        return;
+    };
+    let deref = state.view.stack.entry(key).or_insert(Deref::new());
     let inserted = deref.try_push(val);
+    state.update_smashed_stack_slots();
 
     if inserted && val != 0 {
         // If this is a code location, go ahead and try
         // to request the asm at that spot
-        if let Some(mm) = &state.memory_map {
-            for r in mm {
-                let is_path = r.is_path(state.filepath.as_ref().unwrap().to_str().unwrap());
-                if r.contains(val) && (is_path || r.is_exec()) {
-                    // send a search for a symbol!
-                    debug!("stack deref: trying to read as asm: {val:02x}");
-                    state.next_write.push(data_disassemble(val as usize, INSTRUCTION_LEN));
-                    state.written.push_back(Written::SymbolAtAddrStack(begin.clone()));
-                    return;
-                }
-            }
+        if is_code(state, val) {
+            // send a search for a symbol!
+            debug!("stack deref: trying to read as asm: {val:02x}");
+            state.next_write.push(data_disassemble(val as usize, INSTRUCTION_LEN));
+            state.written.push_back(Written::SymbolAtAddrStack(begin));
+            return;
         }
 
         // all string? Request the next
-        if val > 0xff {
-            let bytes = val.to_le_bytes();
-            if bytes
-                .iter()
-                .all(|a| a.is_ascii_alphabetic() || a.is_ascii_graphic() || a.is_ascii_whitespace())
-            {
-                let addr = data["begin"].strip_prefix("0x").unwrap().to_string();
-                let addr = u64::from_str_radix(&addr, 16).unwrap();
-                state.next_write.push(data_read_memory_bytes(addr + len, 0, len));
-                state.written.push_back(Written::Stack(Some(begin)));
-                return;
-            }
+        if is_string_word(val)
+            && let Some(addr) = next_word_addr(&data, len)
+        {
+            state.next_write.push(data_read_memory_bytes(addr, 0, len));
+            state.written.push_back(Written::Stack(Some(begin)));
+            return;
         }
 
         // regular value to request
@@ -194,3 +442,303 @@ fn update_stack(data: HashMap<String, String>, state: &mut State, begin: String)
         state.written.push_back(Written::Stack(Some(begin)));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::PagedMemory;
+    use crate::mi::{MIResponse, parse_mi_response};
+    use crate::search::{FIND_CHUNK_LEN, FIND_IN_FLIGHT};
+    use crate::{Args, Mode, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    fn memory(line: &str) -> String {
+        let MIResponse::ExecResult(_, kv) = parse_mi_response(line) else {
+            panic!("Expected ExecResult");
+        };
+        kv["memory"].clone()
+    }
+
+    #[test]
+    fn test_memory_write_check() {
+        let mut state = create_test_state();
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x8000000, &[0x11, 0x22, 0x33, 0x44]));
+        state.view.hexdump_edits.insert(1, 0xaa);
+        state.view.hexdump_edits.insert(2, 0xbb);
+        state.write_hexdump_edits();
+        assert_eq!(
+            state.next_write,
+            vec![
+                "-data-write-memory-bytes 0x8000001 aabb",
+                "-data-read-memory-bytes 0x8000001+0x00 2"
+            ]
+        );
+        assert!(state.view.hexdump_edits.is_empty());
+
+        // flash ignored the second byte
+        state.written.pop_front();
+        recv_exec_result_memory(
+            &mut state,
+            &memory(
+                r#"^done,memory=[{begin="0x08000001",offset="0x00000000",end="0x08000003",contents="aa33"}]"#,
+            ),
+        );
+        let bytes = state.view.hexdump.as_ref().and_then(PagedMemory::contiguous);
+        assert_eq!(bytes, Some(vec![0x11, 0xaa, 0x33, 0x44]));
+        assert_eq!(state.view.hexdump_failed.iter().copied().collect::<Vec<_>>(), vec![2]);
+        assert_eq!(
+            state.output.last().unwrap(),
+            "h> 1 of 2 written bytes did not stick, first at 0x8000002"
+        );
+
+        // the hexdump was reopened below the write before its check came back
+        state.view.hexdump_edits.insert(3, 0xcc);
+        state.write_hexdump_edits();
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x7fffff0, &[0; 4]));
+        state.written.pop_front();
+        recv_exec_result_memory(
+            &mut state,
+            &memory(
+                r#"^done,memory=[{begin="0x08000003",offset="0x00000000",end="0x08000004",contents="cc"}]"#,
+            ),
+        );
+        let bytes = state.view.hexdump.as_ref().and_then(PagedMemory::contiguous);
+        assert_eq!(bytes, Some(vec![0; 4]));
+        assert!(state.view.hexdump_failed.is_empty());
+        assert_eq!(state.output.last().unwrap(), "h> wrote 1 bytes at 0x8000003");
+    }
+
+    #[test]
+    fn test_memory_refresh_changes() {
+        let mut state = create_test_state();
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x8000000, &[0x11, 0x22, 0x33, 0x44]));
+        state.view.hexdump_cursor = 3;
+        state.refresh_hexdump();
+        assert_eq!(state.next_write, vec!["-data-read-memory-bytes 0x8000000+0x00 4"]);
+
+        recv_exec_result_memory(
+            &mut state,
+            &memory(
+                r#"^done,memory=[{begin="0x08000000",offset="0x00000000",end="0x08000004",contents="11ff3300"}]"#,
+            ),
+        );
+        let bytes = state.view.hexdump.as_ref().and_then(PagedMemory::contiguous);
+        assert_eq!(bytes, Some(vec![0x11, 0xff, 0x33, 0x00]));
+        assert_eq!(state.view.hexdump_changed.iter().copied().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(state.view.hexdump_cursor, 3);
+
+        // another buffer has nothing to compare with
+        state.load_hexdump(0x9000000, 2);
+        recv_exec_result_memory(
+            &mut state,
+            &memory(
+                r#"^done,memory=[{begin="0x09000000",offset="0x00000000",end="0x09000002",contents="0102"}]"#,
+            ),
+        );
+        assert!(state.view.hexdump_changed.is_empty());
+        let bytes = state.view.hexdump.as_ref().and_then(PagedMemory::contiguous);
+        assert_eq!(bytes, Some(vec![0x01, 0x02]));
+    }
+
+    #[test]
+    fn test_stack_word() {
+        let mut state = create_test_state();
+        let line = memory(
+            r#"^done,memory=[{begin="0x7ffc0000",offset="0x00000000",end="0x7ffc0008",contents="3611400000000000"}]"#,
+        );
+        // no endian yet, nothing to decode
+        state.written.push_back(Written::Stack(None));
+        recv_exec_result_memory(&mut state, &line);
+        assert!(state.view.stack.is_empty());
+
+        // executable mapping without a file loaded
+        state.view.endian = Some(Endian::Little);
+        state.view.memory_map = Some(vec![crate::mi::MemoryMapping {
+            start_address: 0x400000,
+            end_address: 0x402000,
+            size: 0x2000,
+            offset: 0,
+            permissions: Some("r-xp".to_string()),
+            path: None,
+        }]);
+        state.written.push_back(Written::Stack(None));
+        recv_exec_result_memory(&mut state, &line);
+        assert_eq!(state.view.stack[&0x7ffc0000].map, vec![0x401136]);
+        assert!(matches!(state.written.back(), Some(Written::SymbolAtAddrStack(_))));
+
+        // big endian
+        state.view.stack.clear();
+        state.written.clear();
+        state.view.endian = Some(Endian::Big);
+        state.written.push_back(Written::Stack(None));
+        recv_exec_result_memory(&mut state, &line);
+        assert_eq!(state.view.stack[&0x7ffc0000].map, vec![0x3611400000000000]);
+    }
+
+    #[test]
+    fn test_hexdump_pages() {
+        let mut state = create_test_state();
+        state.load_hexdump(0x10000, PAGE_LEN as u64 * 3);
+        assert_eq!(
+            state.next_write,
+            vec![
+                "-data-read-memory-bytes 0x10000+0x00 4096",
+                "-data-read-memory-bytes 0x11000+0x00 4096",
+                "-data-read-memory-bytes 0x12000+0x00 4096",
+            ]
+        );
+        // the rows are not collapsed while pages are missing
+        state.view.hexdump_scroll.set_max_scroll(0x300);
+        state.view.hexdump_scroll.set(0x180);
+        let page = |addr: u64, byte: u8| {
+            memory(&format!(
+                r#"^done,memory=[{{begin="0x{addr:x}",offset="0x00000000",end="0x{:x}",contents="{}"}}]"#,
+                addr + PAGE_LEN as u64,
+                format!("{byte:02x}").repeat(PAGE_LEN)
+            ))
+        };
+        recv_exec_result_memory(&mut state, &page(0x10000, 0));
+        recv_exec_error_memory_page(&mut state, 0x11000);
+        state.written.pop_front();
+        let memory = state.view.hexdump.as_ref().unwrap();
+        assert!(memory.is_unreadable(PAGE_LEN));
+        assert_eq!(state.view.hexdump_scroll.scroll, 0x180);
+
+        // the zero rows collapse once the last page is in, so row 0x180 moves up to line 0x82
+        state.view.hexdump = Some(PagedMemory::new(0x10000, PAGE_LEN * 2));
+        state.view.hexdump.as_mut().unwrap().insert(0, vec![0; PAGE_LEN]);
+        state.written = [Written::Memory(0x11000)].into();
+        recv_exec_result_memory(&mut state, &page(0x11000, 0x41));
+        assert_eq!(state.view.hexdump_scroll.scroll, 0x82);
+    }
+
+    #[test]
+    fn test_snapshot_diff_with_live() {
+        let mut state = create_test_state();
+        assert!(state.snapshot_mem(" a 0x10000").is_err());
+        assert!(state.diff_mem(" a").is_err());
+        state.snapshot_mem(" a 0x10000 0x10").unwrap();
+        assert_eq!(state.next_write, vec!["-data-read-memory-bytes 0x10000+0x00 16"]);
+        let read = |contents: &str| {
+            memory(&format!(
+                r#"^done,memory=[{{begin="0x10000",offset="0x00000000",end="0x10010",contents="{contents}"}}]"#
+            ))
+        };
+        recv_exec_result_memory(&mut state, &read(&"41".repeat(0x10)));
+
+        state.diff_mem(" a").unwrap();
+        assert_eq!(state.mode, Mode::OnlyHexdumpDiff);
+        assert!(matches!(state.written.front(), Some(Written::SnapshotPage(None, 0x10000))));
+        assert_eq!(state.memory_diff.as_ref().unwrap().differences, None);
+        recv_exec_result_memory(
+            &mut state,
+            &read(&format!("{}4242{}", "41".repeat(4), "41".repeat(10))),
+        );
+        let diff = state.memory_diff.as_ref().unwrap();
+        assert_eq!(diff.at(5), Some(true));
+        assert_eq!(diff.at(6), None);
+
+        // read again at a stop, this time unreadable
+        state.refresh_memory_diff();
+        assert_eq!(state.memory_diff.as_ref().unwrap().differences, None);
+        state.written.pop_front();
+        recv_exec_error_snapshot_page(&mut state, None, 0x10000);
+        assert_eq!(state.memory_diff.as_ref().unwrap().at(0), Some(true));
+    }
+
+    #[test]
+    fn test_memory_write_error() {
+        let mut state = create_test_state();
+        recv_exec_error_memory_write(
+            &mut state,
+            0x1000,
+            Some(&"Cannot access memory at address 0x1000".to_string()),
+        );
+        assert_eq!(
+            state.output.last().unwrap(),
+            "h> write to 0x1000 failed: Cannot access memory at address 0x1000"
+        );
+    }
+
+    #[test]
+    fn test_find_in_mapping() {
+        let mut state = create_test_state();
+        state.view.memory_map = Some(vec![crate::mi::MemoryMapping {
+            start_address: 0x405000,
+            end_address: 0x405010,
+            size: 0x10,
+            offset: 0,
+            permissions: Some("rw-p".to_string()),
+            path: Some("[heap]".to_string()),
+        }]);
+        state.find("[heap] de ?? be ef").unwrap();
+        assert_eq!(state.next_write, vec!["-data-read-memory-bytes 0x405000+0x00 16"]);
+
+        recv_exec_result_memory(
+            &mut state,
+            &memory(
+                r#"^done,memory=[{begin="0x0000000000405000",offset="0x0000000000000000",end="0x0000000000405010",contents="0000deadbeef00000000de00beef0000"}]"#,
+            ),
+        );
+        assert!(state.find_job.is_none());
+        assert_eq!(
+            state.output.last().unwrap(),
+            "f> 2 matches for de ?? be ef: 0x405002, 0x40500a"
+        );
+        // the mapping is shown in the hexdump with the cursor on the first match, highlighted
+        // once it is read
+        assert_eq!(state.mode, crate::Mode::OnlyHexdump);
+        assert!(state.view.hexdump_search.is_some());
+        assert_eq!(state.view.hexdump_cursor, 2);
+        assert_eq!(state.next_write.last().unwrap(), "-data-read-memory-bytes 0x405000+0x00 16");
+
+        assert!(state.find("[stack] 00").is_err());
+        assert!(state.find("0x1000").is_err());
+    }
+
+    #[test]
+    fn test_find_unreadable_chunk() {
+        let mut state = create_test_state();
+        state.find("0x1000 16 \"x\"").unwrap();
+        let Some(Written::FindChunk(id)) = state.written.pop_front() else {
+            panic!("Expected FindChunk");
+        };
+        recv_exec_error_find_chunk(&mut state, id);
+        assert!(state.find_job.is_none());
+        assert_eq!(state.output.last().unwrap(), "f> no match for \"x\"");
+
+        // the range wraps around the address space
+        assert!(state.find("0xffffffffffffff00 0x200 \"x\"").is_err());
+    }
+
+    #[test]
+    fn test_find_chunks_in_flight() {
+        let mut state = create_test_state();
+        let chunks = FIND_IN_FLIGHT as u64 + 2;
+        state.find(&format!("0x100000 0x{:x} \"x\"", chunks * FIND_CHUNK_LEN)).unwrap();
+        assert_eq!(state.next_write.len(), FIND_IN_FLIGHT);
+        assert_eq!(state.find_job.as_ref().unwrap().pending, FIND_IN_FLIGHT);
+
+        // each answer sends the next read, until none are left
+        for i in 0..chunks {
+            let Some(Written::FindChunk(id)) = state.written.pop_front() else {
+                panic!("Expected FindChunk");
+            };
+            recv_exec_error_find_chunk(&mut state, id);
+            let sent = (FIND_IN_FLIGHT as u64 + i + 1).min(chunks);
+            assert_eq!(state.next_write.len() as u64, sent);
+        }
+        assert!(state.find_job.is_none());
+        assert!(state.written.is_empty());
+    }
+}
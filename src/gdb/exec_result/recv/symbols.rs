@@ -73,7 +73,7 @@ mod tests {
             log_path: None,
         };
         let mut state = State::new(args);
-        state.written.push_back(Written::Memory);
+        state.written.push_back(Written::Memory(0));
 
         let output = "0x00401000 main\n0x00402000 foo";
         recv_exec_result_symbols(&mut state, output);
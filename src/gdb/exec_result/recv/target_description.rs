@@ -0,0 +1,71 @@
+use crate::flags::parse_tdesc;
+use crate::{State, Written};
+
+/// `MIResponse::ExecResult`, status: "done"
+///
+/// Target description XML from `maint print xml-tdesc`, accumulated from the console stream
+pub fn recv_exec_result_target_description(state: &mut State, accumulated_output: &str) {
+    let Some(Written::TargetDescription) = state.written.pop_front() else {
+        return;
+    };
+    state.view.register_layouts = parse_tdesc(accumulated_output);
+    let mut names: Vec<&str> = state.view.register_layouts.keys().map(String::as_str).collect();
+    names.sort_unstable();
+    if names.is_empty() {
+        state.output.push("h> no register bit fields in the target description".to_string());
+    } else {
+        state
+            .output
+            .push(format!("h> bit fields from the target description: {}", names.join(" ")));
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Target description gdb could not print, such as without a running target
+pub fn recv_exec_error_target_description(state: &mut State, msg: Option<&String>) {
+    let msg = msg.map_or("unknown error", String::as_str);
+    state.output.push(format!("h> no target description: {msg}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size32,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_recv_exec_result_target_description() {
+        let mut state = create_test_state();
+        state.request_target_description();
+        assert_eq!(state.next_write, [r#"-interpreter-exec console "maint print xml-tdesc""#]);
+        let output = r#"<target>
+  <flags id="vpr_reg" size="4">
+    <field name="P0" start="0" end="15"/>
+    <field name="MASK01" start="16" end="19"/>
+  </flags>
+  <reg name="vpr" bitsize="32" type="vpr_reg"/>
+</target>
+"#;
+        recv_exec_result_target_description(&mut state, output);
+        assert!(state.written.is_empty());
+        assert_eq!(state.output.last().unwrap(), "h> bit fields from the target description: vpr");
+        assert_eq!(state.flags_layout("vpr").unwrap().fields.len(), 2);
+        // the built in layouts come first
+        state
+            .view
+            .register_layouts
+            .insert("cpsr".to_string(), state.view.register_layouts["vpr"].clone());
+        assert_eq!(state.flags_layout("cpsr").unwrap().fields.len(), 13);
+    }
+}
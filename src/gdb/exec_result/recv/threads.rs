@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use crate::State;
+use crate::gdb::refresh_frame;
+use crate::mi::{parse_frame, parse_threads};
+
+/// `MIResponse::ExecResult`, key: "threads"
+///
+/// Result of `-thread-info`
+pub fn recv_exec_result_threads(state: &mut State, threads: &str, current: Option<&String>) {
+    state.threads = parse_threads(threads);
+    if let Some(current) = current {
+        state.current_thread_id = Some(current.clone());
+    }
+}
+
+/// `MIResponse::ExecResult`, key: "new-thread-id"
+///
+/// Result of `-thread-select`, re-read everything for the now selected thread
+pub fn recv_exec_result_new_thread_id(state: &mut State, id: &str, kv: &HashMap<String, String>) {
+    state.current_thread_id = Some(id.to_string());
+    let frame = kv.get("frame").map(|f| parse_frame(f)).unwrap_or_default();
+    refresh_frame(state, &frame);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mi::{MIResponse, parse_mi_response};
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_thread_select_refreshes() {
+        let mut state = create_test_state();
+        let MIResponse::ExecResult(_, kv) = parse_mi_response(
+            r#"^done,new-thread-id="3",frame={level="0",addr="0x0000000000401150",func="worker",args=[],file="test.c",fullname="/nonexistent/test.c",line="21",arch="i386:x86-64"}"#,
+        ) else {
+            panic!("Expected ExecResult");
+        };
+        recv_exec_result_new_thread_id(&mut state, &kv["new-thread-id"], &kv);
+        assert_eq!(state.current_thread_id.as_deref(), Some("3"));
+        assert_eq!(state.view.current_source_line, Some(21));
+        assert!(state.next_write.contains(&"-stack-list-frames".to_string()));
+    }
+}
@@ -0,0 +1,74 @@
+use crate::overlay::parse_ptype_offsets;
+use crate::{State, Written};
+
+/// `MIResponse::ExecResult`, status: "done"
+///
+/// Layout of the type of the overlay from `ptype /o`, accumulated from the console stream
+pub fn recv_exec_result_type_layout(state: &mut State, accumulated_output: &str) {
+    let Some(Written::TypeLayout(mut overlay)) = state.written.pop_front() else {
+        return;
+    };
+    match parse_ptype_offsets(accumulated_output) {
+        Some((size, fields)) => {
+            overlay.size = size;
+            overlay.fields = fields;
+            state.view.type_overlay = Some(overlay);
+        }
+        None => state.output.push(format!("h> {} is not a struct or union", overlay.name)),
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Unknown type for the overlay
+pub fn recv_exec_error_type_layout(state: &mut State, name: &str, msg: Option<&String>) {
+    let msg = msg.map_or("unknown error", String::as_str);
+    state.output.push(format!("h> no layout of {name}: {msg}"));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overlay::TypeOverlay;
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_recv_exec_result_type_layout() {
+        let mut state = create_test_state();
+        state.view.type_overlay = Some(TypeOverlay::new("struct old", 0).unwrap());
+        state.written.push_back(Written::TypeLayout(TypeOverlay::new("int", 0x1000).unwrap()));
+        recv_exec_result_type_layout(&mut state, "type = int\n");
+        assert!(state.written.is_empty());
+        assert_eq!(state.output.last().unwrap(), "h> int is not a struct or union");
+        // the previous overlay stays
+        assert_eq!(state.view.type_overlay.as_ref().unwrap().name, "struct old");
+
+        let overlay = TypeOverlay::new("struct pair[4]", 0x1000).unwrap();
+        state.written.push_back(Written::TypeLayout(overlay));
+        let output = "/* offset      |    size */  type = struct pair {
+/*      0      |       4 */    int a;
+/*      4      |       4 */    int b;
+
+                               /* total size (bytes):    8 */
+                             }
+";
+        recv_exec_result_type_layout(&mut state, output);
+        let overlay = state.view.type_overlay.as_ref().unwrap();
+        assert_eq!(
+            (overlay.name.as_str(), overlay.size, overlay.count),
+            ("struct pair", 8, Some(4))
+        );
+        assert_eq!(overlay.fields.len(), 2);
+    }
+}
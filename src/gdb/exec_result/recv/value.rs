@@ -1,29 +1,71 @@
-use crate::{PtrSize, State, Written};
+use crate::stack::StackEnd;
+use crate::{PtrSize, State, Written, parse_number};
 
 /// `MIResponse::ExecResult`, key: "value"
 pub fn recv_exec_result_value(state: &mut State, value: &String) {
     if let Some(Written::SizeOfVoidStar) = state.written.front() {
         match value.as_str() {
             "8" => {
-                state.ptr_size = PtrSize::Size64;
+                state.view.ptr_size = PtrSize::Size64;
                 log::trace!("Setting to 64 bit mode");
             }
             "4" => {
-                state.ptr_size = PtrSize::Size32;
+                state.view.ptr_size = PtrSize::Size32;
                 log::trace!("Setting to 32 bit mode");
             }
             _ => (),
         }
         let _ = state.written.pop_front().unwrap();
+    } else if let Some(Written::StackCanary) = state.written.front() {
+        let _ = state.written.pop_front().unwrap();
+        state.view.canary = value.split_whitespace().next().and_then(parse_number);
+        state.update_smashed_stack_slots();
+    } else if let Some(Written::StackSymbol(end)) = state.written.front() {
+        let end = *end;
+        let _ = state.written.pop_front().unwrap();
+        let addr = value.split_whitespace().next().and_then(parse_number);
+        // the first symbol found of each end is kept
+        let model = &mut state.view.stack_model;
+        match end {
+            StackEnd::Low => model.linker_low = model.linker_low.or(addr),
+            StackEnd::High => model.linker_high = model.linker_high.or(addr),
+        }
+        state.update_smashed_stack_slots();
+    } else if let Some(Written::RegisterAssign(name)) = state.written.front() {
+        let name = name.clone();
+        let _ = state.written.pop_front().unwrap();
+        state.refresh_registers(&name);
+        state.view.registers_edited.insert(name);
+    } else if let Some(Written::VarAssign) = state.written.front() {
+        // the new value is reported again by `-var-update`
+        let _ = state.written.pop_front().unwrap();
     } else {
         // program is stopped, get the current pc
         let pc: Vec<&str> = value.split_whitespace().collect();
         if let Some(pc) = pc[0].strip_prefix("0x") {
-            state.current_pc = u64::from_str_radix(pc, 16).unwrap();
+            state.view.current_pc = u64::from_str_radix(pc, 16).unwrap();
         }
     }
 }
 
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Canary expression gdb could not evaluate. Without the symbol, such as in a binary built without
+/// the stack protector, it is not asked for again at each stop
+pub fn recv_exec_error_stack_canary(state: &mut State, msg: Option<&String>) {
+    if msg.is_some_and(|msg| msg.starts_with("No symbol")) {
+        state.view.canary_missing = true;
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// Register assignment gdb refused, such as an expression it cannot evaluate
+pub fn recv_exec_error_register_assign(state: &mut State, name: &str, msg: Option<&String>) {
+    let msg = msg.map_or("unknown error", String::as_str);
+    state.output.push(format!("h> set ${name} failed: {msg}"));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -50,19 +92,85 @@ mod tests {
 
         recv_exec_result_value(&mut state, &size_str.to_string());
 
-        assert_eq!(state.ptr_size, expected_size);
+        assert_eq!(state.view.ptr_size, expected_size);
+        assert!(state.written.is_empty());
+    }
+
+    #[test]
+    fn test_value_stack_canary() {
+        let mut state = create_test_state();
+        state.written.push_back(Written::StackCanary);
+        recv_exec_result_value(&mut state, &"0x9f3a6c1d52e84b00".to_string());
+        assert_eq!(state.view.canary, Some(0x9f3a6c1d52e84b00));
+        assert!(state.written.is_empty());
+    }
+
+    #[test]
+    fn test_stack_canary_missing() {
+        let mut state = create_test_state();
+        state.read_stack();
+        assert!(matches!(state.written.back(), Some(Written::StackCanary)));
+        state.written.clear();
+        recv_exec_error_stack_canary(&mut state, Some(&"Cannot access memory".to_string()));
+        assert!(!state.view.canary_missing);
+        let msg = r#"No symbol "__stack_chk_guard" in current context."#.to_string();
+        recv_exec_error_stack_canary(&mut state, Some(&msg));
+        assert!(state.view.canary_missing);
+
+        // not asked for again at the next stop, until another file is loaded
+        state.next_write.clear();
+        state.read_stack();
+        assert!(!state.written.iter().any(|w| matches!(w, Written::StackCanary)));
+        state.save_filepath("file /tmp/a.out");
+        assert!(!state.view.canary_missing);
+    }
+
+    #[test]
+    fn test_value_stack_symbol() {
+        let mut state = create_test_state();
+        state.written.push_back(Written::StackSymbol(StackEnd::High));
+        state.written.push_back(Written::StackSymbol(StackEnd::High));
+        recv_exec_result_value(&mut state, &"0x20020000 <_estack>".to_string());
+        recv_exec_result_value(&mut state, &"0x20010000 <__StackTop>".to_string());
+        assert_eq!(state.view.stack_model.linker_high, Some(0x20020000));
+        assert_eq!(state.view.stack_model.linker_low, None);
+        assert!(state.written.is_empty());
+    }
+
+    #[test]
+    fn test_value_register_assign() {
+        let mut state = create_test_state();
+        state.written.push_back(Written::RegisterAssign("rax".to_string()));
+        recv_exec_result_value(&mut state, &"48".to_string());
+        assert!(state.view.registers_edited.contains("rax"));
+        assert_eq!(state.next_write, ["-data-list-register-values x"]);
+        assert!(matches!(state.written.front(), Some(Written::RegisterRefresh { stack: false })));
+        state.written.clear();
+
+        // the stack moved along with the stack pointer, the pc is read again in full
+        state.written.push_back(Written::RegisterAssign("rsp".to_string()));
+        recv_exec_result_value(&mut state, &"0x7fffffffe000".to_string());
+        assert!(matches!(state.written.front(), Some(Written::RegisterRefresh { stack: true })));
+        state.written.clear();
+        state.written.push_back(Written::RegisterAssign("rip".to_string()));
+        recv_exec_result_value(&mut state, &"0x401000".to_string());
         assert!(state.written.is_empty());
+        // the value of an assignment is not the pc
+        assert_eq!(state.view.current_pc, 0);
+
+        recv_exec_error_register_assign(&mut state, "rbx", Some(&"No symbol \"foo\"".to_string()));
+        assert_eq!(state.output.last().unwrap(), "h> set $rbx failed: No symbol \"foo\"");
     }
 
     #[test]
     fn test_value_sizeof_voidstar_unknown() {
         let mut state = create_test_state();
         state.written.push_back(Written::SizeOfVoidStar);
-        let initial_ptr_size = state.ptr_size;
+        let initial_ptr_size = state.view.ptr_size;
 
         recv_exec_result_value(&mut state, &"16".to_string());
 
-        assert_eq!(state.ptr_size, initial_ptr_size);
+        assert_eq!(state.view.ptr_size, initial_ptr_size);
         assert!(state.written.is_empty());
     }
 
@@ -75,6 +183,6 @@ mod tests {
 
         recv_exec_result_value(&mut state, &input.to_string());
 
-        assert_eq!(state.current_pc, expected_pc);
+        assert_eq!(state.view.current_pc, expected_pc);
     }
 }
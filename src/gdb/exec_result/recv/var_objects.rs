@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+
+use crate::mi::{
+    VarObj, parse_var_changelist, parse_var_children, parse_var_obj, parse_variables, var_create,
+};
+use crate::{INSPECTOR_VAR_PREFIX, Local, State, Written, collapse_row, tree_descendants};
+
+/// `MIResponse::ExecResult`, key: "variables"
+///
+/// Result of `-stack-list-variables`, create a variable object for every new local, and
+/// delete the ones no longer in scope
+pub fn recv_exec_result_variables(state: &mut State, variables: &str) {
+    let variables = parse_variables(variables);
+
+    let mut index = 0;
+    while index < state.view.locals.len() {
+        let local = &state.view.locals[index];
+        if local.depth == 0 && !variables.iter().any(|v| v.name == local.var.exp) {
+            state.delete_local(index);
+        } else {
+            index += 1;
+        }
+    }
+
+    for variable in variables {
+        // shadowed variables are listed twice, gdb only resolves the innermost one
+        if state.view.locals.iter().any(|l| l.depth == 0 && l.var.exp == variable.name) {
+            continue;
+        }
+        let name = format!("local{}", state.var_obj_id);
+        state.var_obj_id += 1;
+        state.next_write.push(var_create(&name, &variable.name));
+        state.view.locals.push(Local {
+            var: VarObj {
+                name,
+                exp: variable.name,
+                type_: variable.type_,
+                value: variable.value,
+                numchild: 0,
+            },
+            arg: variable.arg,
+            ..Local::default()
+        });
+    }
+    state.locals_selected = state.locals_selected.min(state.view.locals.len().saturating_sub(1));
+}
+
+/// `MIResponse::ExecResult`, keys: "name" and "numchild"
+///
+/// Result of `-var-create`, for a local or an expression
+pub fn recv_exec_result_var_create(state: &mut State, kv: &HashMap<String, String>) {
+    let Some(var) = parse_var_obj(kv) else {
+        return;
+    };
+    if let Some(Written::ExpressionCreate(name)) = state.written.front()
+        && name == &var.name
+    {
+        let _ = state.written.pop_front();
+    }
+    if let Some(row) = state.var_tree_mut(&var.name).iter_mut().find(|l| l.var.name == var.name) {
+        row.var = VarObj { exp: row.var.exp.clone(), ..var };
+    } else if let Some(expression) = state.view.expressions.iter_mut().find(|e| e.name == var.name)
+    {
+        expression.type_ = var.type_;
+        expression.value = var.value;
+    }
+}
+
+/// `MIResponse::ExecResult`, status: "error"
+///
+/// `-var-create` of an expression failed, such as an unknown symbol in this frame
+pub fn recv_exec_error_expression(state: &mut State, name: &str, msg: Option<&String>) {
+    if name.starts_with(INSPECTOR_VAR_PREFIX) {
+        state.view.inspector.clear();
+        state.inspector_error = Some(msg.cloned().unwrap_or_else(|| "error".to_string()));
+    } else if let Some(expression) = state.view.expressions.iter_mut().find(|e| e.name == name) {
+        expression.error = Some(msg.cloned().unwrap_or_else(|| "error".to_string()));
+        expression.value = None;
+        expression.changed = false;
+    }
+}
+
+/// `MIResponse::ExecResult`, key: "children"
+///
+/// Result of `-var-list-children`, shown below their expanded parent
+pub fn recv_exec_result_var_children(state: &mut State, children: &str) {
+    let children = parse_var_children(children);
+    let Some(first) = children.first() else {
+        return;
+    };
+    let tree = state.var_tree_mut(&first.name);
+    // Children are named "parent.exp", the exp itself could contain a '.'
+    let Some(parent) = tree
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| first.name.starts_with(&format!("{}.", l.var.name)))
+        .max_by_key(|(_, l)| l.var.name.len())
+        .map(|(index, _)| index)
+    else {
+        return;
+    };
+    // collapsed before the children arrived
+    if !tree[parent].expanded {
+        return;
+    }
+    let depth = tree[parent].depth + 1;
+    let rows = tree_descendants(tree, parent);
+    let children = children.into_iter().map(|var| Local { var, depth, ..Local::default() });
+    tree.splice(rows, children);
+}
+
+/// `MIResponse::ExecResult`, key: "changelist"
+///
+/// Result of `-var-update`, values that changed since the last update are highlighted
+pub fn recv_exec_result_changelist(state: &mut State, changelist: &str) {
+    for local in state.view.locals.iter_mut().chain(&mut state.view.inspector) {
+        local.changed = false;
+    }
+    for expression in &mut state.view.expressions {
+        expression.changed = false;
+    }
+    for change in parse_var_changelist(changelist) {
+        if let Some(expression) = state.view.expressions.iter_mut().find(|e| e.name == change.name)
+        {
+            expression.value = change.value;
+            expression.changed = true;
+            continue;
+        }
+        let is_local = !change.name.starts_with(INSPECTOR_VAR_PREFIX);
+        let tree = state.var_tree_mut(&change.name);
+        let Some(index) = tree.iter().position(|l| l.var.name == change.name) else {
+            continue;
+        };
+        if !change.in_scope {
+            if is_local && tree[index].depth == 0 {
+                // Such as the frame was re-entered, `-stack-list-variables` creates it again
+                state.delete_local(index);
+            } else {
+                tree[index].var.value = None;
+            }
+            continue;
+        }
+        if let Some(numchild) = change.new_num_children {
+            tree[index].var.numchild = numchild;
+            collapse_row(tree, index);
+        }
+        let row = &mut tree[index];
+        row.var.value = change.value;
+        row.changed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mi::{MIResponse, parse_mi_response};
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    fn kv(line: &str) -> HashMap<String, String> {
+        let MIResponse::ExecResult(_, kv) = parse_mi_response(line) else {
+            panic!("Expected ExecResult");
+        };
+        kv
+    }
+
+    #[test]
+    fn test_locals_tree() {
+        let mut state = create_test_state();
+        let kv_vars = kv(
+            r#"^done,variables=[{name="n",arg="1",type="int",value="3"},{name="p",type="struct point"}]"#,
+        );
+        recv_exec_result_variables(&mut state, &kv_vars["variables"]);
+        assert_eq!(
+            state.next_write,
+            vec![r#"-var-create local0 * "n""#, r#"-var-create local1 * "p""#]
+        );
+        assert!(state.view.locals[0].arg);
+
+        recv_exec_result_var_create(
+            &mut state,
+            &kv(
+                r#"^done,name="local1",numchild="2",value="{...}",type="struct point",thread-id="1",has_more="0""#,
+            ),
+        );
+        assert_eq!(state.view.locals[1].var.numchild, 2);
+        assert_eq!(state.view.locals[1].var.exp, "p");
+
+        state.next_write.clear();
+        state.locals_selected = 1;
+        state.toggle_local();
+        assert_eq!(state.next_write, vec!["-var-list-children --all-values local1"]);
+        let kv_children = kv(
+            r#"^done,numchild="2",children=[child={name="local1.x",exp="x",numchild="0",value="1",type="int",thread-id="1"},child={name="local1.y",exp="y",numchild="0",value="2",type="int",thread-id="1"}],has_more="0""#,
+        );
+        recv_exec_result_var_children(&mut state, &kv_children["children"]);
+        assert_eq!(state.view.locals.len(), 4);
+        assert_eq!(state.view.locals[2].depth, 1);
+        assert_eq!(state.view.locals[3].var.exp, "y");
+
+        // only the changed values are highlighted
+        let kv_update = kv(
+            r#"^done,changelist=[{name="local1.y",value="5",in_scope="true",type_changed="false",has_more="0"}]"#,
+        );
+        recv_exec_result_changelist(&mut state, &kv_update["changelist"]);
+        assert!(state.view.locals[3].changed);
+        assert_eq!(state.view.locals[3].var.value.as_deref(), Some("5"));
+        assert!(!state.view.locals[2].changed);
+
+        state.toggle_local();
+        assert_eq!(state.view.locals.len(), 2);
+    }
+
+    #[test]
+    fn test_expression_results() {
+        let mut state = create_test_state();
+        state.add_expression("ctx->state");
+        state.add_expression("nope");
+
+        recv_exec_result_var_create(
+            &mut state,
+            &kv(r#"^done,name="expr0",numchild="0",value="1",type="int",has_more="0""#),
+        );
+        assert_eq!(state.view.expressions[0].value.as_deref(), Some("1"));
+        assert!(
+            matches!(state.written.front(), Some(Written::ExpressionCreate(n)) if n == "expr1")
+        );
+
+        let Some(Written::ExpressionCreate(name)) = state.written.pop_front() else {
+            panic!("Expected ExpressionCreate");
+        };
+        let kv_error = kv(r#"^error,msg="No symbol \"nope\" in current context.""#);
+        recv_exec_error_expression(&mut state, &name, kv_error.get("msg"));
+        assert!(state.view.expressions[1].error.as_ref().unwrap().starts_with("No symbol"));
+
+        let kv_update = kv(
+            r#"^done,changelist=[{name="expr0",value="2",in_scope="true",type_changed="false",has_more="0"}]"#,
+        );
+        recv_exec_result_changelist(&mut state, &kv_update["changelist"]);
+        assert!(state.view.expressions[0].changed);
+        assert_eq!(state.view.expressions[0].value.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn test_inspector_tree() {
+        let mut state = create_test_state();
+        state.inspect("*head");
+        state.next_write.clear();
+
+        recv_exec_result_var_create(
+            &mut state,
+            &kv(
+                r#"^done,name="inspect0",numchild="2",value="{...}",type="struct node",thread-id="1",has_more="0""#,
+            ),
+        );
+        assert!(state.written.is_empty());
+        assert_eq!(state.view.inspector[0].var.numchild, 2);
+        assert!(state.view.locals.is_empty());
+
+        state.toggle_inspector_row();
+        assert_eq!(state.next_write, vec!["-var-list-children --all-values inspect0"]);
+        let kv_children = kv(
+            r#"^done,numchild="2",children=[child={name="inspect0.val",exp="val",numchild="0",value="7",type="int"},child={name="inspect0.next",exp="next",numchild="2",value="0x4052c0",type="struct node *"}],has_more="0""#,
+        );
+        recv_exec_result_var_children(&mut state, &kv_children["children"]);
+        assert_eq!(state.view.inspector.len(), 3);
+        assert_eq!(state.view.inspector[2].depth, 1);
+        assert!(state.view.locals.is_empty());
+
+        state.inspector_selected = 2;
+        assert_eq!(state.inspector_pointer(), Some(0x4052c0));
+    }
+
+    #[test]
+    fn test_inspector_error() {
+        let mut state = create_test_state();
+        state.inspect("nope");
+        let Some(Written::ExpressionCreate(name)) = state.written.pop_front() else {
+            panic!("Expected ExpressionCreate");
+        };
+        let kv_error = kv(r#"^error,msg="No symbol \"nope\" in current context.""#);
+        recv_exec_error_expression(&mut state, &name, kv_error.get("msg"));
+        assert!(state.view.inspector.is_empty());
+        assert!(state.inspector_error.as_ref().unwrap().starts_with("No symbol"));
+    }
+
+    #[test]
+    fn test_locals_out_of_scope() {
+        let mut state = create_test_state();
+        let kv_vars = kv(
+            r#"^done,variables=[{name="i",type="int",value="0"},{name="n",type="int",value="3"}]"#,
+        );
+        recv_exec_result_variables(&mut state, &kv_vars["variables"]);
+        state.next_write.clear();
+
+        // `i` went out of scope with its block
+        let kv_vars = kv(r#"^done,variables=[{name="n",type="int",value="3"}]"#);
+        recv_exec_result_variables(&mut state, &kv_vars["variables"]);
+        assert_eq!(state.next_write, vec!["-var-delete local0"]);
+        assert_eq!(state.view.locals.len(), 1);
+        assert_eq!(state.view.locals[0].var.name, "local1");
+    }
+}
@@ -5,17 +5,29 @@ pub fn exec_result_running(state: &mut State) {
     // I'm sure in the future we could make sure we are leaving our own
     // state or something?
 
-    // reset the stack
-    state.stack.clear();
+    // reset the stack, after noting where the canaries were
+    state.remember_canaries();
+    state.view.stack.clear();
+    state.stack_slots = 0;
+    state.view.frame_layouts.clear();
+    state.view.smashed.clear();
 
     // reset the asm
-    state.asm.clear();
+    state.view.asm.clear();
 
-    // reset the regs
-    state.registers.clear();
+    // reset the regs, after noting their values to tell which flags change
+    state.remember_registers();
+    state.view.registers.clear();
+    state.view.registers_edited.clear();
 
-    // reset the hexdump
-    state.hexdump = None;
+    // the hexdump is kept, and read again on the next stop to show what changed. Its reads in
+    // flight are dropped along with `written`
+    if let Some(memory) = &mut state.view.hexdump {
+        memory.drop_pending();
+    }
+
+    // a file load writes its chunks between commands, which would now land in a running target
+    state.cancel_memory_load("continuing");
 
     // reset status
     state.async_result = "Status: running".to_string();
@@ -26,3 +38,41 @@ pub fn exec_result_running(state: &mut State) {
     state.written.clear();
     state.next_write.clear();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Args, PtrSize};
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_exec_result_running_cancels_memory_load() {
+        let path = std::env::temp_dir().join("heretek_test_running_memory_load.bin");
+        std::fs::write(&path, vec![0x41; crate::memory::LOAD_CHUNK + 2]).unwrap();
+        let mut state = create_test_state();
+        state.prepare_memory_load(&format!("{} 0x1000", path.display())).unwrap();
+        state.start_memory_load();
+        exec_result_running(&mut state);
+        assert!(state.view.memory_load.is_none());
+        assert!(state.next_write.is_empty());
+        assert_eq!(
+            state.output.last().unwrap(),
+            &format!(
+                "h> load of {} cancelled by continuing after 0x0 of 0x{:x} bytes at 0x1000",
+                path.display(),
+                crate::memory::LOAD_CHUNK + 2
+            )
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+
+use log::debug;
+
+use crate::mi::{Thread, parse_breakpoint, parse_frame};
+use crate::{Inferior, InferiorView, State};
+
+use super::refresh_frame;
+
+/// `MIResponse::Notify`
+pub fn notify(state: &mut State, event: &str, kv: &HashMap<String, String>) {
+    match event {
+        "breakpoint-created" | "breakpoint-modified" => {
+            if let Some(bkpt) = kv.get("bkpt").and_then(|b| parse_breakpoint(b)) {
+                debug!("{event}: {bkpt:?}");
+                state.upsert_breakpoint(bkpt);
+            }
+        }
+        "breakpoint-deleted" => {
+            if let Some(id) = kv.get("id") {
+                state.breakpoints.retain(|b| &b.number != id);
+            }
+        }
+        // `add-inferior`, or the child of a followed fork
+        "thread-group-added" => {
+            if let Some(id) = kv.get("id")
+                && !state.inferiors.iter().any(|i| &i.id == id)
+            {
+                // Same program as the selected inferior, until `file` or an exec says otherwise
+                let view = InferiorView {
+                    ptr_size: state.view.ptr_size,
+                    filepath: state.view.filepath.clone(),
+                    endian: state.view.endian,
+                    stack_model: state.view.stack_model.clone(),
+                    ..InferiorView::default()
+                };
+                state.inferiors.push(Inferior { view, ..Inferior::new(id) });
+            }
+        }
+        "thread-group-removed" => {
+            if let Some(id) = kv.get("id")
+                && let Some(index) = state.inferiors.iter().position(|i| &i.id == id)
+                && index != state.inferior_selected
+            {
+                state.inferiors.remove(index);
+                if index < state.inferior_selected {
+                    state.inferior_selected -= 1;
+                }
+            }
+        }
+        "thread-group-started" => {
+            if let Some(inferior) = kv.get("id").and_then(|id| state.inferior_mut(id)) {
+                inferior.pid = kv.get("pid").and_then(|p| p.parse().ok());
+                inferior.exit_code = None;
+            }
+        }
+        "thread-group-exited" => {
+            if let Some(inferior) = kv.get("id").and_then(|id| state.inferior_mut(id)) {
+                inferior.pid = None;
+                inferior.threads.clear();
+                // not reported when it was killed
+                inferior.exit_code = kv.get("exit-code").cloned();
+            }
+        }
+        // A new thread is running, the details come with `-thread-info` on the next stop
+        "thread-created" => {
+            if let Some(id) = kv.get("id")
+                && let Some(inferior) = kv.get("group-id").and_then(|g| state.inferior_mut(g))
+                && !inferior.threads.contains(id)
+            {
+                inferior.threads.push(id.clone());
+            }
+            if let Some(id) = kv.get("id")
+                && !state.threads.iter().any(|t| &t.id == id)
+            {
+                state.threads.push(Thread {
+                    id: id.clone(),
+                    target_id: String::new(),
+                    name: None,
+                    state: "running".to_string(),
+                    frame: None,
+                    core: None,
+                });
+            }
+        }
+        "thread-exited" => {
+            if let Some(id) = kv.get("id") {
+                state.threads.retain(|t| &t.id != id);
+                for inferior in &mut state.inferiors {
+                    inferior.threads.retain(|t| t != id);
+                }
+                if state.current_thread_id.as_ref() == Some(id) {
+                    state.current_thread_id = None;
+                }
+            }
+        }
+        // Thread changed from the cli, such as `thread 2`
+        "thread-selected" => {
+            if let Some(id) = kv.get("id") {
+                debug!("{event}: {id}");
+                state.current_thread_id = Some(id.clone());
+                let frame = kv.get("frame").map(|f| parse_frame(f)).unwrap_or_default();
+                refresh_frame(state, &frame);
+            }
+        }
+        _ => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryLoad;
+    use crate::mi::{MIResponse, parse_mi_response};
+    use crate::stack::Growth;
+    use crate::{Args, PtrSize};
+    use std::path::PathBuf;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    fn send(state: &mut State, line: &str) {
+        if let MIResponse::Notify(event, kv) = parse_mi_response(line) {
+            notify(state, &event, &kv);
+        } else {
+            panic!("Expected Notify");
+        }
+    }
+
+    #[test]
+    fn test_notify_breakpoint_lifecycle() {
+        let mut state = create_test_state();
+        send(
+            &mut state,
+            r#"=breakpoint-created,bkpt={number="1",type="breakpoint",disp="keep",enabled="y",addr="0x000000000040182d",func="main",file="test.c",fullname="/src/test.c",line="11",thread-groups=["i1"],times="0"}"#,
+        );
+        assert_eq!(state.breakpoints.len(), 1);
+        assert!(state.breakpoints[0].enabled);
+
+        send(
+            &mut state,
+            r#"=breakpoint-modified,bkpt={number="1",type="breakpoint",disp="keep",enabled="n",addr="0x000000000040182d",func="main",file="test.c",fullname="/src/test.c",line="11",thread-groups=["i1"],times="0"}"#,
+        );
+        assert_eq!(state.breakpoints.len(), 1);
+        assert!(!state.breakpoints[0].enabled);
+
+        send(&mut state, r#"=breakpoint-deleted,id="1""#);
+        assert!(state.breakpoints.is_empty());
+    }
+
+    #[test]
+    fn test_notify_thread_lifecycle() {
+        let mut state = create_test_state();
+        send(&mut state, r#"=thread-created,id="1",group-id="i1""#);
+        send(&mut state, r#"=thread-created,id="2",group-id="i1""#);
+        assert_eq!(state.threads.len(), 2);
+        assert_eq!(state.threads[1].state, "running");
+
+        send(
+            &mut state,
+            r#"=thread-selected,id="2",frame={level="0",addr="0x0000000000401136",func="worker",args=[],file="test.c",fullname="/nonexistent/test.c",line="7",arch="i386:x86-64"}"#,
+        );
+        assert_eq!(state.current_thread_id.as_deref(), Some("2"));
+        assert_eq!(state.view.current_source_line, Some(7));
+        assert!(state.next_write.contains(&"-data-list-register-values x".to_string()));
+
+        send(&mut state, r#"=thread-exited,id="2",group-id="i1""#);
+        assert_eq!(state.threads.len(), 1);
+        assert_eq!(state.current_thread_id, None);
+    }
+
+    #[test]
+    fn test_notify_unrelated_event() {
+        let mut state = create_test_state();
+        send(&mut state, r#"=library-loaded,id="/lib/libc.so.6""#);
+        assert!(state.breakpoints.is_empty());
+    }
+
+    #[test]
+    fn test_notify_forked_inferior() {
+        let mut state = create_test_state();
+        state.view.filepath = Some(PathBuf::from("/tmp/daemon"));
+        send(&mut state, r#"=thread-group-started,id="i1",pid="100""#);
+        send(&mut state, r#"=thread-created,id="1",group-id="i1""#);
+        send(
+            &mut state,
+            r#"=thread-selected,id="1",frame={level="0",addr="0x0000000000401136",func="main",args=[],file="parent.c",fullname="/nonexistent/parent.c",line="9"}"#,
+        );
+        state.view.current_pc = 0x401136;
+        state.view.stack_model.growth = Growth::Up;
+
+        // the child of a followed fork
+        send(&mut state, r#"=thread-group-added,id="i2""#);
+        send(&mut state, r#"=thread-group-started,id="i2",pid="101""#);
+        send(&mut state, r#"=thread-created,id="2",group-id="i2""#);
+        assert_eq!(state.inferiors.len(), 2);
+        assert_eq!(state.inferiors[1].pid, Some(101));
+        assert_eq!(state.inferior_selected, 0);
+
+        send(
+            &mut state,
+            r#"=thread-selected,id="2",frame={level="0",addr="0x0000000000401150",func="child",args=[],file="child.c",fullname="/nonexistent/child.c",line="4"}"#,
+        );
+        assert_eq!(state.inferior_selected, 1);
+        assert_eq!(state.view.current_source_line, Some(4));
+        assert_eq!(state.view.current_pc, 0);
+        // same program as its parent
+        assert_eq!(state.view.filepath, Some(PathBuf::from("/tmp/daemon")));
+        assert_eq!(state.view.stack_model.growth, Growth::Up);
+        state.view.stack_model.growth = Growth::Down;
+
+        // a file load into the child stops at the switch
+        let mut load = MemoryLoad::new("fw.bin".to_string(), 0x1000, vec![0; 0x2000]);
+        load.written = Some(0x1000);
+        state.view.memory_load = Some(load);
+
+        // the parent is shown again as it was left
+        state.next_write.clear();
+        state.select_inferior(0);
+        assert_eq!(state.next_write, vec!["-thread-select 1"]);
+        assert_eq!(state.inferior_selected, 0);
+        assert_eq!(state.view.current_pc, 0x401136);
+        assert_eq!(state.view.current_source_file.as_deref(), Some("/nonexistent/parent.c"));
+        assert_eq!(state.view.stack_model.growth, Growth::Up);
+        assert!(state.inferiors[1].view.memory_load.is_none());
+        assert_eq!(
+            state.output.last().unwrap(),
+            "h> load of fw.bin cancelled by switching inferior after 0x1000 of 0x2000 bytes at 0x1000"
+        );
+
+        send(&mut state, r#"=thread-exited,id="2",group-id="i2""#);
+        send(&mut state, r#"=thread-group-exited,id="i2",exit-code="01""#);
+        assert_eq!(state.inferiors[1].pid, None);
+        assert_eq!(state.inferiors[1].exit_code.as_deref(), Some("01"));
+
+        // without threads, the cli selects it
+        state.next_write.clear();
+        state.next_inferior();
+        assert_eq!(state.next_write, vec![r#"-interpreter-exec console "inferior 2""#]);
+
+        state.select_inferior(0);
+        send(&mut state, r#"=thread-group-removed,id="i2""#);
+        assert_eq!(state.inferiors.len(), 1);
+    }
+}
@@ -18,12 +18,12 @@ pub fn stream_output(
     current_symbols: &mut String,
 ) {
     if s.starts_with("The target endianness") {
-        state.endian = if s.contains("little") {
+        state.view.endian = if s.contains("little") {
             Some(deku::ctx::Endian::Little)
         } else {
             Some(deku::ctx::Endian::Big)
         };
-        debug!("endian: {:?}", state.endian);
+        debug!("endian: {:?}", state.view.endian);
 
         // don't include this is output
         return;
@@ -53,7 +53,7 @@ pub fn stream_output(
                     after_quote[..end_idx].trim()
                 };
 
-                state.source_language = Some(language.to_string());
+                state.view.source_language = Some(language.to_string());
                 debug!("detected source language: {}", language);
             }
         }
@@ -62,13 +62,13 @@ pub fn stream_output(
 
     // When using attach, assume the first symbols found are the text field
     // StreamOutput("~", "Reading symbols from /home/wcampbell/a.out...\n")
-    if state.filepath.is_none() {
+    if state.view.filepath.is_none() {
         let symbols = "Reading symbols from ";
         if let Some(filepath) = s.strip_prefix(symbols) {
             let filepath = filepath.trim_end();
             if let Some(filepath) = filepath.strip_suffix("...") {
                 info!("new filepath: {filepath}");
-                state.filepath = Some(PathBuf::from(filepath));
+                state.view.filepath = Some(PathBuf::from(filepath));
             }
         }
     }
@@ -106,6 +106,21 @@ pub fn stream_output(
         return;
     }
 
+    // the layout of `ptype /o`, `info frame`, the variable addresses of a frame and the target
+    // description, gdb's errors are on the log stream
+    if t == "~"
+        && let Some(
+            Written::TypeLayout(_)
+            | Written::FrameCfa(_)
+            | Written::FrameInfo(_)
+            | Written::FrameVars(..)
+            | Written::TargetDescription,
+        ) = state.written.front()
+    {
+        current_symbols.push_str(s);
+        return;
+    }
+
     if let Some(Written::SymbolAddressLookup(symbol_name)) = state.written.front() {
         let symbol_name = symbol_name.clone();
         if let Some(addr_start) = s.find(" at address ") {
@@ -167,7 +182,7 @@ mod tests {
 
         stream_output("~", input, &mut state, &mut current_map, &mut current_symbols);
 
-        assert_eq!(state.endian, Some(expected_endian));
+        assert_eq!(state.view.endian, Some(expected_endian));
         assert_eq!(state.output.len(), 0);
     }
 
@@ -181,7 +196,7 @@ mod tests {
 
         stream_output("~", input, &mut state, &mut current_map, &mut current_symbols);
 
-        assert_eq!(state.filepath, Some(PathBuf::from(expected_path)));
+        assert_eq!(state.view.filepath, Some(PathBuf::from(expected_path)));
     }
 
     #[rstest]
@@ -309,7 +324,7 @@ mod tests {
     #[test]
     fn test_stream_output_does_not_overwrite_filepath() {
         let mut state = create_test_state();
-        state.filepath = Some(PathBuf::from("/original/path"));
+        state.view.filepath = Some(PathBuf::from("/original/path"));
         let mut current_map = (None, String::new());
         let mut current_symbols = String::new();
 
@@ -321,7 +336,7 @@ mod tests {
             &mut current_symbols,
         );
 
-        assert_eq!(state.filepath, Some(PathBuf::from("/original/path"))); // should not change
+        assert_eq!(state.view.filepath, Some(PathBuf::from("/original/path"))); // should not change
     }
 
     #[rstest]
@@ -340,7 +355,7 @@ mod tests {
 
         stream_output("~", input, &mut state, &mut current_map, &mut current_symbols);
 
-        assert_eq!(state.source_language, Some(expected_lang.to_string()));
+        assert_eq!(state.view.source_language, Some(expected_lang.to_string()));
         assert_eq!(state.output.len(), 0); // should not be added to output
     }
 }
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::parse_number;
+
+/// Name given to an address, or to the `len` bytes from it, with `label` or a hexdump bookmark
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub addr: u64,
+    /// 0 for a single address
+    pub len: u64,
+    pub name: String,
+}
+
+impl Label {
+    pub fn contains(&self, addr: u64) -> bool {
+        addr >= self.addr && addr - self.addr < self.len.max(1)
+    }
+
+    /// The `label` command that recreates it
+    pub fn command(&self) -> String {
+        if self.len == 0 {
+            format!("label 0x{:x} {}", self.addr, self.name)
+        } else {
+            format!("label 0x{:x} 0x{:x} {}", self.addr, self.len, self.name)
+        }
+    }
+}
+
+/// Label of `<addr> [<len>] <name>`, the arguments of `label`
+pub fn parse_label(args: &str) -> Result<Label, String> {
+    const USAGE: &str = "expected 'label <addr> [<len>] <name>'";
+    let split: Vec<&str> = args.split_whitespace().collect();
+    let (addr, len, name) = match split[..] {
+        [addr, name] => (addr, None, name),
+        [addr, len, name] => (addr, Some(len), name),
+        _ => return Err(USAGE.to_string()),
+    };
+    let addr = parse_number(addr).ok_or_else(|| format!("invalid address {addr}"))?;
+    let len = match len {
+        Some(len) => parse_number(len).ok_or_else(|| format!("invalid length {len}"))?,
+        None => 0,
+    };
+    check_name(name)?;
+    Ok(Label { addr, len, name: name.to_string() })
+}
+
+/// A name is a single word that can't be mistaken for an address
+pub fn check_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.contains(char::is_whitespace) || parse_number(name).is_some() {
+        return Err(format!("invalid label name {name}"));
+    }
+    Ok(())
+}
+
+/// User labels, at most one per address and per name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Labels {
+    by_addr: BTreeMap<u64, Label>,
+}
+
+impl Labels {
+    /// Add `label`, replacing the label at its address and the previous one of its name
+    pub fn insert(&mut self, label: Label) {
+        self.remove(&label.name);
+        self.by_addr.insert(label.addr, label);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Label> {
+        let addr = self.get(name)?.addr;
+        self.by_addr.remove(&addr)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Label> {
+        self.by_addr.values().find(|label| label.name == name)
+    }
+
+    /// Labels by address
+    pub fn iter(&self) -> impl Iterator<Item = &Label> {
+        self.by_addr.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_addr.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_addr.is_empty()
+    }
+
+    /// Name of the closest label covering `addr`, as `name+0xoff` inside a range
+    pub fn describe(&self, addr: u64) -> Option<String> {
+        let label = self.by_addr.range(..=addr).rev().map(|(_, l)| l).find(|l| l.contains(addr))?;
+        Some(if label.addr == addr {
+            label.name.clone()
+        } else {
+            format!("{}+0x{:x}", label.name, addr - label.addr)
+        })
+    }
+
+    /// Labels starting in `range`
+    pub fn starting_in(&self, range: Range<u64>) -> impl Iterator<Item = &Label> {
+        self.by_addr.range(range).map(|(_, label)| label)
+    }
+
+    /// `label` commands of every label, one per line, for `label-save`
+    pub fn commands(&self) -> String {
+        self.iter().map(|label| format!("{}\n", label.command())).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_label() {
+        let label = parse_label("0x20001000 0x100 rx_ring").unwrap();
+        assert_eq!(label, Label { addr: 0x20001000, len: 0x100, name: "rx_ring".to_string() });
+        assert_eq!(parse_label(" 4096 start ").unwrap().len, 0);
+        assert!(parse_label("0x1000").is_err());
+        assert!(parse_label("0x1000 0x20").is_err());
+        assert!(parse_label("nope name").is_err());
+        assert_eq!(label.command(), "label 0x20001000 0x100 rx_ring");
+    }
+
+    #[test]
+    fn test_labels() {
+        let mut labels = Labels::default();
+        labels.insert(parse_label("0x1000 0x100 rx_ring").unwrap());
+        labels.insert(parse_label("0x1040 head").unwrap());
+        labels.insert(parse_label("0x2000 tx_ring").unwrap());
+        assert_eq!(labels.describe(0x1000).as_deref(), Some("rx_ring"));
+        assert_eq!(labels.describe(0x1010).as_deref(), Some("rx_ring+0x10"));
+        // the closest label wins
+        assert_eq!(labels.describe(0x1040).as_deref(), Some("head"));
+        assert_eq!(labels.describe(0x1041).as_deref(), Some("rx_ring+0x41"));
+        assert_eq!(labels.describe(0x1100), None);
+        assert_eq!(labels.describe(0x2001), None);
+        let names: Vec<&str> =
+            labels.starting_in(0x1000..0x1041).map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["rx_ring", "head"]);
+
+        // renamed and moved
+        labels.insert(parse_label("0x2000 rx_ring").unwrap());
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels.describe(0x1010), None);
+        assert_eq!(labels.commands(), "label 0x1040 head\nlabel 0x2000 rx_ring\n");
+        assert_eq!(labels.remove("head").map(|l| l.addr), Some(0x1040));
+        assert_eq!(labels.remove("head"), None);
+    }
+}
@@ -14,16 +14,17 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::zombie_processes)]
 
-use std::collections::{BTreeMap, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::fs::{self, File};
 use std::io;
 use std::io::{BufReader, Read, Write};
 use std::net::{SocketAddr, TcpStream};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use std::{env, thread};
+use std::{env, mem, thread};
 
 use anyhow::Context;
 use clap::{Parser, ValueEnum};
@@ -48,16 +49,56 @@ use register::RegisterStorage;
 use tui_input::Input;
 use tui_input::backend::crossterm::EventHandler;
 
-use mi::{Asm, MemoryMapping, data_read_memory_bytes};
-use ui::hexdump::{HEXDUMP_WIDTH, display_index_of_row};
+use diff::{MemoryDiff, SNAPSHOT_MAX_LEN, differences};
+use export::{ExportFormat, identifier};
+use flags::{FlagsLayout, builtin_layout};
+use labels::{Labels, check_name, parse_label};
+use memory::{MemoryLoad, PagedMemory};
+use mi::{
+    Asm, Breakpoint, MemoryMapping, Thread, WatchKind, WatchpointHit, data_read_memory_bytes,
+};
+use overlay::TypeOverlay;
+use search::{FindJob, HexdumpSearch, Pattern, find_chunks};
+use stack::{
+    CanarySlot, DEFAULT_STACK_SLOTS, FP_REGS, FrameLayout, Growth, MAX_FRAME_LAYOUTS, PC_REGS,
+    STACK_HIGH_SYMBOLS, STACK_LOW_SYMBOLS, STACK_PAGE, StackDepth, StackEnd, StackModel,
+    canary_expression, canary_slots, frame_canary_slots, parse_stack_depth, smashed_slots,
+};
+use ui::hexdump::{
+    Geometry, HEXDUMP_WIDTH, HexdumpLayout, RowWidth, display_index_keeping, display_index_of_row,
+    row_at_display_index,
+};
 
 mod deref;
+mod diff;
+mod export;
+mod flags;
 mod gdb;
 mod inferior;
+mod labels;
+mod memory;
 mod mi;
+mod overlay;
 mod register;
+mod search;
+mod stack;
 mod ui;
 
+/// Name prefix of the variable objects of the expression inspector
+const INSPECTOR_VAR_PREFIX: &str = "inspect";
+
+/// Bytes shown when following a pointer outside of any mapping into the hexdump
+const HEXDUMP_FOLLOW_LEN: u64 = 0x1000;
+
+const FIND_USAGE: &str = "expected 'find <mapping> <pattern>' or 'find <addr> <len> <pattern>'";
+
+const SNAPSHOT_USAGE: &str = "expected 'snapshot-mem <name> <addr> <len>'";
+
+const DIFF_USAGE: &str = "expected 'diff-mem <name>' or 'diff-mem <name> <name>'";
+
+/// Match addresses listed in the output by the `find` command
+const FIND_MATCHES_SHOWN: usize = 16;
+
 #[derive(Debug, Copy, Clone)]
 enum InputMode {
     Normal,
@@ -75,6 +116,14 @@ fn resolve_home(path: &str) -> Option<PathBuf> {
     }
 }
 
+/// Number in hex with a `0x` prefix, or decimal
+fn parse_number(val: &str) -> Option<u64> {
+    match val.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => val.parse().ok(),
+    }
+}
+
 #[derive(Debug, Clone)]
 struct LimitedBuffer<T> {
     offset: usize,
@@ -148,6 +197,8 @@ enum PtrSize {
 enum Mode {
     All,
     OnlyRegister,
+    /// New value of the selected register
+    OnlyRegisterPopup,
     OnlyStack,
     OnlyInstructions,
     OnlyOutput,
@@ -155,8 +206,36 @@ enum Mode {
     OnlyHexdump,
     OnlyHexdumpPopup,
     OnlyHexdumpGotoPopup,
+    /// Cursor in the hexdump grid, typed nibbles or characters are pending edits
+    OnlyHexdumpEdit,
+    /// Cursor in the hexdump grid extending a selection from where it started
+    OnlyHexdumpSelect,
+    OnlyHexdumpSearchPopup,
+    /// Two memory snapshots, or a snapshot and live memory, side by side
+    OnlyHexdumpDiff,
+    /// Path and address of a file to load into memory
+    OnlyHexdumpLoadPopup,
+    /// Preview of the bytes a file load overwrites, before writing it
+    OnlyHexdumpLoadConfirm,
+    /// Name of a C type to overlay on the hexdump from the cursor
+    OnlyHexdumpTypePopup,
+    /// Name of a bookmark at the hexdump cursor
+    OnlyHexdumpLabelPopup,
+    /// Every label, to jump to one in the hexdump
+    OnlyHexdumpBookmarks,
     OnlySymbols,
     OnlySource,
+    OnlyWatchpoints,
+    OnlyWatchpointsPopup,
+    OnlyThreads,
+    OnlyBacktrace,
+    OnlyLocals,
+    OnlyLocalsPopup,
+    OnlyExpressionsPopup,
+    /// Expression inspector over the `previous_mode`, with the result tree focused
+    Inspector,
+    /// Expression inspector over the `previous_mode`, with the expression input focused
+    InspectorInput,
     QuitConfirmation,
     Help,
 }
@@ -175,6 +254,10 @@ impl Mode {
             6 => Mode::OnlyHexdump,
             7 => Mode::OnlySymbols,
             8 => Mode::OnlySource,
+            9 => Mode::OnlyWatchpoints,
+            10 => Mode::OnlyThreads,
+            11 => Mode::OnlyBacktrace,
+            12 => Mode::OnlyLocals,
             _ => return None,
         })
     }
@@ -183,6 +266,7 @@ impl Mode {
         match self {
             Mode::All => 0,
             Mode::OnlyRegister => 1,
+            Mode::OnlyRegisterPopup => 1,
             Mode::OnlyStack => 2,
             Mode::OnlyInstructions => 3,
             Mode::OnlyOutput => 4,
@@ -190,8 +274,26 @@ impl Mode {
             Mode::OnlyHexdump => 6,
             Mode::OnlyHexdumpPopup => 6,
             Mode::OnlyHexdumpGotoPopup => 6,
+            Mode::OnlyHexdumpEdit => 6,
+            Mode::OnlyHexdumpSelect => 6,
+            Mode::OnlyHexdumpSearchPopup => 6,
+            Mode::OnlyHexdumpDiff => 6,
+            Mode::OnlyHexdumpLoadPopup => 6,
+            Mode::OnlyHexdumpLoadConfirm => 6,
+            Mode::OnlyHexdumpTypePopup => 6,
+            Mode::OnlyHexdumpLabelPopup => 6,
+            Mode::OnlyHexdumpBookmarks => 6,
             Mode::OnlySymbols => 7,
             Mode::OnlySource => 8,
+            Mode::OnlyWatchpoints => 9,
+            Mode::OnlyWatchpointsPopup => 9,
+            Mode::OnlyThreads => 10,
+            Mode::OnlyBacktrace => 11,
+            Mode::OnlyLocals => 12,
+            Mode::OnlyLocalsPopup => 12,
+            Mode::OnlyExpressionsPopup => 12,
+            Mode::Inspector => 0,
+            Mode::InspectorInput => 0,
             Mode::QuitConfirmation => 0,
             Mode::Help => 0,
         }
@@ -201,6 +303,7 @@ impl Mode {
         match self {
             Mode::All => Mode::OnlyRegister,
             Mode::OnlyRegister => Mode::OnlyStack,
+            Mode::OnlyRegisterPopup => Mode::OnlyRegisterPopup,
             Mode::OnlyStack => Mode::OnlyInstructions,
             Mode::OnlyInstructions => Mode::OnlyOutput,
             Mode::OnlyOutput => Mode::OnlyMapping,
@@ -208,8 +311,26 @@ impl Mode {
             Mode::OnlyHexdump => Mode::OnlySymbols,
             Mode::OnlyHexdumpPopup => Mode::OnlyHexdumpPopup,
             Mode::OnlyHexdumpGotoPopup => Mode::OnlyHexdumpGotoPopup,
+            Mode::OnlyHexdumpEdit => Mode::OnlyHexdumpEdit,
+            Mode::OnlyHexdumpSelect => Mode::OnlyHexdumpSelect,
+            Mode::OnlyHexdumpSearchPopup => Mode::OnlyHexdumpSearchPopup,
+            Mode::OnlyHexdumpDiff => Mode::OnlyHexdumpDiff,
+            Mode::OnlyHexdumpLoadPopup => Mode::OnlyHexdumpLoadPopup,
+            Mode::OnlyHexdumpLoadConfirm => Mode::OnlyHexdumpLoadConfirm,
+            Mode::OnlyHexdumpTypePopup => Mode::OnlyHexdumpTypePopup,
+            Mode::OnlyHexdumpLabelPopup => Mode::OnlyHexdumpLabelPopup,
+            Mode::OnlyHexdumpBookmarks => Mode::OnlyHexdumpBookmarks,
             Mode::OnlySymbols => Mode::OnlySource,
-            Mode::OnlySource => Mode::All,
+            Mode::OnlySource => Mode::OnlyWatchpoints,
+            Mode::OnlyWatchpoints => Mode::OnlyThreads,
+            Mode::OnlyThreads => Mode::OnlyBacktrace,
+            Mode::OnlyBacktrace => Mode::OnlyLocals,
+            Mode::OnlyLocals => Mode::All,
+            Mode::OnlyLocalsPopup => Mode::OnlyLocalsPopup,
+            Mode::OnlyExpressionsPopup => Mode::OnlyExpressionsPopup,
+            Mode::Inspector => Mode::Inspector,
+            Mode::InspectorInput => Mode::InspectorInput,
+            Mode::OnlyWatchpointsPopup => Mode::OnlyWatchpointsPopup,
             Mode::QuitConfirmation => Mode::QuitConfirmation,
             Mode::Help => Mode::Help,
         }
@@ -218,8 +339,235 @@ impl Mode {
 
 #[derive(Debug, Default, Clone)]
 struct Bt {
-    location: u64,
-    function: Option<String>,
+    frame: mi::Frame,
+    /// From `-stack-list-arguments`, which is answered after `-stack-list-frames`
+    args: Vec<mi::Variable>,
+}
+
+/// Row of the locals tree, a variable object of the selected frame
+#[derive(Debug, Default, Clone)]
+struct Local {
+    var: mi::VarObj,
+    /// 0 for the locals and args of the frame, +1 for each expanded level
+    depth: usize,
+    arg: bool,
+    expanded: bool,
+    /// Value changed since the last stop, from `-var-update`
+    changed: bool,
+}
+
+/// Rows of a variable object tree below the row at `index`
+fn tree_descendants(tree: &[Local], index: usize) -> std::ops::Range<usize> {
+    let depth = tree[index].depth;
+    let len = tree[index + 1..].iter().take_while(|l| l.depth > depth).count();
+    index + 1..index + 1 + len
+}
+
+/// Remove the children rows of the row at `index`
+fn collapse_row(tree: &mut Vec<Local>, index: usize) {
+    tree[index].expanded = false;
+    let children = tree_descendants(tree, index);
+    tree.drain(children);
+}
+
+/// Expand or collapse the row at `index`, returning the command to list the children once
+/// expanded
+fn toggle_row(tree: &mut Vec<Local>, index: usize) -> Option<String> {
+    let row = tree.get_mut(index)?;
+    if row.expanded {
+        collapse_row(tree, index);
+        None
+    } else if row.var.numchild > 0 {
+        row.expanded = true;
+        Some(mi::var_list_children(&row.var.name))
+    } else {
+        None
+    }
+}
+
+/// Display format of an expression value
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum ValueFormat {
+    /// As printed by gdb
+    #[default]
+    Natural,
+    Hex,
+    Decimal,
+    Binary,
+    Char,
+}
+
+impl ValueFormat {
+    pub fn next(self) -> Self {
+        match self {
+            ValueFormat::Natural => ValueFormat::Hex,
+            ValueFormat::Hex => ValueFormat::Decimal,
+            ValueFormat::Decimal => ValueFormat::Binary,
+            ValueFormat::Binary => ValueFormat::Char,
+            ValueFormat::Char => ValueFormat::Natural,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ValueFormat::Natural => "nat",
+            ValueFormat::Hex => "hex",
+            ValueFormat::Decimal => "dec",
+            ValueFormat::Binary => "bin",
+            ValueFormat::Char => "char",
+        }
+    }
+
+    /// Format the integer at the start of a gdb `value`, such as "65 'A'" or
+    /// "0x401136 <main>". Other values are kept as is
+    pub fn apply(self, value: &str) -> String {
+        let token = value.split_whitespace().next().unwrap_or_default();
+        let int = match token.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => token.parse::<i64>().ok().map(|i| i as u64),
+        };
+        let Some(int) = int.filter(|_| self != ValueFormat::Natural) else {
+            return value.to_string();
+        };
+        match self {
+            ValueFormat::Natural => unreachable!(),
+            ValueFormat::Hex => format!("{int:#x}"),
+            ValueFormat::Decimal => format!("{}", int as i64),
+            ValueFormat::Binary => format!("{int:#b}"),
+            // only a single byte is a char, wider values are kept as is
+            ValueFormat::Char => match u8::try_from(int) {
+                Ok(c) => format!("'{}'", c.escape_ascii()),
+                Err(_) => value.to_string(),
+            },
+        }
+    }
+}
+
+/// User expression of the expressions pane, a floating variable object re-evaluated in the
+/// selected frame on every stop
+#[derive(Debug, Default, Clone)]
+struct Expression {
+    /// Name of the variable object
+    name: String,
+    /// As typed by the user
+    text: String,
+    /// `text` with the internal variables and math resolved, once the variable object is created
+    resolved: Option<String>,
+    type_: Option<String>,
+    value: Option<String>,
+    format: ValueFormat,
+    /// Value changed since the last stop, from `-var-update`
+    changed: bool,
+    /// Message of a failed `-var-create`
+    error: Option<String>,
+}
+
+/// Program state that belongs to one inferior. The selected inferior keeps it in `State::view`,
+/// the others have it parked in `Inferior::view` until they are selected again
+#[derive(Debug, Default, Clone)]
+struct InferiorView {
+    /// -32 bit mode
+    ptr_size: PtrSize,
+    /// Current filepath of .text
+    filepath: Option<PathBuf>,
+    /// Current endian
+    endian: Option<Endian>,
+    /// Memory map TUI
+    memory_map: Option<Vec<MemoryMapping>>,
+    /// Current $pc
+    current_pc: u64, // TODO: replace with AtomicU64?
+    /// Register TUI
+    register_changed: Vec<u16>,
+    register_names: Vec<String>,
+    registers: Vec<RegisterStorage>,
+    /// Registers assigned since the stop, by name
+    registers_edited: BTreeSet<String>,
+    /// Values of the registers at the previous stop, to tell which of their bits changed
+    registers_previous: HashMap<String, u64>,
+    /// Bit fields of the registers from the target description, by name
+    register_layouts: HashMap<String, FlagsLayout>,
+    /// Saved Stack
+    stack: BTreeMap<u64, Deref>,
+    /// Stack pointer, growth and bounds of the stack, from `stack-model` and the linker symbols
+    stack_model: StackModel,
+    /// Frames of the backtrace as laid out on the stack, by level
+    frame_layouts: Vec<FrameLayout>,
+    /// Value of the stack protector canary, read at each stop
+    canary: Option<u64>,
+    /// Slots that held the canary at a previous stop, in frames still on the stack
+    canary_slots: Vec<CanarySlot>,
+    /// gdb has no symbol for the canary expression, which is not evaluated again until another
+    /// file is loaded
+    canary_missing: bool,
+    /// Stack slots that look smashed, with why, from [`State::update_smashed_stack_slots`]
+    smashed: BTreeMap<u64, String>,
+    /// Saved ASM
+    asm: Vec<Asm>,
+    /// Hexdump
+    hexdump: Option<PagedMemory>,
+    hexdump_scroll: Scroll,
+    /// Byte offset into `hexdump` of the edit cursor
+    hexdump_cursor: usize,
+    /// The edit cursor is in the ascii column, instead of the hex bytes
+    hexdump_cursor_ascii: bool,
+    /// The high nibble of the byte under the cursor was typed, the low one is next
+    hexdump_low_nibble: bool,
+    /// Pending edits by byte offset into `hexdump`, until written with `-data-write-memory-bytes`
+    hexdump_edits: BTreeMap<usize, u8>,
+    /// Byte offsets of written edits that did not read back, such as writes to flash
+    hexdump_failed: BTreeSet<usize>,
+    /// Byte offsets into `hexdump` that differ from the buffer read at the previous stop
+    hexdump_changed: BTreeSet<usize>,
+    /// Byte offset into `hexdump` where the selection started, it ends at the cursor
+    hexdump_selection: Option<usize>,
+    /// Matches of the last search of the hexdump, searched again for every new buffer
+    hexdump_search: Option<HexdumpSearch>,
+    /// Fields of a C type colored over the hexdump, once its layout is read
+    type_overlay: Option<TypeOverlay>,
+    /// File being loaded into target memory
+    memory_load: Option<MemoryLoad>,
+    bt: Vec<Bt>,
+    /// Current source file and line info
+    current_source_file: Option<String>,
+    current_source_line: Option<u32>,
+    source_lines: Vec<String>,
+    /// Current source language detected by GDB
+    source_language: Option<String>,
+    /// Locals TUI, the tree of variable objects flattened with children after their parent
+    locals: Vec<Local>,
+    /// Frame the `locals` were created in, variable objects are deleted once it changes
+    locals_frame: Option<String>,
+    /// Expressions TUI, next to the locals
+    expressions: Vec<Expression>,
+    /// Expression inspector, the result tree of `inspector_input`
+    inspector: Vec<Local>,
+    /// Deref chain of the pointer being telescoped
+    inspector_telescope: Option<(u64, Deref)>,
+}
+
+/// Inferior of gdb, known from the `=thread-group-*` notifications
+#[derive(Debug, Default, Clone)]
+struct Inferior {
+    /// Thread-group id, such as "i1"
+    id: String,
+    /// Process id while it runs
+    pid: Option<u64>,
+    /// Exit code of the last run
+    exit_code: Option<String>,
+    /// Ids of its threads, from `=thread-created`
+    threads: Vec<String>,
+    view: InferiorView,
+}
+
+impl Inferior {
+    fn new(id: &str) -> Self {
+        Self { id: id.to_string(), ..Self::default() }
+    }
+
+    /// Number of the inferior for the cli, "2" for "i2"
+    fn num(&self) -> &str {
+        self.id.trim_start_matches('i')
+    }
 }
 
 #[derive(Debug, Default, Clone)]
@@ -289,6 +637,16 @@ impl Scroll {
         self.scroll = pos.min(self.max_scroll);
         self.state = self.state.position(self.scroll);
     }
+
+    /// Scroll the least amount needed for row `pos` to be inside a viewport
+    /// of `height` rows
+    pub fn keep_visible(&mut self, pos: usize, height: usize) {
+        if pos < self.scroll {
+            self.set(pos);
+        } else if height != 0 && pos >= self.scroll + height {
+            self.set(pos + 1 - height);
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -299,12 +657,8 @@ struct State {
     written: VecDeque<Written>,
     /// Waiting for execution to stop (after si, continue, step, run, etc.)
     executing: bool,
-    /// -32 bit mode
-    ptr_size: PtrSize,
-    /// Current filepath of .text
-    filepath: Option<PathBuf>,
-    /// Current endian
-    endian: Option<Endian>,
+    /// Program state of the selected inferior
+    view: InferiorView,
     /// Current mode
     mode: Mode,
     /// Previous mode (for quit confirmation)
@@ -316,15 +670,12 @@ struct State {
     /// List of previously sent commands from our own input
     sent_input: LimitedBuffer<String>,
     /// Memory map TUI
-    memory_map: Option<Vec<MemoryMapping>>,
     memory_map_scroll: Scroll,
     memory_map_selected: usize,
     memory_map_viewport_height: u16,
     /// Clickable tab regions in the title bar: (row, x_start, x_end_exclusive)
     /// per tab index. Populated on each render for mouse hit-testing
     tab_regions: Vec<(u16, u16, u16)>,
-    /// Current $pc
-    current_pc: u64, // TODO: replace with AtomicU64?
     /// All output from gdb
     output: Vec<String>,
     output_scroll: Scroll,
@@ -333,30 +684,105 @@ struct State {
     /// Saved output such as (gdb) or > from gdb
     stream_output_prompt: String,
     /// Register TUI
-    register_changed: Vec<u16>,
-    register_names: Vec<String>,
-    registers: Vec<RegisterStorage>,
     registers_scroll: Scroll,
-    /// Saved Stack
-    stack: BTreeMap<u64, Deref>,
-    /// Saved ASM
-    asm: Vec<Asm>,
-    /// Hexdump
-    hexdump: Option<(u64, Vec<u8>)>,
-    hexdump_scroll: Scroll,
+    /// Row of the Registers pane selected to edit
+    registers_selected: usize,
+    registers_popup: Input,
+    /// How far past `$sp` the Stack pane reads, from `stack-depth`
+    stack_depth: StackDepth,
+    /// Slots past `$sp` requested since the stop, more are read as the Stack pane scrolls
+    stack_slots: u64,
+    stack_scroll: Scroll,
+    /// Rows of the Stack pane, from the last draw
+    stack_viewport_height: u16,
+    /// Canonical frame address and level of the frame scrolled to with `f`
+    stack_cfa: Option<(u64, usize)>,
+    /// Slot address to scroll to once it is read
+    stack_goto: Option<u64>,
     hexdump_popup: Input,
+    /// Format the save popup writes, cycled with Tab
+    hexdump_export: ExportFormat,
     hexdump_goto_popup: Input,
+    hexdump_load_popup: Input,
+    hexdump_type_popup: Input,
+    /// Names of addresses and ranges from `label` and the hexdump bookmarks
+    labels: Labels,
+    label_popup: Input,
+    /// Highlighted row of the bookmarks list, an index into `labels`
+    bookmarks_selected: usize,
+    /// Only show the hexdump rows with changed bytes
+    hexdump_changes_only: bool,
+    /// Rows of the hexdump pane, from the last draw
+    hexdump_viewport_height: u16,
+    hexdump_layout: HexdumpLayout,
+    /// Bytes per row, the fixed width of `hexdump_layout` or the auto width of the last draw
+    hexdump_width: usize,
+    hexdump_search_popup: Input,
+    /// Regions captured by `snapshot-mem`, by name
+    snapshots: BTreeMap<String, PagedMemory>,
+    /// Comparison opened by `diff-mem`
+    memory_diff: Option<MemoryDiff>,
+    /// Running `find` command
+    find_job: Option<FindJob>,
+    /// Id of the next `find` command
+    find_id: usize,
     /// Last async status reported by gdb, shown in the status bar
     async_result: String,
-    bt: Vec<Bt>,
+    /// Frame level selected in gdb, with `-stack-select-frame`
+    bt_selected: usize,
+    /// Highlighted row of the backtrace pane
+    bt_cursor: usize,
+    bt_scroll: Scroll,
     completions: Vec<String>,
-    /// Current source file and line info
-    current_source_file: Option<String>,
-    current_source_line: Option<u32>,
-    source_lines: Vec<String>,
     source_scroll: Scroll,
-    /// Current source language detected by GDB
-    source_language: Option<String>,
+    /// 0-indexed line of the source cursor, used for breakpoints and run-to
+    source_cursor: usize,
+    /// Index into `asm` of the instruction cursor, None follows $pc
+    asm_cursor: Option<usize>,
+    /// Breakpoints known to gdb, kept in sync by `=breakpoint-*` notifications
+    breakpoints: Vec<Breakpoint>,
+    /// Watchpoints TUI, the watchpoints are kept within `breakpoints`
+    watchpoints_selected: usize,
+    watchpoints_scroll: Scroll,
+    /// Every watchpoint trigger, oldest first
+    watchpoint_hits: Vec<WatchpointHit>,
+    watchpoint_hits_scroll: Scroll,
+    /// Expression input for a new watchpoint
+    watchpoint_popup: Input,
+    watchpoint_popup_kind: WatchKind,
+    /// Threads TUI, from `-thread-info` and `=thread-*` notifications
+    threads: Vec<Thread>,
+    threads_selected: usize,
+    threads_scroll: Scroll,
+    /// Thread gdb reads registers and memory from
+    current_thread_id: Option<String>,
+    /// Locals TUI
+    locals_selected: usize,
+    locals_scroll: Scroll,
+    /// Value input for `-var-assign`
+    locals_popup: Input,
+    /// Suffix of the next variable object name
+    var_obj_id: usize,
+    /// Expressions TUI, next to the locals
+    expressions_selected: usize,
+    expressions_scroll: Scroll,
+    /// Keys of the Locals tab go to the expressions pane
+    expressions_focus: bool,
+    /// Expression input for a new expression
+    expressions_popup: Input,
+    /// Expression inspector
+    inspector_selected: usize,
+    inspector_scroll: Scroll,
+    inspector_input: Input,
+    /// Past expressions of the inspector, separate from the command history
+    inspector_history: LimitedBuffer<String>,
+    /// Message of a failed inspection
+    inspector_error: Option<String>,
+    /// Inferiors of gdb, the program state of the selected one is kept in `view`
+    inferiors: Vec<Inferior>,
+    inferior_selected: usize,
+    /// Clickable inferior regions in the title bar, like `tab_regions`
+    inferior_regions: Vec<(u16, u16, u16)>,
     /// Symbol browser
     symbols: Vec<Symbol>,
     symbols_scroll: Scroll,
@@ -379,42 +805,81 @@ impl State {
             next_write: vec![],
             written: VecDeque::new(),
             executing: false,
-            ptr_size: args.ptr_size,
-            filepath: None,
-            endian: None,
+            view: InferiorView { ptr_size: args.ptr_size, ..InferiorView::default() },
             mode: Mode::All,
             previous_mode: Mode::All,
             input: Input::default(),
             input_mode: InputMode::Normal,
             sent_input: LimitedBuffer::new(100),
-            memory_map: None,
             memory_map_scroll: Scroll::default(),
             memory_map_selected: 0,
             memory_map_viewport_height: 0,
             tab_regions: Vec::new(),
-            current_pc: 0,
             output: Vec::new(),
             output_scroll: Scroll::default(),
             output_prev_len: 0,
             stream_output_prompt: String::new(),
-            register_changed: vec![],
-            register_names: vec![],
-            registers: vec![],
             registers_scroll: Scroll::default(),
-            stack: BTreeMap::new(),
-            asm: Vec::new(),
-            hexdump: None,
-            hexdump_scroll: Scroll::default(),
+            registers_selected: 0,
+            registers_popup: Input::default(),
+            stack_depth: StackDepth::default(),
+            stack_slots: 0,
+            stack_scroll: Scroll::default(),
+            stack_viewport_height: 0,
+            stack_cfa: None,
+            stack_goto: None,
             hexdump_popup: Input::default(),
+            hexdump_export: ExportFormat::default(),
             hexdump_goto_popup: Input::default(),
+            hexdump_load_popup: Input::default(),
+            hexdump_type_popup: Input::default(),
+            labels: Labels::default(),
+            label_popup: Input::default(),
+            bookmarks_selected: 0,
+            hexdump_changes_only: false,
+            hexdump_viewport_height: 0,
+            hexdump_layout: HexdumpLayout::default(),
+            hexdump_width: HEXDUMP_WIDTH,
+            hexdump_search_popup: Input::default(),
+            snapshots: BTreeMap::new(),
+            memory_diff: None,
+            find_job: None,
+            find_id: 0,
             async_result: String::new(),
-            bt: vec![],
+            bt_selected: 0,
+            bt_cursor: 0,
+            bt_scroll: Scroll::default(),
             completions: vec![],
-            current_source_file: None,
-            current_source_line: None,
-            source_lines: Vec::new(),
             source_scroll: Scroll::default(),
-            source_language: None,
+            source_cursor: 0,
+            asm_cursor: None,
+            breakpoints: Vec::new(),
+            watchpoints_selected: 0,
+            watchpoints_scroll: Scroll::default(),
+            watchpoint_hits: Vec::new(),
+            watchpoint_hits_scroll: Scroll::default(),
+            watchpoint_popup: Input::default(),
+            watchpoint_popup_kind: WatchKind::Write,
+            threads: Vec::new(),
+            threads_selected: 0,
+            threads_scroll: Scroll::default(),
+            current_thread_id: None,
+            locals_selected: 0,
+            locals_scroll: Scroll::default(),
+            locals_popup: Input::default(),
+            var_obj_id: 0,
+            expressions_selected: 0,
+            expressions_scroll: Scroll::default(),
+            expressions_focus: false,
+            expressions_popup: Input::default(),
+            inspector_selected: 0,
+            inspector_scroll: Scroll::default(),
+            inspector_input: Input::default(),
+            inspector_history: LimitedBuffer::new(100),
+            inspector_error: None,
+            inferiors: vec![Inferior::new("i1")],
+            inferior_selected: 0,
+            inferior_regions: Vec::new(),
             symbols: Vec::new(),
             symbols_scroll: Scroll::default(),
             symbols_selected: 0,
@@ -491,24 +956,248 @@ impl State {
         let filepath: Vec<&str> = val.split_whitespace().collect();
         if filepath.len() > 1 {
             let filepath = resolve_home(filepath[1]).unwrap();
-            self.filepath = Some(filepath);
+            self.view.filepath = Some(filepath);
+            // the linker symbols of the stack are looked up again in the new file
+            self.view.stack_model.symbols_read = false;
+            self.view.stack_model.linker_low = None;
+            self.view.stack_model.linker_high = None;
+            self.view.canary_missing = false;
         }
     }
 
     pub fn find_first_heap(&mut self) -> Option<MemoryMapping> {
-        if let Some(memory_map) = self.memory_map.clone() {
+        if let Some(memory_map) = self.view.memory_map.clone() {
             memory_map.iter().find(|a| a.is_heap()).cloned()
         } else {
             None
         }
     }
 
+    /// Bytes of a stack slot
+    pub fn stack_slot_len(&self) -> u64 {
+        if self.view.ptr_size == PtrSize::Size32 { 4 } else { 8 }
+    }
+
+    /// Value of the stack pointer, from the registers of the last stop. An expression of the
+    /// stack model that is not a register is the first slot read from it
+    pub fn stack_pointer(&self) -> Option<u64> {
+        let names = self.view.stack_model.sp_registers();
+        if names.is_empty() {
+            return match self.view.stack_model.growth {
+                Growth::Down => self.view.stack.keys().next().copied(),
+                Growth::Up => self.view.stack.keys().next_back().copied(),
+            };
+        }
+        let register = self.view.registers.iter().find(|r| names.contains(&r.name.as_str()))?;
+        parse_number(register.register.as_ref()?.value.as_ref()?)
+    }
+
+    /// Addresses of the stack, from the stack model, the `[stack]` mapping or the linker symbols
+    pub fn stack_bounds(&self) -> Option<Range<u64>> {
+        let mapping = self.find_first_stack().map(|m| m.start_address..m.end_address);
+        self.view.stack_model.stack_bounds(mapping, self.stack_pointer())
+    }
+
+    /// Slots past `$sp` the Stack pane reads up to
+    pub fn stack_limit(&self) -> u64 {
+        match self.stack_depth {
+            StackDepth::Slots(slots) => slots,
+            StackDepth::Mapping => match (self.stack_bounds(), self.stack_pointer()) {
+                (Some(bounds), Some(sp)) if bounds.contains(&sp) => {
+                    match self.view.stack_model.growth {
+                        Growth::Down => (bounds.end - sp) / self.stack_slot_len(),
+                        Growth::Up => (sp - bounds.start) / self.stack_slot_len() + 1,
+                    }
+                }
+                _ => DEFAULT_STACK_SLOTS,
+            },
+        }
+    }
+
+    /// Request the slots past `$sp` up to `until`, rounded up to a [`STACK_PAGE`] and within
+    /// [`Self::stack_limit`], that were not requested since the stop
+    pub fn read_stack_slots(&mut self, until: u64) {
+        let until = (until.div_ceil(STACK_PAGE) * STACK_PAGE).min(self.stack_limit());
+        if until <= self.stack_slots {
+            return;
+        }
+        let size = self.stack_slot_len();
+        gdb::dump_sp_bytes(self, size, self.stack_slots, until - self.stack_slots);
+        self.stack_slots = until;
+    }
+
+    /// Read the slots past `$sp` at a stop, as many as were scrolled through before, and the
+    /// canary to find them
+    pub fn read_stack(&mut self) {
+        self.stack_slots = 0;
+        let shown = self.stack_scroll.scroll + self.stack_viewport_height as usize;
+        self.read_stack_slots((shown as u64).max(STACK_PAGE));
+
+        self.view.canary = None;
+        if !self.view.canary_missing {
+            let ptr_64 = self.view.ptr_size != PtrSize::Size32;
+            let expr = canary_expression(&self.view.register_names, ptr_64);
+            self.next_write.push(format!("-data-evaluate-expression {expr}"));
+            self.written.push_back(Written::StackCanary);
+        }
+    }
+
+    /// Remember the slots holding the canary before the stack read at this stop is dropped, to
+    /// tell at the next stop whether they were overwritten
+    pub fn remember_canaries(&mut self) {
+        let Some(canary) = self.view.canary else {
+            return;
+        };
+        let slots = self.view.stack.iter().filter_map(|(addr, d)| Some((*addr, *d.map.front()?)));
+        let growth = self.view.stack_model.growth;
+        self.view.canary_slots =
+            canary_slots(&self.view.canary_slots, &self.view.frame_layouts, slots, canary, growth);
+    }
+
+    /// Check the stack slots for ones that look smashed again, after the stack, the frame
+    /// layouts, the canary or the memory map changed. The status bar and the Stack pane show them
+    pub fn update_smashed_stack_slots(&mut self) {
+        let slot_value = |addr| self.view.stack.get(&addr).and_then(|d| d.map.front().copied());
+        let mappings = self.view.memory_map.as_deref().unwrap_or_default();
+        let layouts = &self.view.frame_layouts;
+        let mut canaries = self.view.canary_slots.clone();
+        for slot in frame_canary_slots(layouts, self.stack_slot_len()) {
+            if !canaries.iter().any(|c| c.addr == slot.addr) {
+                canaries.push(slot);
+            }
+        }
+        self.view.smashed = smashed_slots(
+            layouts,
+            slot_value,
+            mappings,
+            self.stack_bounds(),
+            self.view.canary,
+            &canaries,
+        );
+    }
+
+    /// Set how far the Stack pane reads, from `stack-depth <slots>|mapping`
+    pub fn set_stack_depth(&mut self, arg: &str) -> Result<(), String> {
+        if arg.trim().is_empty() {
+            let depth = match self.stack_depth {
+                StackDepth::Slots(slots) => format!("{slots} slots"),
+                StackDepth::Mapping => "the end of the stack".to_string(),
+            };
+            self.output.push(format!("h> stack depth: {depth}, {} slots", self.stack_limit()));
+            return Ok(());
+        }
+        self.stack_depth = parse_stack_depth(arg)?;
+        // the slots read past the new depth are dropped
+        let limit = self.stack_limit();
+        if self.stack_slots > limit {
+            let (growth, slot_len) = (self.view.stack_model.growth, self.stack_slot_len());
+            if let Some(sp) = self.stack_pointer() {
+                self.view.stack.retain(|addr, _| {
+                    growth.offset(sp, *addr).is_some_and(|o| o / slot_len < limit)
+                });
+            }
+            self.stack_slots = limit;
+        }
+        Ok(())
+    }
+
+    /// Ask gdb for the CFA of the frame selected in the backtrace, to scroll the Stack pane to
+    pub fn goto_stack_frame(&mut self) {
+        if self.executing || self.view.registers.is_empty() {
+            return;
+        }
+        if let Some(layout) = self.view.frame_layouts.iter().find(|l| l.level == self.bt_selected) {
+            self.scroll_stack_to(layout.cfa, layout.level);
+            return;
+        }
+        self.next_write.push(mi::info_frame(self.bt_selected));
+        self.written.push_back(Written::FrameCfa(self.bt_selected));
+    }
+
+    /// Scroll the Stack pane to `cfa` of frame `level`, reading up to it
+    pub fn scroll_stack_to(&mut self, cfa: u64, level: usize) {
+        let Some(sp) = self.stack_pointer() else {
+            return;
+        };
+        let growth = self.view.stack_model.growth;
+        let Some(offset) = growth.offset(sp, cfa) else {
+            let sp = &self.view.stack_model.sp;
+            self.output.push(format!("h> frame #{level} at 0x{cfa:x} is not past {sp}"));
+            return;
+        };
+        let slot = self.stack_slot_len();
+        let index = offset / slot;
+        if index >= self.stack_limit() {
+            self.output.push(format!(
+                "h> frame #{level} at 0x{cfa:x} is past the {} slots read, see stack-depth",
+                self.stack_limit()
+            ));
+            return;
+        }
+        self.stack_cfa = Some((cfa, level));
+        self.stack_goto = Some(growth.slot_addr(sp, index, slot));
+        self.read_stack_slots(index + u64::from(self.stack_viewport_height));
+    }
+
+    /// Read the layout of the innermost frames of a new backtrace, with `info frame` and the
+    /// addresses of their variables
+    pub fn request_frame_layouts(&mut self) {
+        self.view.frame_layouts.clear();
+        for level in 0..self.view.bt.len().min(MAX_FRAME_LAYOUTS) {
+            self.next_write.push(mi::info_frame(level));
+            self.written.push_back(Written::FrameInfo(level));
+            let thread = self.current_thread_id.as_deref();
+            self.next_write.push(mi::stack_list_locals(thread, level));
+            self.written.push_back(Written::FrameLocals(level));
+        }
+    }
+
     pub fn find_first_stack(&self) -> Option<MemoryMapping> {
-        if let Some(memory_map) = self.memory_map.clone() {
-            memory_map.iter().find(|a| a.is_stack()).cloned()
-        } else {
-            None
+        self.view.memory_map.as_ref()?.iter().find(|a| a.is_stack()).cloned()
+    }
+
+    /// Look up the linker symbols of the ends of the stack, once
+    pub fn request_stack_symbols(&mut self) {
+        self.view.stack_model.symbols_read = true;
+        let symbols = STACK_LOW_SYMBOLS
+            .iter()
+            .map(|s| (s, StackEnd::Low))
+            .chain(STACK_HIGH_SYMBOLS.iter().map(|s| (s, StackEnd::High)));
+        for (symbol, end) in symbols {
+            self.next_write.push(format!("-data-evaluate-expression (void*)&{symbol}"));
+            self.written.push_back(Written::StackSymbol(end));
+        }
+    }
+
+    /// Set the stack pointer, growth or bounds of the stack, from `stack-model`, then read the
+    /// stack again
+    pub fn set_stack_model(&mut self, arg: &str) -> Result<(), String> {
+        if arg.trim().is_empty() {
+            let model = &self.view.stack_model;
+            let bounds = match self.stack_bounds() {
+                Some(bounds) => format!(
+                    "0x{:x}..0x{:x} from {}",
+                    bounds.start,
+                    bounds.end,
+                    model.bounds_source(self.find_first_stack().is_some())
+                ),
+                None => "unknown".to_string(),
+            };
+            self.output.push(format!(
+                "h> stack: sp {}, grows {}, bounds {bounds}",
+                model.sp,
+                model.growth.name()
+            ));
+            return Ok(());
+        }
+        self.view.stack_model.apply(arg)?;
+        self.view.stack.clear();
+        self.view.smashed.clear();
+        self.stack_scroll.reset();
+        if !self.executing && !self.view.registers.is_empty() {
+            self.read_stack();
         }
+        Ok(())
     }
 
     pub fn classify_val(&self, val: u64, filepath: &str) -> (bool, bool, bool) {
@@ -516,9 +1205,13 @@ impl State {
         let mut is_heap = false;
         let mut is_text = false;
         if val != 0 {
+            // the stack model knows the stack of targets without a [stack] mapping
+            if self.stack_bounds().is_some_and(|bounds| bounds.contains(&val)) {
+                return (true, false, false);
+            }
             // look through, add see if the value is part of the stack
             // trace!("{:02x?}", memory_map);
-            if let Some(memory_map) = &self.memory_map {
+            if let Some(memory_map) = &self.view.memory_map {
                 for r in memory_map {
                     if r.contains(val) {
                         if r.is_stack() {
@@ -540,89 +1233,1319 @@ impl State {
         (is_stack, is_heap, is_text)
     }
 
-    /// Get filtered symbols based on search input
-    pub fn get_filtered_symbols(&self) -> Vec<(usize, &Symbol)> {
-        // Filter based on search input, regardless of whether search mode is active
-        if self.symbols_search_input.value().is_empty() {
-            return self.symbols.iter().enumerate().collect();
+    /// Replace the breakpoint with the same number, or add it if new
+    pub fn upsert_breakpoint(&mut self, bkpt: Breakpoint) {
+        if let Some(b) = self.breakpoints.iter_mut().find(|b| b.number == bkpt.number) {
+            *b = bkpt;
+        } else {
+            self.breakpoints.push(bkpt);
         }
-
-        let search_term = self.symbols_search_input.value().to_lowercase();
-        let mut results: Vec<(usize, &Symbol)> = self
-            .symbols
-            .iter()
-            .enumerate()
-            .filter(|(_, sym)| {
-                // Simple fuzzy matching: check if all characters in search appear in order
-                let name_lower = sym.name.to_lowercase();
-                let mut name_chars = name_lower.chars();
-                search_term.chars().all(|c| name_chars.any(|nc| nc == c))
-            })
-            .collect();
-        // Sort exact matches to the top
-        results.sort_by(|(_, a), (_, b)| {
-            let a_exact = a.name.to_lowercase() == search_term;
-            let b_exact = b.name.to_lowercase() == search_term;
-            b_exact.cmp(&a_exact)
-        });
-        results
     }
-}
 
-#[derive(Debug, Clone)]
-enum Written {
-    /// Requested Register Value deref
-    // TODO: Could this just be the register name?
-    RegisterValue((String, u64)),
-    /// Requested Stack Bytes
-    ///
-    /// None - This is the first time this is requested
-    /// Some - This has alrady been read, and this is a deref, trust
-    ///        the `base_reg` of .0
-    Stack(Option<String>),
-    /// Requested Memory Read (for hexdump)
-    Memory,
-    /// Requested Asm At $pc
-    AsmAtPc,
-    /// Requested symbol at addr for register (from deref)
-    SymbolAtAddrRegister((String, u64)),
-    /// Requested symbol at addr for stack (from deref)
-    SymbolAtAddrStack(String),
-    /// Requested size of arch ptr for `ptr_size`
-    SizeOfVoidStar,
-    /// Requested list of all symbols
-    SymbolList,
-    /// Requested disassembly of a specific symbol by name
-    SymbolDisassembly(String),
-    /// Requested address lookup for symbol (to disassemble it next)
-    SymbolAddressLookup(String),
-}
+    /// Breakpoint placed on `line` (1-indexed) of the current source file
+    pub fn breakpoint_at_line(&self, line: u32) -> Option<&Breakpoint> {
+        let file = self.view.current_source_file.as_ref()?;
+        self.breakpoints.iter().find(|b| b.is_at_line(file, line))
+    }
 
-fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
+    /// Breakpoint placed on the instruction at `addr`
+    pub fn breakpoint_at_addr(&self, addr: u64) -> Option<&Breakpoint> {
+        self.breakpoints.iter().find(|b| b.addr == Some(addr))
+    }
 
-    // initialize logging, to log_path if available
-    init_logging(args.log_path.as_ref())?;
+    /// Watchpoints within `breakpoints`, in the order gdb reported them
+    pub fn watchpoints(&self) -> Vec<&Breakpoint> {
+        self.breakpoints.iter().filter(|b| b.is_watchpoint()).collect()
+    }
 
-    // Check for valid cmd file
-    if let Some(cmds) = &args.cmds
-        && !cmds.exists()
-    {
-        anyhow::bail!("Filepath for --cmds does not exist: `{}`", cmds.display());
+    /// Watchpoint hits shown in the history, only those of the selected watchpoint
+    /// unless there are no watchpoints left
+    pub fn watchpoint_history(&self) -> Vec<&WatchpointHit> {
+        let selected = self.watchpoints().get(self.watchpoints_selected).map(|w| w.number.clone());
+        self.watchpoint_hits
+            .iter()
+            .filter(|h| selected.as_ref().is_none_or(|number| &h.number == number))
+            .collect()
     }
-    // Start rx thread
-    let (gdb_stdout, mut app) = App::new_stream(args.clone());
-    let state = State::new(args.clone());
-    let mut state_share = StateShare { state: Arc::new(Mutex::new(state)) };
 
-    // Setup terminal
-    let mut terminal = ratatui::init();
-    execute!(terminal.backend_mut(), EnableMouseCapture)?;
+    /// Delete the selected watchpoint
+    pub fn delete_watchpoint(&mut self) {
+        if let Some(number) =
+            self.watchpoints().get(self.watchpoints_selected).map(|w| w.number.clone())
+        {
+            self.next_write.push(mi::break_delete(&number));
+            self.next_write.push(mi::break_list());
+        }
+    }
 
-    spawn_gdb_interact(&state_share, gdb_stdout);
+    /// Enable or disable the selected watchpoint
+    pub fn toggle_watchpoint(&mut self) {
+        if let Some(cmd) = self.watchpoints().get(self.watchpoints_selected).map(|w| {
+            if w.enabled { mi::break_disable(&w.number) } else { mi::break_enable(&w.number) }
+        }) {
+            self.next_write.push(cmd);
+            self.next_write.push(mi::break_list());
+        }
+    }
 
-    // Give the inferior its own tty so its stdout/stderr don't share gdb's MI pipe
-    // Written directly (not via next_write) so it reaches gdb before any --cmds "run"
+    /// Switch gdb to the thread selected in the threads pane. Only a stopped thread
+    /// has registers to read
+    pub fn select_thread(&mut self) {
+        if self.executing {
+            return;
+        }
+        if let Some(thread) = self.threads.get(self.threads_selected) {
+            self.next_write.push(mi::thread_select(&thread.id));
+        }
+    }
+
+    /// Select the frame under the backtrace cursor, and re-read the registers, stack, asm
+    /// and source as seen from that frame
+    pub fn select_frame(&mut self) {
+        if self.executing {
+            return;
+        }
+        let Some(frame) = self.view.bt.get(self.bt_cursor).map(|b| b.frame.clone()) else {
+            return;
+        };
+        self.next_write.push(mi::stack_select_frame(self.bt_cursor));
+        self.bt_selected = self.bt_cursor;
+        gdb::read_selected_frame(self, &frame);
+    }
+
+    fn inferior_mut(&mut self, id: &str) -> Option<&mut Inferior> {
+        self.inferiors.iter_mut().find(|i| i.id == id)
+    }
+
+    /// Exchange the program state in `State` with the one parked for inferior `index`
+    fn swap_inferior_view(&mut self, index: usize) {
+        mem::swap(&mut self.view, &mut self.inferiors[index].view);
+    }
+
+    /// Show the program state of inferior `index` in every pane
+    fn show_inferior(&mut self, index: usize) {
+        if index == self.inferior_selected || index >= self.inferiors.len() {
+            return;
+        }
+        // the chunks of a file load would go to the inferior gdb has selected next
+        self.cancel_memory_load("switching inferior");
+
+        // park the state of the selected inferior, then take the one of `index`
+        self.swap_inferior_view(self.inferior_selected);
+        self.swap_inferior_view(index);
+        self.inferior_selected = index;
+
+        self.asm_cursor = None;
+        self.bt_selected = 0;
+        self.bt_cursor = 0;
+        self.memory_map_selected = 0;
+        self.memory_map_scroll.reset();
+        self.registers_selected = 0;
+        self.locals_selected = 0;
+        self.expressions_selected = 0;
+        self.inspector_selected = 0;
+    }
+
+    /// Show the inferior of the selected thread, such as a forked child that stopped
+    pub fn follow_thread_inferior(&mut self) {
+        let Some(id) = &self.current_thread_id else {
+            return;
+        };
+        if let Some(index) = self.inferiors.iter().position(|i| i.threads.contains(id)) {
+            self.show_inferior(index);
+        }
+    }
+
+    /// Switch gdb and every pane to inferior `index`
+    pub fn select_inferior(&mut self, index: usize) {
+        if self.executing {
+            return;
+        }
+        let Some(inferior) = self.inferiors.get(index) else {
+            return;
+        };
+        // Selecting one of its threads also selects the inferior, and re-reads everything
+        if let Some(thread) = inferior.threads.first() {
+            self.next_write.push(mi::thread_select(thread));
+        } else {
+            self.next_write.push(mi::inferior_select(inferior.num()));
+        }
+        self.show_inferior(index);
+    }
+
+    /// Cycle through the inferiors, from the title bar switcher
+    pub fn next_inferior(&mut self) {
+        if self.inferiors.len() > 1 {
+            self.select_inferior((self.inferior_selected + 1) % self.inferiors.len());
+        }
+    }
+
+    /// Tree of the variable object `name`, the inspector or the locals
+    fn var_tree_mut(&mut self, name: &str) -> &mut Vec<Local> {
+        if name.starts_with(INSPECTOR_VAR_PREFIX) {
+            &mut self.view.inspector
+        } else {
+            &mut self.view.locals
+        }
+    }
+
+    /// Remove the local at `index` with its children, and delete its variable object
+    fn delete_local(&mut self, index: usize) {
+        let rows = index..tree_descendants(&self.view.locals, index).end;
+        self.next_write.push(mi::var_delete(&self.view.locals[index].var.name));
+        self.view.locals.drain(rows);
+    }
+
+    /// Expand or collapse the selected local, children are listed once expanded
+    pub fn toggle_local(&mut self) {
+        if self.executing {
+            return;
+        }
+        if let Some(cmd) = toggle_row(&mut self.view.locals, self.locals_selected) {
+            self.next_write.push(cmd);
+        }
+    }
+
+    /// Assign `value` to the selected local, and refresh every value it could have changed
+    pub fn assign_local(&mut self, value: &str) {
+        if self.executing {
+            return;
+        }
+        let Some(local) = self.view.locals.get(self.locals_selected) else {
+            return;
+        };
+        self.next_write.push(mi::var_assign(&local.var.name, value));
+        self.written.push_back(Written::VarAssign);
+        self.next_write.push(mi::var_update());
+    }
+
+    /// Index in `registers` of each row of the Registers pane
+    pub fn shown_registers(&self) -> Vec<usize> {
+        (0..self.view.registers.len())
+            .filter(|i| self.view.registers[*i].shown_value().is_some())
+            .collect()
+    }
+
+    /// Move the selected row of the Registers pane by `delta` rows
+    pub fn move_register_selection(&mut self, delta: isize) {
+        let last = self.shown_registers().len().saturating_sub(1);
+        self.registers_selected = self.registers_selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Open the popup to edit the selected register, with its current value
+    pub fn edit_register(&mut self) {
+        if self.executing {
+            return;
+        }
+        let Some(index) = self.shown_registers().get(self.registers_selected).copied() else {
+            return;
+        };
+        let value = self.view.registers[index].register.as_ref().and_then(|r| r.value.clone());
+        self.registers_popup = Input::new(value.unwrap_or_default());
+        self.mode = Mode::OnlyRegisterPopup;
+    }
+
+    /// Assign the expression `value` to the selected register, the registers are read again once
+    /// gdb answers
+    pub fn assign_register(&mut self, value: &str) {
+        if self.executing {
+            return;
+        }
+        let Some(index) = self.shown_registers().get(self.registers_selected).copied() else {
+            return;
+        };
+        let name = self.view.registers[index].name.clone();
+        let mut resolved = value.to_string();
+        replace_internal_variables(self, &mut resolved);
+        resolve_paren_math(&mut resolved);
+        self.next_write.push(mi::register_assign(&name, &resolved));
+        self.written.push_back(Written::RegisterAssign(name));
+    }
+
+    /// Bit fields of the status register `name`, built in or from the target description
+    pub fn flags_layout(&self, name: &str) -> Option<&FlagsLayout> {
+        builtin_layout(name, self.view.ptr_size != PtrSize::Size32)
+            .or_else(|| self.view.register_layouts.get(name))
+    }
+
+    /// Remember the values of the registers before they are dropped for the next stop
+    pub fn remember_registers(&mut self) {
+        if self.view.registers.is_empty() {
+            return;
+        }
+        self.view.registers_previous = self
+            .view
+            .registers
+            .iter()
+            .filter_map(|r| Some((r.name.clone(), r.shown_value()?)))
+            .collect();
+    }
+
+    /// Ask gdb for its target description, for the bit fields of the registers it defines
+    pub fn request_target_description(&mut self) {
+        self.next_write.push(mi::maint_print_xml_tdesc());
+        self.written.push_back(Written::TargetDescription);
+    }
+
+    /// Read the registers again after `name` was assigned, with their derefs. The stack is read
+    /// again when it was the stack or frame pointer, and the asm too when it was the pc. The
+    /// changed registers are kept from the stop
+    pub fn refresh_registers(&mut self, name: &str) {
+        self.next_write.push("-data-list-register-values x".to_string());
+        if !PC_REGS.contains(&name) {
+            let stack =
+                self.view.stack_model.sp_registers().contains(&name) || FP_REGS.contains(&name);
+            self.written.push_back(Written::RegisterRefresh { stack });
+        }
+    }
+
+    /// Add an expression, evaluated right away when the program is stopped
+    pub fn add_expression(&mut self, text: &str) {
+        let name = format!("expr{}", self.var_obj_id);
+        self.var_obj_id += 1;
+        self.view.expressions.push(Expression {
+            name,
+            text: text.to_string(),
+            ..Expression::default()
+        });
+        self.expressions_selected = self.view.expressions.len() - 1;
+        if !self.executing {
+            self.create_expression(self.view.expressions.len() - 1);
+        }
+    }
+
+    /// (Re-)create the variable object of the expression at `index` when it is missing, failed
+    /// or its resolved text changed, such as a mapping moved between runs
+    pub fn create_expression(&mut self, index: usize) {
+        let mut resolved = self.view.expressions[index].text.clone();
+        replace_internal_variables(self, &mut resolved);
+        resolve_paren_math(&mut resolved);
+
+        let expression = &mut self.view.expressions[index];
+        if expression.error.is_none() && expression.resolved.as_ref() == Some(&resolved) {
+            return;
+        }
+        if expression.resolved.is_some() && expression.error.is_none() {
+            self.next_write.push(mi::var_delete(&expression.name));
+        }
+        self.next_write.push(mi::var_create_floating(&expression.name, &resolved));
+        self.written.push_back(Written::ExpressionCreate(expression.name.clone()));
+        expression.resolved = Some(resolved);
+        expression.error = None;
+        expression.changed = false;
+    }
+
+    /// Remove the selected expression, and delete its variable object
+    pub fn delete_expression(&mut self) {
+        if self.expressions_selected >= self.view.expressions.len() {
+            return;
+        }
+        let expression = self.view.expressions.remove(self.expressions_selected);
+        if expression.resolved.is_some() && expression.error.is_none() {
+            self.next_write.push(mi::var_delete(&expression.name));
+        }
+        self.expressions_selected =
+            self.expressions_selected.min(self.view.expressions.len().saturating_sub(1));
+    }
+
+    /// Evaluate `text` in the inspector, replacing the previous result
+    pub fn inspect(&mut self, text: &str) {
+        self.inspector_history.push(text.to_string());
+        self.inspector_history.offset = 0;
+        if let Some(root) = self.view.inspector.first() {
+            self.next_write.push(mi::var_delete(&root.var.name));
+        }
+        self.view.inspector.clear();
+        self.inspector_selected = 0;
+        self.inspector_error = None;
+        self.view.inspector_telescope = None;
+        if self.executing {
+            self.inspector_error = Some("program is running".to_string());
+            return;
+        }
+
+        let mut resolved = text.to_string();
+        replace_internal_variables(self, &mut resolved);
+        resolve_paren_math(&mut resolved);
+        let name = format!("{INSPECTOR_VAR_PREFIX}{}", self.var_obj_id);
+        self.var_obj_id += 1;
+        self.next_write.push(mi::var_create(&name, &resolved));
+        self.written.push_back(Written::ExpressionCreate(name.clone()));
+        let var = mi::VarObj { name, exp: text.to_string(), ..mi::VarObj::default() };
+        self.view.inspector.push(Local { var, ..Local::default() });
+    }
+
+    /// Expand or collapse the selected row of the inspector
+    pub fn toggle_inspector_row(&mut self) {
+        if self.executing {
+            return;
+        }
+        if let Some(cmd) = toggle_row(&mut self.view.inspector, self.inspector_selected) {
+            self.next_write.push(cmd);
+        }
+    }
+
+    /// Address held by the selected row of the inspector, when it is a pointer
+    pub fn inspector_pointer(&self) -> Option<u64> {
+        let row = self.view.inspector.get(self.inspector_selected)?;
+        if !row.var.type_.as_ref()?.contains('*') {
+            return None;
+        }
+        let value = row.var.value.as_ref()?;
+        // such as "(char *) 0x402004 \"hello\"" or "0x4052a0"
+        let hex = value.split_whitespace().find_map(|v| v.strip_prefix("0x"))?;
+        u64::from_str_radix(hex, 16).ok()
+    }
+
+    /// Follow the deref chain of `addr`, as shown for registers and the stack
+    pub fn telescope(&mut self, addr: u64) {
+        self.view.inspector_telescope = Some((addr, Deref::new()));
+        if addr == 0 {
+            return;
+        }
+        let len = if self.view.ptr_size == PtrSize::Size32 { 4 } else { 8 };
+        self.next_write.push(data_read_memory_bytes(addr, 0, len));
+        self.written.push_back(Written::Telescope);
+    }
+
+    /// Show `len` bytes of memory from `start` in the hexdump. Small ranges are read in full,
+    /// larger ones a page at a time as they are shown
+    pub fn load_hexdump(&mut self, start: u64, len: u64) {
+        let len = len as usize;
+        self.view.hexdump = Some(PagedMemory::new(start, len));
+        self.view.hexdump_scroll.reset();
+        self.view.hexdump_cursor = 0;
+        self.view.hexdump_edits.clear();
+        self.view.hexdump_failed.clear();
+        self.view.hexdump_changed.clear();
+        self.hexdump_changes_only = false;
+        if let Some(search) = &mut self.view.hexdump_search {
+            search.update(&[]);
+        }
+        if self.view.hexdump.as_ref().is_some_and(PagedMemory::is_eager) {
+            self.request_hexdump_pages(0..len);
+        }
+    }
+
+    /// Read the pages of the hexdump holding the bytes of `range` that are not loaded yet
+    pub fn request_hexdump_pages(&mut self, range: Range<usize>) {
+        if self.executing {
+            return;
+        }
+        let Some(memory) = &mut self.view.hexdump else {
+            return;
+        };
+        for index in memory.request(range) {
+            let (addr, len) = memory.page_range(index);
+            self.next_write.push(data_read_memory_bytes(addr, 0, len));
+            self.written.push_back(Written::Memory(addr));
+        }
+    }
+
+    /// Show the mapping holding `addr` in the hexdump, with the cursor on `addr`
+    pub fn follow_in_hexdump(&mut self, addr: u64) {
+        let (start, end) = self
+            .view
+            .memory_map
+            .as_ref()
+            .and_then(|mm| mm.iter().find(|m| m.contains(addr)))
+            .map_or((addr, addr.saturating_add(HEXDUMP_FOLLOW_LEN)), |m| {
+                (m.start_address, m.end_address)
+            });
+        self.load_hexdump(start, end - start);
+        let offset = (addr - start) as usize;
+        self.view.hexdump_cursor = offset;
+        // Rows are not collapsed before all of them are read
+        self.view.hexdump_scroll.set_max_scroll((end - start) as usize / self.hexdump_width);
+        self.view.hexdump_scroll.set(offset / self.hexdump_width);
+        self.mode = Mode::OnlyHexdump;
+    }
+
+    /// Search the hexdump for `query`, and show the first match. An empty query clears the search
+    pub fn search_hexdump(&mut self, query: &str) {
+        if query.trim().is_empty() {
+            self.view.hexdump_search = None;
+            return;
+        }
+        let pattern = match Pattern::parse(query, self.view.ptr_size) {
+            Ok(pattern) => pattern,
+            Err(e) => {
+                self.output.push(format!("h> invalid search {query}: {e}"));
+                return;
+            }
+        };
+        let runs = self.view.hexdump.as_ref().map(PagedMemory::runs).unwrap_or_default();
+        let search = HexdumpSearch::new(query, pattern, &runs);
+        if search.matches.is_empty() {
+            self.output.push(format!("h> no match for {query}"));
+        }
+        self.view.hexdump_search = Some(search);
+        self.show_search_match();
+    }
+
+    /// Select the next match of the hexdump search, or the previous one
+    pub fn next_search_match(&mut self, forward: bool) {
+        if let Some(search) = &mut self.view.hexdump_search {
+            if forward {
+                search.next();
+            } else {
+                search.prev();
+            }
+        }
+        self.show_search_match();
+    }
+
+    /// Scroll the selected match of the hexdump search to the top, its row is never collapsed
+    fn show_search_match(&mut self) {
+        let Some(offset) =
+            self.view.hexdump_search.as_ref().and_then(HexdumpSearch::current_offset)
+        else {
+            return;
+        };
+        self.hexdump_changes_only = false;
+        if let Some(memory) = &self.view.hexdump {
+            let geometry = self.hexdump_geometry();
+            let index = display_index_keeping(memory, geometry, offset / geometry.width);
+            self.view.hexdump_scroll.set(index);
+        }
+    }
+
+    /// Search target memory with chunked reads, for the `find` command:
+    /// `find <mapping> <pattern>` or `find <addr> <len> <pattern>`
+    pub fn find(&mut self, args: &str) -> Result<(), String> {
+        let (first, rest) = args.trim().split_once(char::is_whitespace).ok_or(FIND_USAGE)?;
+        let (start, end, query) = if let Some(addr) = parse_number(first) {
+            let (len, query) = rest.trim().split_once(char::is_whitespace).ok_or(FIND_USAGE)?;
+            let len = parse_number(len).ok_or(FIND_USAGE)?;
+            (addr, addr.checked_add(len).ok_or(FIND_USAGE)?, query)
+        } else {
+            let memory_map = self.view.memory_map.as_ref().ok_or("no memory mapping to search")?;
+            let mapping = memory_map
+                .iter()
+                .find(|m| m.path.as_ref().is_some_and(|p| p.contains(first)))
+                .ok_or_else(|| format!("no mapping matching {first}"))?;
+            (mapping.start_address, mapping.end_address, rest)
+        };
+        let query = query.trim();
+        let pattern = Pattern::parse(query, self.view.ptr_size)?;
+
+        let id = self.find_id;
+        self.find_id += 1;
+        let chunks = find_chunks(start, end, &pattern);
+        self.find_job = Some(FindJob {
+            id,
+            query: query.to_string(),
+            pattern,
+            end,
+            chunks,
+            pending: 0,
+            matches: vec![],
+        });
+        self.output.push(format!("f> searching 0x{start:x}..0x{end:x} for {query}"));
+        self.finish_find();
+        Ok(())
+    }
+
+    /// Name an address or range for the `label <addr> [<len>] <name>` command, remove one with
+    /// `label -d <name>`, or list them without arguments
+    pub fn label(&mut self, args: &str) -> Result<(), String> {
+        let args = args.trim();
+        if args.is_empty() {
+            if self.labels.is_empty() {
+                self.output.push("h> no labels".to_string());
+            }
+            for label in self.labels.iter() {
+                let len =
+                    if label.len == 0 { String::new() } else { format!(" 0x{:x}", label.len) };
+                self.output.push(format!("h> 0x{:x}{len} {}", label.addr, label.name));
+            }
+            return Ok(());
+        }
+        if let Some(name) = args.strip_prefix("-d ") {
+            let name = name.trim();
+            return self.labels.remove(name).map(|_| ()).ok_or_else(|| format!("no label {name}"));
+        }
+        self.labels.insert(parse_label(args)?);
+        Ok(())
+    }
+
+    /// Write every label as a `label` command to `path`, for `label-load` or `--cmds`
+    pub fn save_labels(&mut self, path: &str) -> Result<(), String> {
+        let path = path.trim();
+        let file = resolve_home(path).ok_or_else(|| format!("invalid path {path}"))?;
+        fs::write(&file, self.labels.commands()).map_err(|e| format!("{path}: {e}"))?;
+        self.output.push(format!("h> saved {} labels to {path}", self.labels.len()));
+        Ok(())
+    }
+
+    /// Add the labels of the `label` commands of `path`, such as written by `label-save`
+    pub fn load_labels(&mut self, path: &str) -> Result<(), String> {
+        let path = path.trim();
+        let file = resolve_home(path).ok_or_else(|| format!("invalid path {path}"))?;
+        let data = fs::read_to_string(&file).map_err(|e| format!("{path}: {e}"))?;
+        let mut count = 0;
+        for line in data.lines() {
+            if let Some(args) = line.trim().strip_prefix("label ") {
+                self.labels.insert(parse_label(args)?);
+                count += 1;
+            }
+        }
+        self.output.push(format!("h> loaded {count} labels from {path}"));
+        Ok(())
+    }
+
+    /// Bookmark the hexdump cursor from the label popup, `<name> [<len>]`
+    pub fn bookmark_hexdump(&mut self, input: &str) -> Result<(), String> {
+        let addr = self
+            .view
+            .hexdump
+            .as_ref()
+            .map(|m| m.start + self.view.hexdump_cursor as u64)
+            .ok_or("no hexdump to bookmark")?;
+        let (name, len) = match input.split_whitespace().collect::<Vec<_>>()[..] {
+            [name] => (name, None),
+            [name, len] => (name, Some(len)),
+            _ => return Err("expected '<name> [<len>]'".to_string()),
+        };
+        check_name(name)?;
+        let len = len.map_or("", |len| len);
+        self.labels.insert(parse_label(&format!("0x{addr:x} {len} {name}"))?);
+        Ok(())
+    }
+
+    /// Show `addr` under the hexdump cursor, following it to its mapping when it is outside the
+    /// hexdump
+    pub fn show_in_hexdump(&mut self, addr: u64) {
+        let Some(memory) = &self.view.hexdump else {
+            self.follow_in_hexdump(addr);
+            return;
+        };
+        let Some(offset) = addr.checked_sub(memory.start).filter(|o| *o < memory.len as u64) else {
+            self.follow_in_hexdump(addr);
+            return;
+        };
+        let geometry = self.hexdump_geometry();
+        self.view.hexdump_cursor = offset as usize;
+        let index = display_index_of_row(memory, geometry, offset as usize / geometry.width);
+        self.view.hexdump_scroll.set(index);
+        self.mode = Mode::OnlyHexdump;
+    }
+
+    /// Jump to the highlighted label of the bookmarks list
+    pub fn goto_bookmark(&mut self) {
+        let addr = self.labels.iter().nth(self.bookmarks_selected).map(|l| l.addr);
+        if let Some(addr) = addr {
+            self.show_in_hexdump(addr);
+        }
+        self.mode = Mode::OnlyHexdump;
+    }
+
+    /// Capture a region of memory under a name, for the `snapshot-mem <name> <addr> <len>`
+    /// command. Taking a snapshot with the name of another replaces it
+    pub fn snapshot_mem(&mut self, args: &str) -> Result<(), String> {
+        let [name, addr, len] = args.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(SNAPSHOT_USAGE.to_string());
+        };
+        let addr = parse_number(addr).ok_or(SNAPSHOT_USAGE)?;
+        let len = parse_number(len).ok_or(SNAPSHOT_USAGE)? as usize;
+        if len == 0 || len > SNAPSHOT_MAX_LEN {
+            return Err(format!("snapshots are 1 to 0x{SNAPSHOT_MAX_LEN:x} bytes"));
+        }
+        if self.executing {
+            return Err("the target is running".to_string());
+        }
+        let mut memory = PagedMemory::new(addr, len);
+        self.read_snapshot(Some(name), &mut memory);
+        self.snapshots.insert(name.to_string(), memory);
+        if let Some(diff) = &mut self.memory_diff
+            && (diff.left == name || diff.right.as_deref() == Some(name))
+        {
+            diff.differences = None;
+        }
+        self.output.push(format!("h> snapshot {name}: 0x{len:x} bytes at 0x{addr:x}"));
+        Ok(())
+    }
+
+    /// Read every page of `memory`, a snapshot or the live side of the memory diff
+    fn read_snapshot(&mut self, name: Option<&str>, memory: &mut PagedMemory) {
+        for index in memory.request(0..memory.len) {
+            let (addr, len) = memory.page_range(index);
+            self.next_write.push(data_read_memory_bytes(addr, 0, len));
+            self.written.push_back(Written::SnapshotPage(name.map(str::to_string), addr));
+        }
+    }
+
+    /// Compare two snapshots, or a snapshot with the live memory of its range, for the
+    /// `diff-mem <name> [<name>]` command
+    pub fn diff_mem(&mut self, args: &str) -> Result<(), String> {
+        let names: Vec<&str> = args.split_whitespace().collect();
+        let (left, right) = match names[..] {
+            [left] => (left, None),
+            [left, right] => (left, Some(right)),
+            _ => return Err(DIFF_USAGE.to_string()),
+        };
+        for name in names {
+            if !self.snapshots.contains_key(name) {
+                return Err(format!("no snapshot named {name}"));
+            }
+        }
+        let live = right.is_none().then(|| {
+            let snapshot = &self.snapshots[left];
+            PagedMemory::new(snapshot.start, snapshot.len)
+        });
+        self.memory_diff = Some(MemoryDiff::new(left.to_string(), right.map(str::to_string), live));
+        self.refresh_memory_diff();
+        self.mode = Mode::OnlyHexdumpDiff;
+        Ok(())
+    }
+
+    /// Read the live side of the memory diff again, such as after a stop
+    pub fn refresh_memory_diff(&mut self) {
+        let Some(diff) = &mut self.memory_diff else {
+            return;
+        };
+        let Some(live) = &diff.live else {
+            self.update_memory_diff();
+            return;
+        };
+        let mut live = PagedMemory::new(live.start, live.len);
+        diff.differences = None;
+        self.read_snapshot(None, &mut live);
+        if let Some(diff) = &mut self.memory_diff {
+            diff.live = Some(live);
+        }
+    }
+
+    /// Snapshot `name`, or the live side of the memory diff
+    pub fn snapshot_mut(&mut self, name: Option<&str>) -> Option<&mut PagedMemory> {
+        match name {
+            Some(name) => self.snapshots.get_mut(name),
+            None => self.memory_diff.as_mut()?.live.as_mut(),
+        }
+    }
+
+    /// Find the differences of the memory diff once both sides are read
+    pub fn update_memory_diff(&mut self) {
+        let Some(diff) = &mut self.memory_diff else {
+            return;
+        };
+        if diff.differences.is_some() {
+            return;
+        }
+        let Some(left) = self.snapshots.get(&diff.left) else {
+            return;
+        };
+        let right = match &diff.right {
+            Some(name) => self.snapshots.get(name),
+            None => diff.live.as_ref(),
+        };
+        let Some(right) = right else {
+            return;
+        };
+        if left.is_settled() && right.is_settled() {
+            diff.differences = Some(differences(left, right));
+            diff.current = 0;
+        }
+    }
+
+    /// Select the next run of differences of the memory diff, or the previous one, and scroll
+    /// its row to the top
+    pub fn next_memory_difference(&mut self, forward: bool) {
+        let Some(diff) = &mut self.memory_diff else {
+            return;
+        };
+        if let Some(offset) = diff.next_difference(forward) {
+            diff.scroll.set(offset / diff.width);
+        }
+    }
+
+    /// Read the file of the load popup, `<path> [<addr>]`, and the target bytes it would
+    /// overwrite for the preview. Without an address the file goes to the hexdump cursor
+    pub fn prepare_memory_load(&mut self, input: &str) -> Result<(), String> {
+        let mut input = input.trim().to_string();
+        replace_internal_variables(self, &mut input);
+        resolve_paren_expressions(&mut input);
+        let (path, addr) = match input.rsplit_once(char::is_whitespace) {
+            Some((path, addr)) if parse_number(addr).is_some() => (path.trim(), parse_number(addr)),
+            _ => (input.as_str(), None),
+        };
+        let addr = addr
+            .or_else(|| {
+                self.view.hexdump.as_ref().map(|m| m.start + self.view.hexdump_cursor as u64)
+            })
+            .ok_or("expected '<path> <addr>'")?;
+        let file = resolve_home(path).ok_or_else(|| format!("invalid path {path}"))?;
+        let bytes = std::fs::read(&file).map_err(|e| format!("{path}: {e}"))?;
+        if bytes.is_empty() {
+            return Err(format!("{path} is empty"));
+        }
+        let load = MemoryLoad::new(path.to_string(), addr, bytes);
+        self.next_write.push(data_read_memory_bytes(addr, 0, load.preview_len() as u64));
+        self.written.push_back(Written::LoadPreview);
+        self.view.memory_load = Some(load);
+        self.mode = Mode::OnlyHexdumpLoadConfirm;
+        Ok(())
+    }
+
+    /// Start writing the confirmed file load
+    pub fn start_memory_load(&mut self) {
+        if let Some(load) = &mut self.view.memory_load {
+            load.written = Some(0);
+        }
+        self.write_memory_load_chunk();
+    }
+
+    /// Write the next chunk of the file load, or report it done and read the hexdump again to
+    /// show the bytes it changed
+    pub fn write_memory_load_chunk(&mut self) {
+        let Some(load) = &self.view.memory_load else {
+            return;
+        };
+        if let Some((addr, chunk)) = load.next_chunk() {
+            self.next_write.push(mi::data_write_memory_bytes(addr, chunk));
+            self.written.push_back(Written::LoadChunk(chunk.len()));
+        } else if load.written.is_some() {
+            self.output.push(format!(
+                "h> loaded 0x{:x} bytes of {} at 0x{:x}",
+                load.bytes.len(),
+                load.path,
+                load.addr
+            ));
+            self.view.memory_load = None;
+            self.refresh_hexdump();
+        }
+    }
+
+    /// Stop a file load before its next chunk is written, reporting how far it got
+    pub fn cancel_memory_load(&mut self, reason: &str) {
+        let Some(load) = self.view.memory_load.take() else {
+            return;
+        };
+        if self.mode == Mode::OnlyHexdumpLoadConfirm {
+            self.mode = Mode::OnlyHexdump;
+        }
+        if let Some(written) = load.written {
+            self.output.push(format!(
+                "h> load of {} cancelled by {reason} after 0x{written:x} of 0x{:x} bytes at 0x{:x}",
+                load.path,
+                load.bytes.len(),
+                load.addr
+            ));
+        }
+    }
+
+    /// Read the layout of the type of the type popup, `<type> [[<count>]]`, to overlay it from
+    /// the hexdump cursor. An empty type removes the overlay
+    pub fn request_type_overlay(&mut self, input: &str) -> Result<(), String> {
+        if input.trim().is_empty() {
+            self.view.type_overlay = None;
+            return Ok(());
+        }
+        let addr = self
+            .view
+            .hexdump
+            .as_ref()
+            .map(|m| m.start + self.view.hexdump_cursor as u64)
+            .ok_or("no hexdump to overlay")?;
+        let overlay = TypeOverlay::new(input, addr)?;
+        self.next_write.push(mi::ptype_offsets(&overlay.name));
+        self.written.push_back(Written::TypeLayout(overlay));
+        Ok(())
+    }
+
+    /// Read the next chunks of a `find` command, or report its matches once the last chunk is
+    /// in and show the first one in the hexdump
+    pub fn finish_find(&mut self) {
+        if let Some(job) = &mut self.find_job {
+            while let Some((begin, len)) = job.next_chunk() {
+                self.next_write.push(data_read_memory_bytes(begin, 0, len));
+                self.written.push_back(Written::FindChunk(job.id));
+            }
+        }
+        if self.find_job.as_ref().is_none_or(|job| job.pending != 0) {
+            return;
+        }
+        let Some(mut job) = self.find_job.take() else {
+            return;
+        };
+        job.matches.sort_unstable();
+        if job.matches.is_empty() {
+            self.output.push(format!("f> no match for {}", job.query));
+            return;
+        }
+        let shown: Vec<String> =
+            job.matches.iter().take(FIND_MATCHES_SHOWN).map(|a| format!("0x{a:x}")).collect();
+        let more = if job.matches.len() > FIND_MATCHES_SHOWN { ", ..." } else { "" };
+        self.output.push(format!(
+            "f> {} matches for {}: {}{more}",
+            job.matches.len(),
+            job.query,
+            shown.join(", ")
+        ));
+        self.view.hexdump_search = Some(HexdumpSearch::new(&job.query, job.pattern, &[]));
+        self.follow_in_hexdump(job.matches[0]);
+    }
+
+    /// Keep the hexdump cursor where it is when it is still shown, otherwise put it on the
+    /// first row shown. False without any bytes to put it on
+    fn show_hexdump_cursor(&mut self) -> bool {
+        if self.view.hexdump.as_ref().is_none_or(|memory| memory.len == 0) {
+            return false;
+        }
+        // The cursor moves over every row, not only the changed ones
+        if self.hexdump_changes_only {
+            self.hexdump_changes_only = false;
+            self.view.hexdump_scroll.reset();
+        }
+        let Some(memory) = &self.view.hexdump else {
+            return false;
+        };
+        let top = self.view.hexdump_scroll.scroll;
+        let height = self.hexdump_viewport_height as usize;
+        let geometry = self.hexdump_geometry();
+        let index =
+            display_index_of_row(memory, geometry, self.view.hexdump_cursor / geometry.width);
+        if self.view.hexdump_cursor >= memory.len || index < top || index >= top + height {
+            self.view.hexdump_cursor = row_at_display_index(memory, geometry, top) * geometry.width;
+        }
+        true
+    }
+
+    /// Read the loaded pages of the hexdump again after a stop, to compare with the bytes of the
+    /// previous stop
+    pub fn refresh_hexdump(&mut self) {
+        let Some(memory) = &self.view.hexdump else {
+            return;
+        };
+        self.view.hexdump_changed.clear();
+        self.view.hexdump_failed.clear();
+        for index in memory.loaded() {
+            let (addr, len) = memory.page_range(index);
+            self.next_write.push(data_read_memory_bytes(addr, 0, len));
+            self.written.push_back(Written::MemoryRefresh(addr));
+        }
+    }
+
+    /// Toggle showing only the hexdump rows that changed since the previous stop
+    pub fn toggle_hexdump_changes_only(&mut self) {
+        self.hexdump_changes_only = !self.hexdump_changes_only;
+        self.view.hexdump_scroll.reset();
+    }
+
+    /// Bytes per row and zero row collapsing of the hexdump display lines
+    pub fn hexdump_geometry(&self) -> Geometry {
+        Geometry { width: self.hexdump_width, collapse: self.hexdump_layout.collapse }
+    }
+
+    /// Switch the hexdump to `layout` with rows of `width` bytes, keeping the row at the top of
+    /// the pane shown
+    pub fn relayout_hexdump(&mut self, layout: HexdumpLayout, width: usize) {
+        let top = self.view.hexdump.as_ref().map(|memory| {
+            let geometry = self.hexdump_geometry();
+            row_at_display_index(memory, geometry, self.view.hexdump_scroll.scroll) * geometry.width
+        });
+        self.hexdump_layout = layout;
+        self.hexdump_width = width;
+        let (Some(memory), Some(top)) = (&self.view.hexdump, top) else {
+            return;
+        };
+        if self.hexdump_changes_only {
+            self.view.hexdump_scroll.reset();
+            return;
+        }
+        let index = display_index_of_row(memory, self.hexdump_geometry(), top / width);
+        // the draw sets the exact bound
+        self.view.hexdump_scroll.set_max_scroll(memory.len.div_ceil(width));
+        self.view.hexdump_scroll.set(index);
+    }
+
+    /// Cycle the bytes per row of the hexdump: 8, 16, 32, then fitting the pane
+    pub fn cycle_hexdump_width(&mut self) {
+        let width = self.hexdump_layout.width.next();
+        let bytes = match width {
+            RowWidth::Fixed(bytes) => bytes,
+            // picked at the next draw
+            RowWidth::Auto => self.hexdump_width,
+        };
+        self.relayout_hexdump(HexdumpLayout { width, ..self.hexdump_layout }, bytes);
+    }
+
+    /// Toggle collapsing the runs of zero rows of the hexdump
+    pub fn toggle_hexdump_collapse(&mut self) {
+        let layout =
+            HexdumpLayout { collapse: !self.hexdump_layout.collapse, ..self.hexdump_layout };
+        self.relayout_hexdump(layout, self.hexdump_width);
+    }
+
+    /// Start editing the hexdump at the cursor
+    pub fn edit_hexdump(&mut self) {
+        if !self.show_hexdump_cursor() {
+            return;
+        }
+        self.view.hexdump_low_nibble = false;
+        self.mode = Mode::OnlyHexdumpEdit;
+    }
+
+    /// Start a selection at the cursor, to save only the selected bytes
+    pub fn select_hexdump(&mut self) {
+        if !self.show_hexdump_cursor() {
+            return;
+        }
+        self.view.hexdump_selection = Some(self.view.hexdump_cursor);
+        self.mode = Mode::OnlyHexdumpSelect;
+    }
+
+    /// Byte offsets into `hexdump` from the start of the selection to the cursor, both included
+    pub fn hexdump_selection_range(&self) -> Option<Range<usize>> {
+        let start = self.view.hexdump_selection?;
+        Some(start.min(self.view.hexdump_cursor)..start.max(self.view.hexdump_cursor) + 1)
+    }
+
+    /// Write the selection, or else the whole hexdump, to `path` as `hexdump_export`, at the
+    /// addresses it was read from
+    pub fn save_hexdump(&mut self, path: &str) {
+        let Some(memory) = &self.view.hexdump else {
+            return;
+        };
+        let (start, bytes) = match self.hexdump_selection_range() {
+            Some(range) => (range.start, range.map(|offset| memory.get(offset)).collect()),
+            None => (0, memory.contiguous()),
+        };
+        let Some(bytes) = bytes else {
+            let msg = if self.view.hexdump_selection.is_some() {
+                "h> selection is not fully read, scroll over it first".to_string()
+            } else {
+                format!(
+                    "h> hexdump is not fully read, only ranges of up to 0x{:x} bytes are saved",
+                    memory::PAGE_LEN * memory::EAGER_PAGES
+                )
+            };
+            self.output.push(msg);
+            return;
+        };
+        let Some(path) = resolve_home(path) else {
+            return;
+        };
+        let addr = memory.start + start as u64;
+        let name = identifier(path.file_stem().and_then(|stem| stem.to_str()).unwrap_or(""));
+        let format = self.hexdump_export;
+        let msg = match format.export(&name, addr, &bytes) {
+            Ok(contents) => match fs::write(&path, contents) {
+                Ok(()) => format!(
+                    "h> 0x{:x} bytes from 0x{addr:x} written to {} as {}",
+                    bytes.len(),
+                    path.display(),
+                    format.name()
+                ),
+                Err(e) => format!("h> could not write {}: {e}", path.display()),
+            },
+            Err(e) => format!("h> {e}"),
+        };
+        self.output.push(msg);
+    }
+
+    /// Move the cursor the data inspector decodes from by `delta` bytes, scrolling to keep it shown
+    pub fn inspect_hexdump_byte(&mut self, delta: isize) {
+        if !self.show_hexdump_cursor() {
+            return;
+        }
+        self.move_hexdump_cursor(delta);
+        if let Some(memory) = &self.view.hexdump {
+            let geometry = self.hexdump_geometry();
+            let index =
+                display_index_of_row(memory, geometry, self.view.hexdump_cursor / geometry.width);
+            let height = self.hexdump_viewport_height as usize;
+            self.view.hexdump_scroll.keep_visible(index, height);
+        }
+    }
+
+    /// Move the edit cursor by `delta` bytes, within the hexdump
+    pub fn move_hexdump_cursor(&mut self, delta: isize) {
+        let Some(memory) = &self.view.hexdump else {
+            return;
+        };
+        let last = memory.len.saturating_sub(1);
+        self.view.hexdump_cursor = self.view.hexdump_cursor.saturating_add_signed(delta).min(last);
+        self.view.hexdump_low_nibble = false;
+    }
+
+    /// Byte at `offset` of the hexdump, with its pending edit
+    pub fn hexdump_byte(&self, offset: usize) -> Option<u8> {
+        let memory = self.view.hexdump.as_ref()?;
+        self.view.hexdump_edits.get(&offset).copied().or_else(|| memory.get(offset))
+    }
+
+    /// Type a hex digit over the nibble under the cursor, which moves on after the low nibble
+    pub fn edit_hexdump_nibble(&mut self, digit: u8) {
+        let Some(byte) = self.hexdump_byte(self.view.hexdump_cursor) else {
+            return;
+        };
+        if self.view.hexdump_low_nibble {
+            self.view.hexdump_edits.insert(self.view.hexdump_cursor, (byte & 0xf0) | digit);
+            self.move_hexdump_cursor(1);
+        } else {
+            self.view.hexdump_edits.insert(self.view.hexdump_cursor, (digit << 4) | (byte & 0x0f));
+            self.view.hexdump_low_nibble = true;
+        }
+    }
+
+    /// Type a character over the byte under the cursor, from the ascii column
+    pub fn edit_hexdump_ascii(&mut self, c: u8) {
+        if self.hexdump_byte(self.view.hexdump_cursor).is_none() {
+            return;
+        }
+        self.view.hexdump_edits.insert(self.view.hexdump_cursor, c);
+        self.move_hexdump_cursor(1);
+    }
+
+    /// Drop the pending edit under the cursor, or else the one before it
+    pub fn revert_hexdump_edit(&mut self) {
+        if self.view.hexdump_edits.remove(&self.view.hexdump_cursor).is_none() {
+            self.move_hexdump_cursor(-1);
+            self.view.hexdump_edits.remove(&self.view.hexdump_cursor);
+        }
+        self.view.hexdump_low_nibble = false;
+    }
+
+    /// Write the pending edits, with one `-data-write-memory-bytes` per run of consecutive
+    /// bytes. The edited bytes are read back afterwards, as writes to flash or rom can fail
+    /// silently. Returns false when the edits are kept, as the target is running
+    pub fn write_hexdump_edits(&mut self) -> bool {
+        if self.view.hexdump_edits.is_empty() {
+            return true;
+        }
+        if self.executing {
+            self.output.push("h> edits not written, the target is running".to_string());
+            return false;
+        }
+        let Some(memory) = &self.view.hexdump else {
+            return true;
+        };
+        let base = memory.start;
+
+        let edits = mem::take(&mut self.view.hexdump_edits);
+        let mut runs: Vec<(usize, Vec<u8>)> = Vec::new();
+        for (&offset, &byte) in &edits {
+            match runs.last_mut() {
+                Some((start, bytes)) if *start + bytes.len() == offset => bytes.push(byte),
+                _ => runs.push((offset, vec![byte])),
+            }
+        }
+        for (offset, bytes) in runs {
+            let addr = base + offset as u64;
+            self.next_write.push(mi::data_write_memory_bytes(addr, &bytes));
+            self.written.push_back(Written::MemoryWrite(addr));
+            self.next_write.push(data_read_memory_bytes(addr, 0, bytes.len() as u64));
+            let check = (addr..).zip(bytes).collect();
+            self.written.push_back(Written::MemoryWriteCheck(check));
+        }
+        self.view.hexdump_failed.clear();
+        self.view.hexdump_low_nibble = false;
+        true
+    }
+
+    /// Cycle the display format of the selected expression
+    pub fn cycle_expression_format(&mut self) {
+        if let Some(expression) = self.view.expressions.get_mut(self.expressions_selected) {
+            expression.format = expression.format.next();
+        }
+    }
+
+    /// Index into `asm` of the instruction cursor, falling back to $pc
+    pub fn asm_cursor_index(&self) -> Option<usize> {
+        self.asm_cursor
+            .or_else(|| self.view.asm.iter().position(|a| a.address == self.view.current_pc))
+    }
+
+    /// Move the source cursor by `delta` lines, clamped to the file
+    pub fn move_source_cursor(&mut self, delta: isize) {
+        let last = self.view.source_lines.len().saturating_sub(1);
+        self.source_cursor = self.source_cursor.saturating_add_signed(delta).min(last);
+    }
+
+    /// Move the instruction cursor by `delta` instructions, clamped to `asm`
+    pub fn move_asm_cursor(&mut self, delta: isize) {
+        if self.view.asm.is_empty() {
+            return;
+        }
+        let last = self.view.asm.len() - 1;
+        let cursor = self.asm_cursor_index().unwrap_or(0);
+        self.asm_cursor = Some(cursor.saturating_add_signed(delta).min(last));
+    }
+
+    /// gdb location of the cursor in the current pane, along with the breakpoint
+    /// already placed there
+    fn cursor_location(&self) -> Option<(String, Option<&Breakpoint>)> {
+        match self.mode {
+            Mode::OnlySource => {
+                let file = self.view.current_source_file.as_ref()?;
+                if self.view.source_lines.is_empty() {
+                    return None;
+                }
+                let line = self.source_cursor as u32 + 1;
+                Some((mi::linespec(file, line), self.breakpoint_at_line(line)))
+            }
+            Mode::OnlyInstructions => {
+                let addr = self.view.asm.get(self.asm_cursor_index()?)?.address;
+                Some((mi::addrspec(addr), self.breakpoint_at_addr(addr)))
+            }
+            _ => None,
+        }
+    }
+
+    /// Insert a breakpoint at the pane cursor, or delete the one already there
+    pub fn toggle_breakpoint(&mut self) {
+        let Some((location, bkpt)) = self.cursor_location() else {
+            return;
+        };
+        let cmd = match bkpt {
+            Some(bkpt) => mi::break_delete(&bkpt.number),
+            None => mi::break_insert(&location),
+        };
+        self.next_write.push(cmd);
+        // -break-delete is silent, re-sync the list from gdb
+        self.next_write.push(mi::break_list());
+    }
+
+    /// Continue until the location under the pane cursor
+    ///
+    /// Unlike `-exec-until`, a temporary breakpoint also stops in a different function
+    /// or after the current frame returns
+    pub fn run_to_cursor(&mut self) {
+        let Some((location, _)) = self.cursor_location() else {
+            return;
+        };
+        self.next_write.push(mi::break_insert_temporary(&location));
+        self.next_write.push(mi::exec_continue());
+        self.executing = true;
+    }
+
+    /// Get filtered symbols based on search input
+    pub fn get_filtered_symbols(&self) -> Vec<(usize, &Symbol)> {
+        // Filter based on search input, regardless of whether search mode is active
+        if self.symbols_search_input.value().is_empty() {
+            return self.symbols.iter().enumerate().collect();
+        }
+
+        let search_term = self.symbols_search_input.value().to_lowercase();
+        let mut results: Vec<(usize, &Symbol)> = self
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, sym)| {
+                // Simple fuzzy matching: check if all characters in search appear in order
+                let name_lower = sym.name.to_lowercase();
+                let mut name_chars = name_lower.chars();
+                search_term.chars().all(|c| name_chars.any(|nc| nc == c))
+            })
+            .collect();
+        // Sort exact matches to the top
+        results.sort_by(|(_, a), (_, b)| {
+            let a_exact = a.name.to_lowercase() == search_term;
+            let b_exact = b.name.to_lowercase() == search_term;
+            b_exact.cmp(&a_exact)
+        });
+        results
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Written {
+    /// Requested Register Value deref
+    // TODO: Could this just be the register name?
+    RegisterValue((String, u64)),
+    /// Requested Stack Bytes
+    ///
+    /// None - This is the first time this is requested
+    /// Some - This has alrady been read, and this is a deref, trust
+    ///        the `base_reg` of .0
+    Stack(Option<String>),
+    /// Requested page of the hexdump, by address
+    Memory(u64),
+    /// Requested re-read of a page of the hexdump after a stop, by address
+    MemoryRefresh(u64),
+    /// Requested Asm At $pc
+    AsmAtPc,
+    /// Requested symbol at addr for register (from deref)
+    SymbolAtAddrRegister((String, u64)),
+    /// Requested symbol at addr for stack (from deref)
+    SymbolAtAddrStack(String),
+    /// Requested size of arch ptr for `ptr_size`
+    SizeOfVoidStar,
+    /// Requested list of all symbols
+    SymbolList,
+    /// Requested disassembly of a specific symbol by name
+    SymbolDisassembly(String),
+    /// Requested address lookup for symbol (to disassemble it next)
+    SymbolAddressLookup(String),
+    /// Requested new value of a variable object, answered with its value
+    VarAssign,
+    /// Requested variable object of an expression, by name
+    ExpressionCreate(String),
+    /// Requested memory write of hexdump edits, at addr
+    MemoryWrite(u64),
+    /// Requested read back of a run of hexdump edits after writing it, by address
+    MemoryWriteCheck(BTreeMap<u64, u8>),
+    /// Requested page of a memory snapshot by name and address, `None` for the live memory of
+    /// the memory diff
+    SnapshotPage(Option<String>, u64),
+    /// Requested target bytes a file load overwrites, for its preview
+    LoadPreview,
+    /// Requested memory write of a chunk of a file load, by length
+    LoadChunk(usize),
+    /// Requested layout of a C type with `ptype /o`, for the overlay
+    TypeLayout(TypeOverlay),
+    /// Requested `info frame` of a frame level, to scroll the Stack pane to its CFA
+    FrameCfa(usize),
+    /// Requested `info frame` of a frame level, for its [`FrameLayout`]
+    FrameInfo(usize),
+    /// Requested locals of a frame level, to ask for the addresses of its variables
+    FrameLocals(usize),
+    /// Requested addresses of the variables of a frame level, by name
+    FrameVars(usize, Vec<String>),
+    /// Requested value of the stack protector canary
+    StackCanary,
+    /// Requested assignment of a register, by name
+    RegisterAssign(String),
+    /// Requested register values after an assignment, which read the stack again when `stack`
+    RegisterRefresh { stack: bool },
+    /// Requested target description XML, for the bit fields of the registers
+    TargetDescription,
+    /// Requested address of a linker symbol at an end of the stack
+    StackSymbol(StackEnd),
+    /// Requested chunk of memory for a `find` command, by job id
+    FindChunk(usize),
+    /// Requested memory of the inspector telescope
+    Telescope,
+    /// Requested symbol at addr for the inspector telescope (from deref)
+    SymbolAtAddrTelescope,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    // initialize logging, to log_path if available
+    init_logging(args.log_path.as_ref())?;
+
+    // Check for valid cmd file
+    if let Some(cmds) = &args.cmds
+        && !cmds.exists()
+    {
+        anyhow::bail!("Filepath for --cmds does not exist: `{}`", cmds.display());
+    }
+    // Start rx thread
+    let (gdb_stdout, mut app) = App::new_stream(args.clone());
+    let state = State::new(args.clone());
+    let mut state_share = StateShare { state: Arc::new(Mutex::new(state)) };
+
+    // Setup terminal
+    let mut terminal = ratatui::init();
+    execute!(terminal.backend_mut(), EnableMouseCapture)?;
+
+    spawn_gdb_interact(&state_share, gdb_stdout);
+
+    // Give the inferior its own tty so its stdout/stderr don't share gdb's MI pipe
+    // Written directly (not via next_write) so it reaches gdb before any --cmds "run"
     if let Some(pty) = &app.inferior_pty {
         write_mi(&app.gdb_stdin, &format!("-inferior-tty-set {}", pty.tty_path));
         inferior::spawn_reader(pty, Arc::clone(&state_share.state));
@@ -754,54 +2677,147 @@ where
                     }
                     _ => {}
                 }
-                continue;
-            }
-            let Event::Key(key) = event else {
-                continue;
-            };
-            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
-                gdb::write_mi(&app.gdb_stdin, "-exec-interrupt");
-                let mut state = state_share.state.lock().unwrap();
-                state.input.reset();
-                state.completions.clear();
-                continue;
-            }
-            let (input_mode, mode) = {
-                let state = state_share.state.lock().unwrap();
-                (state.input_mode, state.mode)
-            };
-            match (&input_mode, key.code, &mode) {
-                // hexdump popup
-                (_, KeyCode::Esc, Mode::OnlyHexdumpPopup) => {
+                continue;
+            }
+            let Event::Key(key) = event else {
+                continue;
+            };
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                gdb::write_mi(&app.gdb_stdin, "-exec-interrupt");
+                let mut state = state_share.state.lock().unwrap();
+                state.input.reset();
+                state.completions.clear();
+                continue;
+            }
+            let (input_mode, mode) = {
+                let state = state_share.state.lock().unwrap();
+                (state.input_mode, state.mode)
+            };
+            match (&input_mode, key.code, &mode) {
+                // hexdump popup
+                (_, KeyCode::Esc, Mode::OnlyHexdumpPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_popup = Input::default();
+                    state.view.hexdump_selection = None;
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, KeyCode::Char('S'), Mode::OnlyHexdumpPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.input.handle_event(&Event::Key(key));
+                }
+                (_, KeyCode::Tab, Mode::OnlyHexdumpPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_export = state.hexdump_export.next();
+                }
+                (_, KeyCode::Enter, Mode::OnlyHexdumpPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let val = state.hexdump_popup.value().to_string();
+                    state.save_hexdump(&val);
+                    state.hexdump_popup = Input::default();
+                    state.view.hexdump_selection = None;
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, _, Mode::OnlyHexdumpPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_popup.handle_event(&Event::Key(key));
+                }
+                // hexdump load popup
+                (_, KeyCode::Esc, Mode::OnlyHexdumpLoadPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_load_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, KeyCode::Enter, Mode::OnlyHexdumpLoadPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let val = state.hexdump_load_popup.value().to_string();
+                    state.hexdump_load_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                    if let Err(e) = state.prepare_memory_load(&val) {
+                        state.output.push(format!("h> {e}"));
+                    }
+                }
+                (_, _, Mode::OnlyHexdumpLoadPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_load_popup.handle_event(&Event::Key(key));
+                }
+                // hexdump type overlay popup
+                (_, KeyCode::Esc, Mode::OnlyHexdumpTypePopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_type_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, KeyCode::Enter, Mode::OnlyHexdumpTypePopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let val = state.hexdump_type_popup.value().to_string();
+                    state.hexdump_type_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                    if let Err(e) = state.request_type_overlay(&val) {
+                        state.output.push(format!("h> {e}"));
+                    }
+                }
+                (_, _, Mode::OnlyHexdumpTypePopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_type_popup.handle_event(&Event::Key(key));
+                }
+                // hexdump bookmark popup
+                (_, KeyCode::Esc, Mode::OnlyHexdumpLabelPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.label_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, KeyCode::Enter, Mode::OnlyHexdumpLabelPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let val = state.label_popup.value().to_string();
+                    state.label_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                    if let Err(e) = state.bookmark_hexdump(&val) {
+                        state.output.push(format!("h> {e}"));
+                    }
+                }
+                (_, _, Mode::OnlyHexdumpLabelPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.label_popup.handle_event(&Event::Key(key));
+                }
+                // bookmarks list
+                (_, KeyCode::Esc, Mode::OnlyHexdumpBookmarks) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_popup = Input::default();
                     state.mode = Mode::OnlyHexdump;
                 }
-                (_, KeyCode::Char('S'), Mode::OnlyHexdumpPopup) => {
+                (_, KeyCode::Enter, Mode::OnlyHexdumpBookmarks) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.input.handle_event(&Event::Key(key));
+                    state.goto_bookmark();
                 }
-                (_, KeyCode::Enter, Mode::OnlyHexdumpPopup) => {
+                (_, KeyCode::Char('j') | KeyCode::Down, Mode::OnlyHexdumpBookmarks) => {
                     let mut state = state_share.state.lock().unwrap();
-                    let val = state.hexdump_popup.clone();
-                    let val = val.value();
-
-                    if let Some(hexdump) = state.hexdump.as_ref()
-                        && let Some(path) = resolve_home(val)
-                        && std::fs::write(&path, &hexdump.1).is_ok()
-                    {
-                        state.output.push(format!(
-                            "h> hexdump succesfully written to {}",
-                            path.to_str().unwrap()
-                        ));
+                    let last = state.labels.len().saturating_sub(1);
+                    state.bookmarks_selected = (state.bookmarks_selected + 1).min(last);
+                }
+                (_, KeyCode::Char('k') | KeyCode::Up, Mode::OnlyHexdumpBookmarks) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.bookmarks_selected = state.bookmarks_selected.saturating_sub(1);
+                }
+                (_, KeyCode::Char('d'), Mode::OnlyHexdumpBookmarks) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let name =
+                        state.labels.iter().nth(state.bookmarks_selected).map(|l| l.name.clone());
+                    if let Some(name) = name {
+                        state.labels.remove(&name);
                     }
-                    state.hexdump_popup = Input::default();
+                    let last = state.labels.len().saturating_sub(1);
+                    state.bookmarks_selected = state.bookmarks_selected.min(last);
+                }
+                (_, _, Mode::OnlyHexdumpBookmarks) => {}
+                (_, KeyCode::Esc, Mode::OnlyHexdumpLoadConfirm) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.view.memory_load = None;
                     state.mode = Mode::OnlyHexdump;
                 }
-                (_, _, Mode::OnlyHexdumpPopup) => {
+                (_, KeyCode::Enter, Mode::OnlyHexdumpLoadConfirm) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_popup.handle_event(&Event::Key(key));
+                    state.start_memory_load();
+                    state.mode = Mode::OnlyHexdump;
                 }
+                (_, _, Mode::OnlyHexdumpLoadConfirm) => {}
                 // hexdump goto popup
                 (_, KeyCode::Esc, Mode::OnlyHexdumpGotoPopup) => {
                     let mut state = state_share.state.lock().unwrap();
@@ -812,14 +2828,16 @@ where
                     let mut state = state_share.state.lock().unwrap();
                     let val = state.hexdump_goto_popup.value().to_string();
                     let trimmed = val.trim().trim_start_matches("0x");
-                    match u64::from_str_radix(trimmed, 16) {
+                    let label = state.labels.get(val.trim()).map(|label| label.addr);
+                    match label.map_or_else(|| u64::from_str_radix(trimmed, 16), Ok) {
                         Ok(addr) => {
-                            if let Some((base, data)) = state.hexdump.as_ref() {
-                                let (base, len) = (*base, data.len());
+                            if let Some(memory) = state.view.hexdump.as_ref() {
+                                let (base, len) = (memory.start, memory.len);
                                 if addr >= base && (addr - base) < len as u64 {
-                                    let row = (addr - base) as usize / HEXDUMP_WIDTH;
-                                    let index = display_index_of_row(data, row);
-                                    state.hexdump_scroll.set(index);
+                                    let geometry = state.hexdump_geometry();
+                                    let row = (addr - base) as usize / geometry.width;
+                                    let index = display_index_of_row(memory, geometry, row);
+                                    state.view.hexdump_scroll.set(index);
                                 } else {
                                     state.output.push(format!(
                                         "h> 0x{addr:x} is outside the current hexdump (0x{base:x}..0x{:x})",
@@ -839,6 +2857,188 @@ where
                     let mut state = state_share.state.lock().unwrap();
                     state.hexdump_goto_popup.handle_event(&Event::Key(key));
                 }
+                // hexdump search popup
+                (_, KeyCode::Esc, Mode::OnlyHexdumpSearchPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_search_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, KeyCode::Enter, Mode::OnlyHexdumpSearchPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let query = state.hexdump_search_popup.value().to_string();
+                    state.search_hexdump(&query);
+                    state.hexdump_search_popup = Input::default();
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, _, Mode::OnlyHexdumpSearchPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_search_popup.handle_event(&Event::Key(key));
+                }
+                // hexdump edit
+                (_, KeyCode::Esc, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.view.hexdump_edits.clear();
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, KeyCode::Enter, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.write_hexdump_edits() {
+                        state.mode = Mode::OnlyHexdump;
+                    }
+                }
+                (_, KeyCode::Tab | KeyCode::BackTab, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.view.hexdump_cursor_ascii = !state.view.hexdump_cursor_ascii;
+                    state.view.hexdump_low_nibble = false;
+                }
+                (_, KeyCode::Left, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_hexdump_cursor(-1);
+                }
+                (_, KeyCode::Right, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_hexdump_cursor(1);
+                }
+                (_, KeyCode::Up, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let width = state.hexdump_width as isize;
+                    state.move_hexdump_cursor(-width);
+                }
+                (_, KeyCode::Down, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let width = state.hexdump_width as isize;
+                    state.move_hexdump_cursor(width);
+                }
+                (_, KeyCode::Backspace, Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.revert_hexdump_edit();
+                }
+                (_, KeyCode::Char(c), Mode::OnlyHexdumpEdit) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.view.hexdump_cursor_ascii {
+                        if c == ' ' || c.is_ascii_graphic() {
+                            state.edit_hexdump_ascii(c as u8);
+                        }
+                    } else if let Some(digit) = c.to_digit(16) {
+                        state.edit_hexdump_nibble(digit as u8);
+                    }
+                }
+                (_, _, Mode::OnlyHexdumpEdit) => {}
+                // hexdump selection
+                (_, KeyCode::Esc | KeyCode::Char('v'), Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.view.hexdump_selection = None;
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (_, KeyCode::Char('S') | KeyCode::Enter, Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyHexdumpPopup;
+                }
+                (_, KeyCode::Left | KeyCode::Char('h'), Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_hexdump_cursor(-1);
+                }
+                (_, KeyCode::Right | KeyCode::Char('l'), Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_hexdump_cursor(1);
+                }
+                (_, KeyCode::Up | KeyCode::Char('k'), Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let width = state.hexdump_width as isize;
+                    state.move_hexdump_cursor(-width);
+                }
+                (_, KeyCode::Down | KeyCode::Char('j'), Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let width = state.hexdump_width as isize;
+                    state.move_hexdump_cursor(width);
+                }
+                (_, KeyCode::Char('g'), Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.view.hexdump_cursor = 0;
+                }
+                (_, KeyCode::Char('G'), Mode::OnlyHexdumpSelect) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_hexdump_cursor(isize::MAX);
+                }
+                (_, _, Mode::OnlyHexdumpSelect) => {}
+                // watchpoint popup
+                (_, KeyCode::Esc, Mode::OnlyWatchpointsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoint_popup = Input::default();
+                    state.mode = Mode::OnlyWatchpoints;
+                }
+                (_, KeyCode::Enter, Mode::OnlyWatchpointsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let expr = state.watchpoint_popup.value().trim().to_string();
+                    if !expr.is_empty() {
+                        let cmd = mi::break_watch(state.watchpoint_popup_kind, &expr);
+                        state.next_write.push(cmd);
+                        state.next_write.push(mi::break_list());
+                    }
+                    state.watchpoint_popup = Input::default();
+                    state.mode = Mode::OnlyWatchpoints;
+                }
+                (_, _, Mode::OnlyWatchpointsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoint_popup.handle_event(&Event::Key(key));
+                }
+                // register popup
+                (_, KeyCode::Esc, Mode::OnlyRegisterPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.registers_popup = Input::default();
+                    state.mode = Mode::OnlyRegister;
+                }
+                (_, KeyCode::Enter, Mode::OnlyRegisterPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let value = state.registers_popup.value().trim().to_string();
+                    if !value.is_empty() {
+                        state.assign_register(&value);
+                    }
+                    state.registers_popup = Input::default();
+                    state.mode = Mode::OnlyRegister;
+                }
+                (_, _, Mode::OnlyRegisterPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.registers_popup.handle_event(&Event::Key(key));
+                }
+                // locals popup
+                (_, KeyCode::Esc, Mode::OnlyLocalsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.locals_popup = Input::default();
+                    state.mode = Mode::OnlyLocals;
+                }
+                (_, KeyCode::Enter, Mode::OnlyLocalsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let value = state.locals_popup.value().trim().to_string();
+                    if !value.is_empty() {
+                        state.assign_local(&value);
+                    }
+                    state.locals_popup = Input::default();
+                    state.mode = Mode::OnlyLocals;
+                }
+                (_, _, Mode::OnlyLocalsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.locals_popup.handle_event(&Event::Key(key));
+                }
+                // expressions popup
+                (_, KeyCode::Esc, Mode::OnlyExpressionsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.expressions_popup = Input::default();
+                    state.mode = Mode::OnlyLocals;
+                }
+                (_, KeyCode::Enter, Mode::OnlyExpressionsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let text = state.expressions_popup.value().trim().to_string();
+                    if !text.is_empty() {
+                        state.add_expression(&text);
+                    }
+                    state.expressions_popup = Input::default();
+                    state.mode = Mode::OnlyLocals;
+                }
+                (_, _, Mode::OnlyExpressionsPopup) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.expressions_popup.handle_event(&Event::Key(key));
+                }
                 // quit confirmation
                 (_, KeyCode::Enter, Mode::QuitConfirmation) => {
                     return Ok(());
@@ -853,6 +3053,102 @@ where
                     state.mode = state.previous_mode;
                 }
                 (_, _, Mode::Help) => {}
+                // expression inspector
+                (_, KeyCode::Esc, Mode::InspectorInput) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    // back to the result, or close when there is none yet
+                    if state.view.inspector.is_empty() && state.inspector_error.is_none() {
+                        state.mode = state.previous_mode;
+                    } else {
+                        state.mode = Mode::Inspector;
+                    }
+                }
+                (_, KeyCode::Enter, Mode::InspectorInput) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let text = state.inspector_input.value().trim().to_string();
+                    if !text.is_empty() {
+                        state.inspect(&text);
+                        state.mode = Mode::Inspector;
+                    }
+                }
+                (_, KeyCode::Up, Mode::InspectorInput) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    inspector_history_up(&mut state);
+                }
+                (_, KeyCode::Down, Mode::InspectorInput) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    inspector_history_down(&mut state);
+                }
+                (_, _, Mode::InspectorInput) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.inspector_input.handle_event(&Event::Key(key));
+                }
+                (_, KeyCode::Esc | KeyCode::Char('q'), Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = state.previous_mode;
+                }
+                (_, KeyCode::Char('i'), Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::InspectorInput;
+                }
+                (_, KeyCode::Char('g'), Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.inspector_selected = 0;
+                }
+                (_, KeyCode::Char('G'), Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.inspector_selected = state.view.inspector.len().saturating_sub(1);
+                }
+                (_, KeyCode::Char('j') | KeyCode::Down, Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.inspector_selected + 1 < state.view.inspector.len() {
+                        state.inspector_selected += 1;
+                    }
+                }
+                (_, KeyCode::Char('k') | KeyCode::Up, Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.inspector_selected = state.inspector_selected.saturating_sub(1);
+                }
+                (_, KeyCode::Enter | KeyCode::Char(' '), Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.toggle_inspector_row();
+                }
+                (_, KeyCode::Char('H'), Mode::Inspector) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if let Some(addr) = state.inspector_pointer() {
+                        state.follow_in_hexdump(addr);
+                    }
+                }
+                (_, KeyCode::Char('t'), Mode::Inspector) if key.modifiers == KeyModifiers::NONE => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if let Some(addr) = state.inspector_pointer() {
+                        state.telescope(addr);
+                    }
+                }
+                // Alt+t and Alt+l still switch to the Threads and Locals views
+                (_, code, Mode::Inspector)
+                    if !(key.modifiers.contains(KeyModifiers::ALT)
+                        && matches!(code, KeyCode::Char('t' | 'l'))) => {}
+                (InputMode::Normal, KeyCode::Char('p'), _)
+                    if !matches!(mode, Mode::QuitConfirmation) && {
+                        let state = state_share.state.lock().unwrap();
+                        !(state.mode == Mode::OnlySymbols && state.symbols_search_active)
+                    } =>
+                {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.previous_mode = state.mode;
+                    state.inspector_input.reset();
+                    state.mode = Mode::InspectorInput;
+                }
+                (InputMode::Normal, KeyCode::Char('I'), _)
+                    if !matches!(mode, Mode::QuitConfirmation) && {
+                        let state = state_share.state.lock().unwrap();
+                        !(state.mode == Mode::OnlySymbols && state.symbols_search_active)
+                    } =>
+                {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.next_inferior();
+                }
                 (InputMode::Normal, KeyCode::Char('?'), _)
                     if !matches!(mode, Mode::QuitConfirmation) && {
                         let state = state_share.state.lock().unwrap();
@@ -928,6 +3224,23 @@ where
                     let mut state = state_share.state.lock().unwrap();
                     state.mode = Mode::OnlySource;
                 }
+                (_, KeyCode::F(10), _) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyWatchpoints;
+                }
+                // F11 is taken by the fullscreen toggle of most terminals
+                (_, KeyCode::Char('t'), _) if key.modifiers.contains(KeyModifiers::ALT) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyThreads;
+                }
+                (_, KeyCode::F(12), _) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyBacktrace;
+                }
+                (_, KeyCode::Char('l'), _) if key.modifiers.contains(KeyModifiers::ALT) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyLocals;
+                }
                 (InputMode::Editing, KeyCode::Esc, _) => {
                     let mut state = state_share.state.lock().unwrap();
                     state.input_mode = InputMode::Normal;
@@ -950,19 +3263,54 @@ where
                 }
                 (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyRegister) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.registers_scroll.down(1);
+                    state.move_register_selection(1);
                 }
                 (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyRegister) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.registers_scroll.up(1);
+                    state.move_register_selection(-1);
                 }
                 (InputMode::Normal, KeyCode::Char('J'), Mode::OnlyRegister) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.registers_scroll.down(50);
+                    state.move_register_selection(50);
                 }
                 (InputMode::Normal, KeyCode::Char('K'), Mode::OnlyRegister) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.registers_scroll.up(50);
+                    state.move_register_selection(-50);
+                }
+                (InputMode::Normal, KeyCode::Char('e'), Mode::OnlyRegister) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.edit_register();
+                }
+                // stack
+                (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyStack) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.stack_scroll.down(1);
+                }
+                (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyStack) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.stack_scroll.up(1);
+                }
+                (InputMode::Normal, KeyCode::Char('J') | KeyCode::PageDown, Mode::OnlyStack) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let height = state.stack_viewport_height as usize;
+                    state.stack_scroll.down(height);
+                }
+                (InputMode::Normal, KeyCode::Char('K') | KeyCode::PageUp, Mode::OnlyStack) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let height = state.stack_viewport_height as usize;
+                    state.stack_scroll.up(height);
+                }
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyStack) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.stack_scroll.reset();
+                }
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyStack) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.stack_scroll.end();
+                }
+                (InputMode::Normal, KeyCode::Char('f'), Mode::OnlyStack) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.goto_stack_frame();
                 }
                 // output
                 (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyOutput) => {
@@ -997,7 +3345,7 @@ where
                 }
                 (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyMapping) => {
                     let mut state = state_share.state.lock().unwrap();
-                    if let Some(memory) = state.memory_map.as_ref() {
+                    if let Some(memory) = state.view.memory_map.as_ref() {
                         let len = memory.len();
                         if len > 0 {
                             state.memory_map_selected = len - 1;
@@ -1007,7 +3355,7 @@ where
                 }
                 (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyMapping) => {
                     let mut state = state_share.state.lock().unwrap();
-                    if let Some(memory) = state.memory_map.as_ref() {
+                    if let Some(memory) = state.view.memory_map.as_ref() {
                         let len = memory.len();
                         if state.memory_map_selected < len.saturating_sub(1) {
                             state.memory_map_selected += 1;
@@ -1038,7 +3386,7 @@ where
                 }
                 (InputMode::Normal, KeyCode::Char('J'), Mode::OnlyMapping) => {
                     let mut state = state_share.state.lock().unwrap();
-                    if let Some(memory) = state.memory_map.as_ref() {
+                    if let Some(memory) = state.view.memory_map.as_ref() {
                         let len = memory.len();
                         let new_selected =
                             (state.memory_map_selected + 50).min(len.saturating_sub(1));
@@ -1068,75 +3416,177 @@ where
                 }
                 (InputMode::Normal, KeyCode::Char('H'), Mode::OnlyMapping) => {
                     let mut state = state_share.state.lock().unwrap();
-                    if let Some(memory_map) = state.memory_map.as_ref()
+                    if let Some(memory_map) = state.view.memory_map.as_ref()
                         && let Some(selected_mapping) = memory_map.get(state.memory_map_selected)
                     {
-                        let s = data_read_memory_bytes(
-                            selected_mapping.start_address,
-                            0,
-                            selected_mapping.size,
-                        );
-                        state.next_write.push(s);
-                        state.written.push_back(Written::Memory);
-
+                        let (start, size) = (selected_mapping.start_address, selected_mapping.size);
+                        state.load_hexdump(start, size);
                         state.mode = Mode::OnlyHexdump;
-                        state.hexdump_scroll.reset();
                     }
                 }
-                // hexdump
-                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyHexdump) => {
+                // hexdump
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.view.hexdump_scroll.reset();
+                }
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.view.hexdump_scroll.end();
+                }
+                (InputMode::Normal, KeyCode::Char('S'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyHexdumpPopup;
+                }
+                (InputMode::Normal, KeyCode::Char('/'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if let Some(search) = &state.view.hexdump_search {
+                        state.hexdump_search_popup = Input::new(search.query.clone());
+                    }
+                    state.mode = Mode::OnlyHexdumpSearchPopup;
+                }
+                (InputMode::Normal, KeyCode::Char('n'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.next_search_match(true);
+                }
+                (InputMode::Normal, KeyCode::Char('N'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.next_search_match(false);
+                }
+                (InputMode::Normal, KeyCode::Char('e'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.edit_hexdump();
+                }
+                (InputMode::Normal, KeyCode::Char('v'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.select_hexdump();
+                }
+                (InputMode::Normal, KeyCode::Char('L'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyHexdumpLoadPopup;
+                }
+                (InputMode::Normal, KeyCode::Char('t'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyHexdumpTypePopup;
+                }
+                (InputMode::Normal, KeyCode::Char('m'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyHexdumpLabelPopup;
+                }
+                (InputMode::Normal, KeyCode::Char('\''), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let last = state.labels.len().saturating_sub(1);
+                    state.bookmarks_selected = state.bookmarks_selected.min(last);
+                    state.mode = Mode::OnlyHexdumpBookmarks;
+                }
+                (InputMode::Normal, KeyCode::Char(':'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.mode = Mode::OnlyHexdumpGotoPopup;
+                }
+                (InputMode::Normal, KeyCode::Char('H'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if let Some(find_heap) = state.find_first_heap() {
+                        state.load_hexdump(find_heap.start_address, find_heap.size);
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('T'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if let Some(bounds) = state.stack_bounds() {
+                        state.load_hexdump(bounds.start, bounds.end - bounds.start);
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('c'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.toggle_hexdump_changes_only();
+                }
+                (InputMode::Normal, KeyCode::Char('w'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.cycle_hexdump_width();
+                }
+                (InputMode::Normal, KeyCode::Char('u'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_layout.group = state.hexdump_layout.next_group();
+                }
+                (InputMode::Normal, KeyCode::Char('z'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.toggle_hexdump_collapse();
+                }
+                (InputMode::Normal, KeyCode::Char('a'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.hexdump_layout.encoding = state.hexdump_layout.encoding.next();
+                }
+                (InputMode::Normal, KeyCode::Char('h'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.inspect_hexdump_byte(-1);
+                }
+                (InputMode::Normal, KeyCode::Char('l'), Mode::OnlyHexdump) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.inspect_hexdump_byte(1);
+                }
+                // memory diff
+                (InputMode::Normal, KeyCode::Esc, Mode::OnlyHexdumpDiff) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.memory_diff = None;
+                    state.mode = Mode::OnlyHexdump;
+                }
+                (InputMode::Normal, KeyCode::Char('n'), Mode::OnlyHexdumpDiff) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.next_memory_difference(true);
+                }
+                (InputMode::Normal, KeyCode::Char('N'), Mode::OnlyHexdumpDiff) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_scroll.reset();
+                    state.next_memory_difference(false);
                 }
-                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyHexdump) => {
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyHexdumpDiff) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_scroll.end();
+                    if let Some(diff) = &mut state.memory_diff {
+                        diff.scroll.reset();
+                    }
                 }
-                (InputMode::Normal, KeyCode::Char('S'), Mode::OnlyHexdump) => {
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyHexdumpDiff) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.mode = Mode::OnlyHexdumpPopup;
+                    if let Some(diff) = &mut state.memory_diff {
+                        diff.scroll.end();
+                    }
                 }
-                (InputMode::Normal, KeyCode::Char(':'), Mode::OnlyHexdump) => {
+                (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyHexdumpDiff) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.mode = Mode::OnlyHexdumpGotoPopup;
+                    if let Some(diff) = &mut state.memory_diff {
+                        diff.scroll.down(1);
+                    }
                 }
-                (InputMode::Normal, KeyCode::Char('H'), Mode::OnlyHexdump) => {
+                (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyHexdumpDiff) => {
                     let mut state = state_share.state.lock().unwrap();
-                    if let Some(find_heap) = state.find_first_heap() {
-                        let s = data_read_memory_bytes(find_heap.start_address, 0, find_heap.size);
-                        state.next_write.push(s);
-                        state.written.push_back(Written::Memory);
-
-                        // reset position
-                        state.hexdump_scroll.reset();
+                    if let Some(diff) = &mut state.memory_diff {
+                        diff.scroll.up(1);
                     }
                 }
-                (InputMode::Normal, KeyCode::Char('T'), Mode::OnlyHexdump) => {
+                (InputMode::Normal, KeyCode::Char('J'), Mode::OnlyHexdumpDiff) => {
                     let mut state = state_share.state.lock().unwrap();
-                    if let Some(find_heap) = state.find_first_stack() {
-                        let s = data_read_memory_bytes(find_heap.start_address, 0, find_heap.size);
-                        state.next_write.push(s);
-                        state.written.push_back(Written::Memory);
-
-                        // reset position
-                        state.hexdump_scroll.reset();
+                    if let Some(diff) = &mut state.memory_diff {
+                        diff.scroll.down(50);
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('K'), Mode::OnlyHexdumpDiff) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if let Some(diff) = &mut state.memory_diff {
+                        diff.scroll.up(50);
                     }
                 }
                 (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyHexdump) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_scroll.down(1);
+                    state.view.hexdump_scroll.down(1);
                 }
                 (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyHexdump) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_scroll.up(1);
+                    state.view.hexdump_scroll.up(1);
                 }
                 (InputMode::Normal, KeyCode::Char('J'), Mode::OnlyHexdump) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_scroll.down(50);
+                    state.view.hexdump_scroll.down(50);
                 }
                 (InputMode::Normal, KeyCode::Char('K'), Mode::OnlyHexdump) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.hexdump_scroll.up(50);
+                    state.view.hexdump_scroll.up(50);
                 }
                 // symbols - list navigation
                 (InputMode::Normal, KeyCode::Char('r' | 'R'), Mode::OnlySymbols)
@@ -1333,30 +3783,239 @@ where
                         state.symbols_scroll.reset();
                     }
                 }
-                // source scrolling
+                // source cursor, the view scrolls to follow it
                 (InputMode::Normal, KeyCode::Char('g'), Mode::OnlySource) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.source_scroll.reset();
+                    state.source_cursor = 0;
                 }
                 (InputMode::Normal, KeyCode::Char('G'), Mode::OnlySource) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.source_scroll.end();
+                    state.source_cursor = state.view.source_lines.len().saturating_sub(1);
                 }
                 (InputMode::Normal, KeyCode::Char('j'), Mode::OnlySource) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.source_scroll.down(1);
+                    state.move_source_cursor(1);
                 }
                 (InputMode::Normal, KeyCode::Char('k'), Mode::OnlySource) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.source_scroll.up(1);
+                    state.move_source_cursor(-1);
                 }
                 (InputMode::Normal, KeyCode::Char('J'), Mode::OnlySource) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.source_scroll.down(50);
+                    state.move_source_cursor(50);
                 }
                 (InputMode::Normal, KeyCode::Char('K'), Mode::OnlySource) => {
                     let mut state = state_share.state.lock().unwrap();
-                    state.source_scroll.up(50);
+                    state.move_source_cursor(-50);
+                }
+                // instruction cursor
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyInstructions) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if !state.view.asm.is_empty() {
+                        state.asm_cursor = Some(0);
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyInstructions) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.asm_cursor = state.view.asm.len().checked_sub(1);
+                }
+                (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyInstructions) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_asm_cursor(1);
+                }
+                (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyInstructions) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_asm_cursor(-1);
+                }
+                (InputMode::Normal, KeyCode::Char('J'), Mode::OnlyInstructions) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_asm_cursor(50);
+                }
+                (InputMode::Normal, KeyCode::Char('K'), Mode::OnlyInstructions) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.move_asm_cursor(-50);
+                }
+                // watchpoints
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoints_selected = 0;
+                    state.watchpoints_scroll.reset();
+                }
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoints_selected = state.watchpoints().len().saturating_sub(1);
+                }
+                (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    let len = state.watchpoints().len();
+                    if state.watchpoints_selected + 1 < len {
+                        state.watchpoints_selected += 1;
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoints_selected = state.watchpoints_selected.saturating_sub(1);
+                }
+                (InputMode::Normal, KeyCode::Char('J'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoint_hits_scroll.down(1);
+                }
+                (InputMode::Normal, KeyCode::Char('K'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoint_hits_scroll.up(1);
+                }
+                (
+                    InputMode::Normal,
+                    KeyCode::Char(c @ ('w' | 'r' | 'a')),
+                    Mode::OnlyWatchpoints,
+                ) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.watchpoint_popup_kind = match c {
+                        'r' => WatchKind::Read,
+                        'a' => WatchKind::Access,
+                        _ => WatchKind::Write,
+                    };
+                    state.mode = Mode::OnlyWatchpointsPopup;
+                }
+                (InputMode::Normal, KeyCode::Char('d'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.delete_watchpoint();
+                }
+                (InputMode::Normal, KeyCode::Char('e'), Mode::OnlyWatchpoints) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.toggle_watchpoint();
+                }
+                // threads
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyThreads) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.threads_selected = 0;
+                }
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyThreads) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.threads_selected = state.threads.len().saturating_sub(1);
+                }
+                (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyThreads) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.threads_selected + 1 < state.threads.len() {
+                        state.threads_selected += 1;
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyThreads) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.threads_selected = state.threads_selected.saturating_sub(1);
+                }
+                (InputMode::Normal, KeyCode::Enter, Mode::OnlyThreads) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.select_thread();
+                }
+                // backtrace
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyBacktrace) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.bt_cursor = 0;
+                }
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyBacktrace) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.bt_cursor = state.view.bt.len().saturating_sub(1);
+                }
+                (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyBacktrace) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.bt_cursor + 1 < state.view.bt.len() {
+                        state.bt_cursor += 1;
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyBacktrace) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.bt_cursor = state.bt_cursor.saturating_sub(1);
+                }
+                (InputMode::Normal, KeyCode::Enter, Mode::OnlyBacktrace) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.select_frame();
+                }
+                // locals and expressions
+                (InputMode::Normal, KeyCode::Char('h'), Mode::OnlyLocals) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.expressions_focus = false;
+                }
+                (InputMode::Normal, KeyCode::Char('l'), Mode::OnlyLocals) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.expressions_focus = true;
+                }
+                (InputMode::Normal, KeyCode::Char('g'), Mode::OnlyLocals) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.expressions_focus {
+                        state.expressions_selected = 0;
+                    } else {
+                        state.locals_selected = 0;
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('G'), Mode::OnlyLocals) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.expressions_focus {
+                        state.expressions_selected = state.view.expressions.len().saturating_sub(1);
+                    } else {
+                        state.locals_selected = state.view.locals.len().saturating_sub(1);
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('j'), Mode::OnlyLocals) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.expressions_focus {
+                        if state.expressions_selected + 1 < state.view.expressions.len() {
+                            state.expressions_selected += 1;
+                        }
+                    } else if state.locals_selected + 1 < state.view.locals.len() {
+                        state.locals_selected += 1;
+                    }
+                }
+                (InputMode::Normal, KeyCode::Char('k'), Mode::OnlyLocals) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    if state.expressions_focus {
+                        state.expressions_selected = state.expressions_selected.saturating_sub(1);
+                    } else {
+                        state.locals_selected = state.locals_selected.saturating_sub(1);
+                    }
+                }
+                (
+                    InputMode::Normal,
+                    KeyCode::Char('a' | 'd' | 'f' | 'e' | ' ') | KeyCode::Enter,
+                    Mode::OnlyLocals,
+                ) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    match (key.code, state.expressions_focus) {
+                        (KeyCode::Char('a'), true) => state.mode = Mode::OnlyExpressionsPopup,
+                        (KeyCode::Char('d'), true) => state.delete_expression(),
+                        (KeyCode::Char('f'), true) => state.cycle_expression_format(),
+                        (KeyCode::Enter | KeyCode::Char(' '), false) => state.toggle_local(),
+                        (KeyCode::Char('e'), false) => {
+                            // aggregates are edited through their children
+                            if let Some(local) = state.view.locals.get(state.locals_selected)
+                                && local.var.numchild == 0
+                            {
+                                state.locals_popup =
+                                    Input::new(local.var.value.clone().unwrap_or_default());
+                                state.mode = Mode::OnlyLocalsPopup;
+                            }
+                        }
+                        // nothing to expand in the expressions pane, repeat the last command
+                        (KeyCode::Enter, true) => key_enter(app, &mut state)?,
+                        _ => (),
+                    }
+                }
+                // breakpoints and run-to at the cursor
+                (
+                    InputMode::Normal,
+                    KeyCode::Char('b'),
+                    Mode::OnlySource | Mode::OnlyInstructions,
+                ) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.toggle_breakpoint();
+                }
+                (
+                    InputMode::Normal,
+                    KeyCode::Char('u'),
+                    Mode::OnlySource | Mode::OnlyInstructions,
+                ) => {
+                    let mut state = state_share.state.lock().unwrap();
+                    state.run_to_cursor();
                 }
                 (_, KeyCode::Tab, _) => {
                     let mut state = state_share.state.lock().unwrap();
@@ -1421,6 +4080,32 @@ fn key_down(state: &mut State) {
     }
 }
 
+fn update_from_previous_inspection(state: &mut State) {
+    let history = &state.inspector_history;
+    if history.buffer.len() >= history.offset
+        && let Some(text) = history.buffer.get(history.buffer.len() - history.offset)
+    {
+        state.inspector_input = Input::new(text.clone());
+    }
+}
+
+fn inspector_history_up(state: &mut State) {
+    if state.inspector_history.offset < state.inspector_history.buffer.len() {
+        state.inspector_history.offset += 1;
+    }
+    update_from_previous_inspection(state);
+}
+
+fn inspector_history_down(state: &mut State) {
+    if state.inspector_history.offset != 0 {
+        state.inspector_history.offset -= 1;
+        if state.inspector_history.offset == 0 {
+            state.inspector_input.reset();
+        }
+    }
+    update_from_previous_inspection(state);
+}
+
 fn completion(app: &mut App, state: &mut State) -> Result<(), io::Error> {
     let val = state.input.clone();
     let val = val.value();
@@ -1437,10 +4122,26 @@ fn mouse_scroll(state: &mut State, up: bool, amount: usize) {
     let scroll = match state.mode {
         Mode::All | Mode::OnlyRegister => &mut state.registers_scroll,
         Mode::OnlyOutput => &mut state.output_scroll,
-        Mode::OnlyHexdump | Mode::OnlyHexdumpPopup | Mode::OnlyHexdumpGotoPopup => {
-            &mut state.hexdump_scroll
+        Mode::OnlyStack => &mut state.stack_scroll,
+        Mode::OnlyHexdump
+        | Mode::OnlyHexdumpPopup
+        | Mode::OnlyHexdumpGotoPopup
+        | Mode::OnlyHexdumpSearchPopup => &mut state.view.hexdump_scroll,
+        Mode::OnlyHexdumpDiff => match &mut state.memory_diff {
+            Some(diff) => &mut diff.scroll,
+            None => return,
+        },
+        // the scroll follows the cursor
+        Mode::OnlyHexdumpEdit | Mode::OnlyHexdumpSelect => {
+            let width = state.hexdump_width as isize;
+            state.move_hexdump_cursor(if up { -width } else { width } * amount as isize);
+            return;
+        }
+        Mode::OnlySource => {
+            state.move_source_cursor(if up { -(amount as isize) } else { amount as isize });
+            return;
         }
-        Mode::OnlySource => &mut state.source_scroll,
+        Mode::OnlyWatchpoints => &mut state.watchpoint_hits_scroll,
         _ => return,
     };
     if up {
@@ -1453,6 +4154,12 @@ fn mouse_scroll(state: &mut State, up: bool, amount: usize) {
 /// Handle a left mouse click at `(col, row)`: if it lands on a title-bar tab,
 /// switch to that mode. Returns true if the click was consumed
 fn mouse_click(state: &mut State, col: u16, row: u16) -> bool {
+    for (i, &(inferior_row, x_start, x_end)) in state.inferior_regions.iter().enumerate() {
+        if row == inferior_row && col >= x_start && col < x_end {
+            state.select_inferior(i);
+            return true;
+        }
+    }
     for (i, &(tab_row, x_start, x_end)) in state.tab_regions.iter().enumerate() {
         if row == tab_row && col >= x_start && col < x_end {
             if let Some(mode) = Mode::from_tab_index(i) {
@@ -1493,6 +4200,17 @@ fn process_line(app: &mut App, state: &mut State, val: &str) {
         replace_internal_variables(state, &mut val);
     }
 
+    // `find` of gdb separates its arguments with commas, the parens of a regex pattern are kept
+    if let Some(args) = val.strip_prefix("find ")
+        && !args.contains(',')
+    {
+        if let Err(e) = state.find(args) {
+            state.output.push(format!("f> {e}"));
+        }
+        state.input.reset();
+        return;
+    }
+
     // Resolve parens with expressions
     resolve_paren_expressions(&mut val);
 
@@ -1597,6 +4315,48 @@ fn process_line(app: &mut App, state: &mut State, val: &str) {
     } else if val.starts_with("file") {
         // we parse file, but still send it on
         state.save_filepath(&val);
+    } else if val == "flags-tdesc" {
+        state.request_target_description();
+    } else if let Some(path) = val.strip_prefix("label-save ") {
+        if let Err(e) = state.save_labels(path) {
+            state.output.push(format!("h> {e}"));
+        }
+        state.input.reset();
+        return;
+    } else if let Some(path) = val.strip_prefix("label-load ") {
+        if let Err(e) = state.load_labels(path) {
+            state.output.push(format!("h> {e}"));
+        }
+        state.input.reset();
+        return;
+    } else if val == "label" || val.starts_with("label ") {
+        if let Err(e) = state.label(&val["label".len()..]) {
+            state.output.push(format!("h> {e}"));
+        }
+        state.input.reset();
+        return;
+    } else if val == "stack-model" || val.starts_with("stack-model ") {
+        if let Err(e) = state.set_stack_model(&val["stack-model".len()..]) {
+            state.output.push(format!("h> {e}"));
+        }
+    } else if val == "stack-depth" || val.starts_with("stack-depth ") {
+        if let Err(e) = state.set_stack_depth(&val["stack-depth".len()..]) {
+            state.output.push(format!("h> {e}"));
+        }
+        state.input.reset();
+        return;
+    } else if let Some(args) = val.strip_prefix("snapshot-mem") {
+        if let Err(e) = state.snapshot_mem(args) {
+            state.output.push(format!("h> {e}"));
+        }
+        state.input.reset();
+        return;
+    } else if let Some(args) = val.strip_prefix("diff-mem") {
+        if let Err(e) = state.diff_mem(args) {
+            state.output.push(format!("h> {e}"));
+        }
+        state.input.reset();
+        return;
     } else if val.starts_with("hexdump") {
         debug!("hexdump: {val}");
         // don't send it on, parse the hexdump command
@@ -1620,9 +4380,7 @@ fn process_line(app: &mut App, state: &mut State, val: &str) {
             len.parse::<u64>().unwrap()
         };
 
-        let s = data_read_memory_bytes(addr_val, 0, len_val);
-        state.next_write.push(s);
-        state.written.push_back(Written::Memory);
+        state.load_hexdump(addr_val, len_val);
         state.input.reset();
         return;
     }
@@ -1630,10 +4388,10 @@ fn process_line(app: &mut App, state: &mut State, val: &str) {
     state.input.reset();
 }
 
-fn resolve_paren_expressions(val: &mut String) {
-    static RE_PAREN: std::sync::LazyLock<Regex> =
-        std::sync::LazyLock::new(|| Regex::new(r"\(([^()]+)\)").unwrap());
+static RE_PAREN: std::sync::LazyLock<Regex> =
+    std::sync::LazyLock::new(|| Regex::new(r"\(([^()]+)\)").unwrap());
 
+fn resolve_paren_expressions(val: &mut String) {
     *val = RE_PAREN
         .replace_all(&*val, |caps: &regex::Captures| {
             let expression = &caps[1];
@@ -1645,6 +4403,19 @@ fn resolve_paren_expressions(val: &mut String) {
         .to_string();
 }
 
+/// Like [`resolve_paren_expressions`], but keeps the parens that are not math, such as the
+/// casts and calls of a C expression
+fn resolve_paren_math(val: &mut String) {
+    *val = RE_PAREN
+        .replace_all(&*val, |caps: &regex::Captures| match evalexpr::eval(&caps[1]) {
+            Ok(result @ (evalexpr::Value::Int(_) | evalexpr::Value::Float(_))) => {
+                result.to_string()
+            }
+            _ => caps[0].to_string(),
+        })
+        .to_string();
+}
+
 enum MappingType {
     Start,
     End,
@@ -1670,7 +4441,7 @@ fn replace_internal_variables(state: &mut State, line: &mut String) {
 fn replace_mapping(state: &mut State, text: &mut String, mt: MappingType) {
     let ret = find_mapping(text, &mt);
     if let Some((path, prefix, start_idx, end_idx)) = ret
-        && let Some(ref memory_map) = state.memory_map
+        && let Some(ref memory_map) = state.view.memory_map
     {
         let resolve =
             memory_map.iter().filter(|a| a.path == Some(path.clone())).nth(prefix as usize);
@@ -1765,6 +4536,113 @@ mod tests {
         assert_eq!(scroll.scroll, 0);
     }
 
+    #[test]
+    fn test_scroll_keep_visible() {
+        let mut scroll = Scroll::default();
+        scroll.set_max_scroll(90);
+
+        // already visible, nothing moves
+        scroll.keep_visible(5, 10);
+        assert_eq!(scroll.scroll, 0);
+        // below the viewport, cursor lands on the last row
+        scroll.keep_visible(25, 10);
+        assert_eq!(scroll.scroll, 16);
+        // above the viewport, cursor lands on the first row
+        scroll.keep_visible(3, 10);
+        assert_eq!(scroll.scroll, 3);
+    }
+
+    fn test_state() -> State {
+        State::new(Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        })
+    }
+
+    #[test]
+    fn test_toggle_breakpoint_source() {
+        let mut state = test_state();
+        state.mode = Mode::OnlySource;
+        state.view.current_source_file = Some("/src/test.c".to_string());
+        state.view.source_lines = (1..=20).map(|i| format!("line {i}")).collect();
+        state.source_cursor = 9;
+
+        state.toggle_breakpoint();
+        assert_eq!(
+            state.next_write,
+            vec![r#"-break-insert "/src/test.c:10""#.to_string(), "-break-list".to_string()]
+        );
+
+        state.next_write.clear();
+        state.upsert_breakpoint(
+            mi::parse_breakpoint(r#"{number="2",enabled="y",fullname="/src/test.c",line="10"}"#)
+                .unwrap(),
+        );
+        state.toggle_breakpoint();
+        assert_eq!(
+            state.next_write,
+            vec!["-break-delete 2".to_string(), "-break-list".to_string()]
+        );
+
+        state.next_write.clear();
+        state.run_to_cursor();
+        assert_eq!(
+            state.next_write,
+            vec![r#"-break-insert -t "/src/test.c:10""#.to_string(), "-exec-continue".to_string()]
+        );
+        assert!(state.executing);
+    }
+
+    #[test]
+    fn test_asm_cursor_follows_pc() {
+        let mut state = test_state();
+        state.mode = Mode::OnlyInstructions;
+        state.view.asm = (0..4)
+            .map(|i| Asm {
+                address: 0x1000 + i * 4,
+                inst: "nop".into(),
+                offset: i * 4,
+                func_name: None,
+            })
+            .collect();
+        state.view.current_pc = 0x1008;
+        assert_eq!(state.asm_cursor_index(), Some(2));
+
+        state.move_asm_cursor(1);
+        assert_eq!(state.asm_cursor, Some(3));
+        state.move_asm_cursor(50);
+        assert_eq!(state.asm_cursor, Some(3));
+
+        state.toggle_breakpoint();
+        assert_eq!(state.next_write[0], "-break-insert *0x100c");
+    }
+
+    #[test]
+    fn test_select_frame() {
+        let mut state = test_state();
+        state.view.bt = vec![
+            Bt {
+                frame: mi::parse_frame(r#"{level="0",addr="0x401136",func="add"}"#),
+                args: vec![],
+            },
+            Bt {
+                frame: mi::parse_frame(
+                    r#"{level="1",addr="0x401180",func="main",file="test.c",fullname="/nonexistent/test.c",line="11"}"#,
+                ),
+                args: vec![],
+            },
+        ];
+        state.bt_cursor = 1;
+        state.select_frame();
+        assert_eq!(state.bt_selected, 1);
+        assert_eq!(state.next_write[0], "-stack-select-frame 1");
+        assert!(state.next_write.contains(&"-data-list-register-values x".to_string()));
+        assert_eq!(state.view.current_source_line, Some(11));
+    }
+
     fn run_a_bit(args: Args) -> (App, StateShare, Terminal<TestBackend>) {
         let (gdb_stdout, mut app) = App::new_stream(args.clone());
         let state = State::new(args.clone());
@@ -1879,8 +4757,8 @@ mod tests {
 
         let (_, state, terminal) = run_a_bit(args);
         let _output = terminal.backend();
-        let registers = state.state.lock().unwrap().registers.clone();
-        let stack = state.state.lock().unwrap().stack.clone();
+        let registers = state.state.lock().unwrap().view.registers.clone();
+        let stack = state.state.lock().unwrap().view.stack.clone();
 
         // rsi repeating
         assert!(registers[4].deref.repeated_pattern);
@@ -1947,7 +4825,7 @@ mod tests {
         // I'm not in love with this testing plan! If this becomes a problem, these
         // could be removed.
         let output = output.to_string();
-        let stack = state.state.lock().unwrap().stack.clone();
+        let stack = state.state.lock().unwrap().view.stack.clone();
         let mut entries: Vec<_> = stack.clone().into_iter().collect();
         entries.sort_by_key(|a| a.0);
         let first_stack = entries[0].0;
@@ -2000,7 +4878,7 @@ mod tests {
         let from = format!("0x{:02x}", first_stack + 112);
         let output = output.replace(&from, "<stack_14>");
 
-        let registers = state.state.lock().unwrap().registers.clone();
+        let registers = state.state.lock().unwrap().view.registers.clone();
         let from = format!(
             "0x{:02x}",
             u64::from_str_radix(
@@ -2131,6 +5009,467 @@ mod tests {
         assert_eq!(val, "No parentheses here");
     }
 
+    #[test]
+    fn test_resolve_paren_math() {
+        let mut val = "*(uint32_t*)(0x40021000 + 4)".to_string();
+        resolve_paren_math(&mut val);
+        assert_eq!(val, "*(uint32_t*)1073876996");
+
+        let mut val = "ctx->f(x)".to_string();
+        resolve_paren_math(&mut val);
+        assert_eq!(val, "ctx->f(x)");
+    }
+
+    #[test]
+    fn test_value_format() {
+        assert_eq!(ValueFormat::Natural.apply("65 'A'"), "65 'A'");
+        assert_eq!(ValueFormat::Hex.apply("65 'A'"), "0x41");
+        assert_eq!(ValueFormat::Decimal.apply("0x401136 <main>"), "4198710");
+        assert_eq!(ValueFormat::Binary.apply("5"), "0b101");
+        assert_eq!(ValueFormat::Char.apply("65"), "'A'");
+        assert_eq!(ValueFormat::Char.apply("10"), r"'\n'");
+        assert_eq!(ValueFormat::Char.apply("0xe9"), r"'\xe9'");
+        // wider than a byte
+        assert_eq!(ValueFormat::Char.apply("0x4142"), "0x4142");
+        assert_eq!(ValueFormat::Char.apply("-1"), "-1");
+        assert_eq!(ValueFormat::Hex.apply("-1"), "0xffffffffffffffff");
+        assert_eq!(ValueFormat::Decimal.apply("-1"), "-1");
+        // not an integer
+        assert_eq!(ValueFormat::Hex.apply("{...}"), "{...}");
+    }
+
+    #[test]
+    fn test_expression_recreated_on_change() {
+        let mut state = test_state();
+        state.add_expression("$rsp+(0x10 * 2)");
+        assert_eq!(state.next_write, vec![r#"-var-create expr0 @ "$rsp+32""#]);
+        assert!(matches!(state.written.back(), Some(Written::ExpressionCreate(n)) if n == "expr0"));
+
+        // unchanged on the next stop
+        state.next_write.clear();
+        state.written.clear();
+        state.create_expression(0);
+        assert!(state.next_write.is_empty());
+
+        // retried after a failure
+        state.view.expressions[0].error = Some("No registers.".to_string());
+        state.create_expression(0);
+        assert_eq!(state.next_write, vec![r#"-var-create expr0 @ "$rsp+32""#]);
+
+        state.next_write.clear();
+        state.view.expressions[0].error = None;
+        state.delete_expression();
+        assert_eq!(state.next_write, vec!["-var-delete expr0"]);
+        assert!(state.view.expressions.is_empty());
+    }
+
+    #[test]
+    fn test_inspect_hexdump_byte() {
+        let mut state = test_state();
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x1000, &(1..=64).collect::<Vec<u8>>()));
+        state.hexdump_viewport_height = 2;
+        state.view.hexdump_scroll.set_max_scroll(2);
+        state.view.hexdump_cursor = 31;
+        state.inspect_hexdump_byte(1);
+        assert_eq!(state.view.hexdump_cursor, 32);
+        assert_eq!(state.view.hexdump_scroll.scroll, 1);
+
+        // a cursor scrolled out of view starts again from the first row shown
+        state.view.hexdump_scroll.set(2);
+        state.view.hexdump_cursor = 0;
+        state.inspect_hexdump_byte(-1);
+        assert_eq!(state.view.hexdump_cursor, 31);
+        assert_eq!(state.view.hexdump_scroll.scroll, 1);
+    }
+
+    #[test]
+    fn test_relayout_hexdump_keeps_top_row() {
+        let mut state = test_state();
+        let mut bytes: Vec<u8> = (1..=0x40).collect();
+        bytes.extend([0; 0x40]);
+        bytes.extend(1..=0x40);
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x1000, &bytes));
+        // rows 0x00..0x50, then `*`, then 0x80 at the top
+        state.view.hexdump_scroll.set_max_scroll(8);
+        state.view.hexdump_scroll.set(6);
+        state.toggle_hexdump_collapse();
+        assert!(!state.hexdump_layout.collapse);
+        assert_eq!(state.view.hexdump_scroll.scroll, 8);
+
+        state.cycle_hexdump_width();
+        assert_eq!(state.hexdump_width, 32);
+        assert_eq!(state.view.hexdump_scroll.scroll, 4);
+
+        // the auto width is picked by the next draw
+        state.toggle_hexdump_collapse();
+        state.cycle_hexdump_width();
+        assert_eq!(state.hexdump_layout.width, RowWidth::Auto);
+        assert_eq!(state.hexdump_width, 32);
+        state.cycle_hexdump_width();
+        assert_eq!(state.hexdump_width, 8);
+        assert_eq!(state.view.hexdump_scroll.scroll, 10);
+    }
+
+    #[test]
+    fn test_labels_and_bookmarks() {
+        let mut state = test_state();
+        assert!(state.label("0x20001000 0x100 rx_ring").is_ok());
+        assert!(state.label("0x20001000").is_err());
+        assert!(state.label("-d nope").is_err());
+
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x1000, &[0; 0x100]));
+        state.view.hexdump_cursor = 0x20;
+        assert!(state.bookmark_hexdump("0x30").is_err());
+        assert!(state.bookmark_hexdump("head 4").is_ok());
+        assert_eq!(state.labels.describe(0x1023).as_deref(), Some("head+0x3"));
+
+        let path = env::temp_dir().join(format!("heretek-labels-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        assert!(state.save_labels(path).is_ok());
+        assert!(state.label("-d head").is_ok());
+        assert_eq!(state.labels.len(), 1);
+        assert!(state.load_labels(path).is_ok());
+        fs::remove_file(path).unwrap();
+        assert_eq!(state.labels.len(), 2);
+        assert_eq!(state.output.last().unwrap(), &format!("h> loaded 2 labels from {path}"));
+
+        // the bookmark inside the hexdump moves the cursor, without reading it again
+        state.view.hexdump_cursor = 0;
+        state.bookmarks_selected = 0;
+        state.goto_bookmark();
+        assert_eq!(state.view.hexdump_cursor, 0x20);
+        assert!(state.next_write.is_empty());
+        assert_eq!(state.mode, Mode::OnlyHexdump);
+    }
+
+    #[test]
+    fn test_stack_depth() {
+        let mut state = test_state();
+        let register = mi::Register {
+            number: "7".to_string(),
+            value: Some("0x7ffffffff000".to_string()),
+            v2_int128: None,
+            v8_int32: None,
+            v4_int64: None,
+            v8_float: None,
+            v16_int8: None,
+            v4_int32: None,
+            error: None,
+        };
+        state.view.registers =
+            vec![RegisterStorage::new("rsp".to_string(), Some(register), Deref::new())];
+        state.view.memory_map = Some(vec![MemoryMapping {
+            start_address: 0x7ffffffde000,
+            end_address: 0x7ffffffff100,
+            size: 0x21100,
+            offset: 0,
+            permissions: Some("rw-p".to_string()),
+            path: Some("[stack]".to_string()),
+        }]);
+        // 0x100 bytes left in the mapping
+        assert_eq!(state.stack_limit(), 32);
+        state.read_stack();
+        assert_eq!(state.stack_slots, 32);
+        assert_eq!(state.next_write.len(), 33);
+        assert_eq!(state.next_write[31], "-data-read-memory-bytes $sp+0xf8 8");
+        assert_eq!(
+            state.next_write[32],
+            "-data-evaluate-expression (void*)*(void**)&__stack_chk_guard"
+        );
+        state.read_stack_slots(64);
+        assert_eq!(state.next_write.len(), 33);
+
+        assert!(state.set_stack_depth("4096").is_ok());
+        state.read_stack_slots(40);
+        assert_eq!(state.stack_slots, 64);
+        assert_eq!(state.next_write[33], "-data-read-memory-bytes $sp+0x100 8");
+        assert!(state.set_stack_depth("nope").is_err());
+        assert_eq!(state.stack_depth, StackDepth::Slots(4096));
+
+        // a smaller depth drops the slots past it
+        state.view.stack.insert(0x7ffffffff000, Deref::new());
+        state.view.stack.insert(0x7ffffffff010, Deref::new());
+        assert!(state.set_stack_depth("2").is_ok());
+        assert_eq!(state.stack_slots, 2);
+        assert_eq!(state.view.stack.len(), 1);
+    }
+
+    #[test]
+    fn test_smashed_stack_slots() {
+        let mut state = test_state();
+        state.view.frame_layouts = vec![FrameLayout {
+            level: 0,
+            func: Some("parse".to_string()),
+            has_source: true,
+            cfa: 0x7fffffffe0f0,
+            saved: vec![("rbp".to_string(), 0x7fffffffe0e0)],
+            vars: vec![],
+        }];
+        state.view.canary = Some(0x1122334455667700);
+        let mut deref = Deref::new();
+        deref.try_push(0x4141414141414141);
+        state.view.stack.insert(0x7fffffffe0d8, deref);
+        assert!(state.view.smashed.is_empty());
+
+        // overflowed before the first stop, the canary was never seen in its slot
+        state.update_smashed_stack_slots();
+        assert_eq!(state.view.smashed[&0x7fffffffe0d8], "canary of #0 is 0x4141414141414141");
+
+        // a variable lives below the saved frame pointer
+        state.view.frame_layouts[0].vars = vec![("len".to_string(), 0x7fffffffe0d8)];
+        state.update_smashed_stack_slots();
+        assert!(state.view.smashed.is_empty());
+    }
+
+    #[test]
+    fn test_stack_model() {
+        let mut state = test_state();
+        state.view.ptr_size = PtrSize::Size32;
+        let register = mi::Register {
+            number: "13".to_string(),
+            value: Some("0x20001f00".to_string()),
+            v2_int128: None,
+            v8_int32: None,
+            v4_int64: None,
+            v8_float: None,
+            v16_int8: None,
+            v4_int32: None,
+            error: None,
+        };
+        state.view.registers =
+            vec![RegisterStorage::new("psp".to_string(), Some(register), Deref::new())];
+        // no [stack] mapping, only the linker symbols
+        state.view.stack_model.linker_low = Some(0x20001000);
+        state.view.stack_model.linker_high = Some(0x20002000);
+        assert_eq!(state.stack_bounds(), Some(0x20001000..0x20002000));
+        assert_eq!(state.classify_val(0x20001800, ""), (true, false, false));
+        assert_eq!(state.classify_val(0x20002000, ""), (false, false, false));
+
+        assert!(state.set_stack_model("sp $psp").is_ok());
+        state.next_write.clear();
+        assert!(state.set_stack_model("grows up").is_ok());
+        assert_eq!(state.stack_pointer(), Some(0x20001f00));
+        // 0xf00 bytes down to the start of the stack, and the slot of $psp
+        assert_eq!(state.stack_limit(), 0x3c1);
+        assert_eq!(state.next_write[1], "-data-read-memory-bytes $psp-0x04 4");
+
+        assert!(state.set_stack_model("bounds 0x20001e00 0x20001f40").is_ok());
+        assert_eq!(state.stack_limit(), 0x41);
+        assert!(state.set_stack_model("grows left").is_err());
+        state.output.clear();
+        assert!(state.set_stack_model("").is_ok());
+        assert_eq!(
+            state.output[0],
+            "h> stack: sp $psp, grows up, bounds 0x20001e00..0x20001f40 from stack-model bounds"
+        );
+    }
+
+    #[test]
+    fn test_assign_register() {
+        let mut state = test_state();
+        let register = |value: Option<&str>| mi::Register {
+            number: "0".to_string(),
+            value: value.map(str::to_string),
+            v2_int128: None,
+            v8_int32: None,
+            v4_int64: None,
+            v8_float: None,
+            v16_int8: None,
+            v4_int32: None,
+            error: None,
+        };
+        state.view.registers = vec![
+            RegisterStorage::new("rax".to_string(), Some(register(Some("0x1"))), Deref::new()),
+            // not shown, so not a row
+            RegisterStorage::new("k0".to_string(), Some(register(None)), Deref::new()),
+            RegisterStorage::new("rbx".to_string(), Some(register(Some("0x2"))), Deref::new()),
+        ];
+        assert_eq!(state.shown_registers(), [0, 2]);
+        state.move_register_selection(5);
+        assert_eq!(state.registers_selected, 1);
+
+        state.edit_register();
+        assert_eq!(state.mode, Mode::OnlyRegisterPopup);
+        assert_eq!(state.registers_popup.value(), "0x2");
+        // the parens are math, the rest is left to gdb
+        state.assign_register("(0x10 + 0x20) * 2");
+        assert_eq!(state.next_write, [r#"-data-evaluate-expression "$rbx = 48 * 2""#]);
+        assert!(
+            matches!(state.written.front(), Some(Written::RegisterAssign(name)) if name == "rbx")
+        );
+
+        // nothing is assigned while running
+        state.executing = true;
+        state.assign_register("0");
+        assert_eq!(state.next_write.len(), 1);
+    }
+
+    #[test]
+    fn test_save_hexdump_selection() {
+        let mut state = test_state();
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x1000, &(0..32).collect::<Vec<u8>>()));
+        state.hexdump_viewport_height = 10;
+        state.view.hexdump_cursor = 0x12;
+        state.select_hexdump();
+        assert_eq!(state.mode, Mode::OnlyHexdumpSelect);
+        // backwards from where it started
+        state.move_hexdump_cursor(-2);
+        assert_eq!(state.hexdump_selection_range(), Some(0x10..0x13));
+
+        let path = env::temp_dir().join(format!("heretek-export-{}.h", std::process::id()));
+        let path = path.to_str().unwrap();
+        state.hexdump_export = ExportFormat::C;
+        state.save_hexdump(path);
+        let text = fs::read_to_string(path).unwrap();
+        assert!(text.contains(&format!("uint8_t heretek_export_{}[3]", std::process::id())));
+        assert!(text.contains("0x10, 0x11, 0x12,"));
+        assert_eq!(
+            state.output.last().unwrap(),
+            &format!("h> 0x3 bytes from 0x1010 written to {path} as C array")
+        );
+
+        // the whole hexdump without a selection
+        state.view.hexdump_selection = None;
+        state.hexdump_export = ExportFormat::Raw;
+        state.save_hexdump(path);
+        assert_eq!(fs::read(path).unwrap(), (0..32).collect::<Vec<u8>>());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_hexdump_edit() {
+        let mut state = test_state();
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x1000, &(0..32).collect::<Vec<u8>>()));
+        state.hexdump_viewport_height = 10;
+        state.view.hexdump_cursor = 17;
+        state.edit_hexdump();
+        assert_eq!(state.mode, Mode::OnlyHexdumpEdit);
+        assert_eq!(state.view.hexdump_cursor, 17);
+
+        // two nibbles, then the cursor moves on
+        state.edit_hexdump_nibble(0xa);
+        assert_eq!(state.hexdump_byte(17), Some(0xa1));
+        assert_eq!(state.view.hexdump_cursor, 17);
+        state.edit_hexdump_nibble(0xb);
+        assert_eq!(state.hexdump_byte(17), Some(0xab));
+        assert_eq!(state.view.hexdump_cursor, 18);
+
+        state.edit_hexdump_ascii(b'A');
+        assert_eq!(state.hexdump_byte(18), Some(b'A'));
+        state.revert_hexdump_edit();
+        assert_eq!(state.hexdump_byte(18), Some(18));
+
+        // stays within the buffer
+        state.move_hexdump_cursor(-100);
+        assert_eq!(state.view.hexdump_cursor, 0);
+        state.move_hexdump_cursor(100);
+        assert_eq!(state.view.hexdump_cursor, 31);
+
+        state.view.hexdump_edits.insert(30, 0);
+        // kept until the target stops
+        state.executing = true;
+        assert!(!state.write_hexdump_edits());
+        assert_eq!(state.output.last().unwrap(), "h> edits not written, the target is running");
+        assert!(state.next_write.is_empty());
+        state.executing = false;
+        assert!(state.write_hexdump_edits());
+        assert_eq!(
+            state.next_write,
+            vec![
+                "-data-write-memory-bytes 0x1011 ab",
+                "-data-read-memory-bytes 0x1011+0x00 1",
+                "-data-write-memory-bytes 0x101e 00",
+                "-data-read-memory-bytes 0x101e+0x00 1",
+            ]
+        );
+        assert!(matches!(state.written.front(), Some(Written::MemoryWrite(0x1011))));
+        assert!(
+            matches!(state.written.back(), Some(Written::MemoryWriteCheck(e)) if e.keys().eq([&0x101e]))
+        );
+    }
+
+    #[test]
+    fn test_hexdump_search() {
+        let mut state = test_state();
+        // the match sits in a run of zero rows
+        let mut buffer = vec![0u8; 0x80];
+        buffer[0x52] = 0x7f;
+        buffer[0x10] = 0x7f;
+        state.view.hexdump = Some(PagedMemory::from_bytes(0x1000, &buffer));
+        state.view.hexdump_scroll.set_max_scroll(10);
+
+        state.search_hexdump("7f");
+        let search = state.view.hexdump_search.as_ref().unwrap();
+        assert_eq!(search.matches, vec![(0x10, 1), (0x52, 1)]);
+        assert_eq!(state.view.hexdump_scroll.scroll, 1);
+
+        state.next_search_match(true);
+        // rows 0, 1, 2 and `*` come before the kept row 5
+        assert_eq!(state.view.hexdump_scroll.scroll, 4);
+        state.next_search_match(true);
+        assert_eq!(state.view.hexdump_scroll.scroll, 1);
+
+        state.search_hexdump("/(/");
+        assert!(state.output.last().unwrap().starts_with("h> invalid search /(/"));
+        state.search_hexdump("");
+        assert!(state.view.hexdump_search.is_none());
+    }
+
+    #[test]
+    fn test_inspect_replaces_root() {
+        let mut state = test_state();
+        state.inspect("argv[(1 + 1)]");
+        assert_eq!(state.next_write, vec![r#"-var-create inspect0 * "argv[2]""#]);
+        assert!(
+            matches!(state.written.back(), Some(Written::ExpressionCreate(n)) if n == "inspect0")
+        );
+        assert_eq!(state.view.inspector.len(), 1);
+        assert_eq!(state.view.inspector[0].var.exp, "argv[(1 + 1)]");
+
+        state.next_write.clear();
+        state.inspect("*node");
+        assert_eq!(
+            state.next_write,
+            vec![
+                "-var-delete inspect0".to_string(),
+                r#"-var-create inspect1 * "*node""#.to_string()
+            ]
+        );
+        assert_eq!(state.inspector_history.as_slice(), &["argv[(1 + 1)]", "*node"]);
+
+        state.inspector_history.offset = 0;
+        inspector_history_up(&mut state);
+        assert_eq!(state.inspector_input.value(), "*node");
+        inspector_history_up(&mut state);
+        assert_eq!(state.inspector_input.value(), "argv[(1 + 1)]");
+        inspector_history_down(&mut state);
+        inspector_history_down(&mut state);
+        assert_eq!(state.inspector_input.value(), "");
+    }
+
+    #[test]
+    fn test_inspector_pointer() {
+        let mut state = test_state();
+        let var = mi::VarObj {
+            name: "inspect0".to_string(),
+            type_: Some("char *".to_string()),
+            value: Some(r#"0x402004 "hello""#.to_string()),
+            ..mi::VarObj::default()
+        };
+        state.view.inspector.push(Local { var, ..Local::default() });
+        assert_eq!(state.inspector_pointer(), Some(0x402004));
+
+        state.view.inspector[0].var.type_ = Some("int".to_string());
+        assert_eq!(state.inspector_pointer(), None);
+
+        state.view.inspector[0].var.type_ = Some("struct node *".to_string());
+        state.view.inspector[0].var.value = Some("0x0".to_string());
+        assert_eq!(state.inspector_pointer(), Some(0));
+        state.telescope(0);
+        assert!(state.next_write.is_empty());
+        assert!(state.view.inspector_telescope.is_some());
+    }
+
     #[test]
     fn test_mouse_click_selects_tab() {
         let args = Args {
@@ -0,0 +1,350 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Range;
+
+/// Bytes read by one `-data-read-memory-bytes` of the hexdump
+pub const PAGE_LEN: usize = 0x1000;
+
+/// Pages kept in memory, the least recently used are dropped first
+pub const CACHED_PAGES: usize = 256;
+
+/// Ranges of up to this many pages are read in full when loaded, larger ones as they are shown
+pub const EAGER_PAGES: usize = 64;
+
+/// Bytes written by one `-data-write-memory-bytes` of a file load
+pub const LOAD_CHUNK: usize = 0x1000;
+
+/// Target bytes shown before a file load overwrites them
+pub const LOAD_PREVIEW: usize = 0x80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Page {
+    /// Read requested, waiting for gdb
+    Pending,
+    Loaded(Vec<u8>),
+    /// The read failed, such as a guard page
+    Unreadable,
+}
+
+/// Virtual view of `len` bytes of target memory from `start`, read in pages of [`PAGE_LEN`]
+/// as they are needed and kept in a bounded LRU cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PagedMemory {
+    pub start: u64,
+    pub len: usize,
+    pages: BTreeMap<usize, Page>,
+    /// Number of `Page::Loaded` in `pages`
+    loaded: usize,
+    /// Page indices, least recently used first
+    lru: VecDeque<usize>,
+}
+
+impl PagedMemory {
+    pub fn new(start: u64, len: usize) -> Self {
+        Self { start, len, pages: BTreeMap::new(), loaded: 0, lru: VecDeque::new() }
+    }
+
+    /// Memory with every page loaded from `bytes`
+    #[cfg(test)]
+    pub fn from_bytes(start: u64, bytes: &[u8]) -> Self {
+        let mut memory = Self::new(start, bytes.len());
+        for (index, page) in bytes.chunks(PAGE_LEN).enumerate() {
+            memory.insert(index, page.to_vec());
+        }
+        memory
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.len.div_ceil(PAGE_LEN)
+    }
+
+    /// Every page is loaded
+    pub fn is_complete(&self) -> bool {
+        self.loaded == self.page_count()
+    }
+
+    /// Every read is answered, each page is loaded or unreadable
+    pub fn is_settled(&self) -> bool {
+        (0..self.page_count())
+            .all(|index| matches!(self.pages.get(&index), Some(Page::Loaded(_) | Page::Unreadable)))
+    }
+
+    /// Small enough to be read in full, and collapse its runs of zero rows
+    pub fn is_eager(&self) -> bool {
+        self.page_count() <= EAGER_PAGES
+    }
+
+    /// Address and length of page `index`
+    pub fn page_range(&self, index: usize) -> (u64, u64) {
+        let offset = index * PAGE_LEN;
+        (self.start + offset as u64, PAGE_LEN.min(self.len - offset) as u64)
+    }
+
+    /// Index of the page starting at `addr`, from the address of a read
+    pub fn page_at(&self, addr: u64) -> Option<usize> {
+        let offset = self.offset_of(addr)?;
+        (offset % PAGE_LEN == 0).then_some(offset / PAGE_LEN)
+    }
+
+    /// Offset of `addr`, when it is within the memory
+    pub fn offset_of(&self, addr: u64) -> Option<usize> {
+        let offset = usize::try_from(addr.checked_sub(self.start)?).ok()?;
+        (offset < self.len).then_some(offset)
+    }
+
+    pub fn page(&self, index: usize) -> Option<&Page> {
+        self.pages.get(&index)
+    }
+
+    /// Pages holding the bytes of `range` that still need a read, which are marked pending.
+    /// Every page of `range` becomes the most recently used
+    pub fn request(&mut self, range: Range<usize>) -> Vec<usize> {
+        let end = range.end.min(self.len);
+        if range.start >= end {
+            return vec![];
+        }
+        let mut missing = vec![];
+        for index in range.start / PAGE_LEN..=(end - 1) / PAGE_LEN {
+            if let Entry::Vacant(entry) = self.pages.entry(index) {
+                entry.insert(Page::Pending);
+                missing.push(index);
+            }
+            self.touch(index);
+        }
+        self.evict();
+        missing
+    }
+
+    pub fn insert(&mut self, index: usize, bytes: Vec<u8>) {
+        self.set_page(index, Page::Loaded(bytes));
+    }
+
+    pub fn set_unreadable(&mut self, index: usize) {
+        self.set_page(index, Page::Unreadable);
+    }
+
+    fn set_page(&mut self, index: usize, page: Page) {
+        self.loaded += usize::from(matches!(page, Page::Loaded(_)));
+        if let Some(Page::Loaded(_)) = self.pages.insert(index, page) {
+            self.loaded -= 1;
+        }
+        self.touch(index);
+        self.evict();
+    }
+
+    /// Forget the reads in flight, gdb drops them when the inferior runs
+    pub fn drop_pending(&mut self) {
+        self.pages.retain(|_, page| *page != Page::Pending);
+        self.lru.retain(|index| self.pages.contains_key(index));
+    }
+
+    /// Byte at `offset`, when its page is loaded
+    pub fn get(&self, offset: usize) -> Option<u8> {
+        match self.pages.get(&(offset / PAGE_LEN))? {
+            Page::Loaded(bytes) => bytes.get(offset % PAGE_LEN).copied(),
+            _ => None,
+        }
+    }
+
+    pub fn is_unreadable(&self, offset: usize) -> bool {
+        self.pages.get(&(offset / PAGE_LEN)) == Some(&Page::Unreadable)
+    }
+
+    /// Overwrite the loaded bytes from `offset`, such as read back after a write
+    pub fn write(&mut self, offset: usize, bytes: &[u8]) {
+        for (i, byte) in bytes.iter().enumerate() {
+            let offset = offset + i;
+            if let Some(Page::Loaded(page)) = self.pages.get_mut(&(offset / PAGE_LEN))
+                && let Some(b) = page.get_mut(offset % PAGE_LEN)
+            {
+                *b = *byte;
+            }
+        }
+    }
+
+    /// Every byte, once all pages are loaded
+    pub fn contiguous(&self) -> Option<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.len);
+        for index in 0..self.page_count() {
+            let Some(Page::Loaded(page)) = self.pages.get(&index) else {
+                return None;
+            };
+            bytes.extend_from_slice(page);
+        }
+        Some(bytes)
+    }
+
+    /// Runs of consecutive loaded pages, as (offset, bytes)
+    pub fn runs(&self) -> Vec<(usize, Vec<u8>)> {
+        self.runs_in(0..self.len)
+    }
+
+    /// Runs of consecutive loaded bytes within `range`, as (offset, bytes)
+    pub fn runs_in(&self, range: Range<usize>) -> Vec<(usize, Vec<u8>)> {
+        let end = range.end.min(self.len);
+        if range.start >= end {
+            return vec![];
+        }
+        let mut runs: Vec<(usize, Vec<u8>)> = vec![];
+        for (&index, page) in self.pages.range(range.start / PAGE_LEN..=(end - 1) / PAGE_LEN) {
+            let Page::Loaded(bytes) = page else {
+                continue;
+            };
+            let page_start = index * PAGE_LEN;
+            let from = range.start.max(page_start);
+            let to = end.min(page_start + bytes.len());
+            if from >= to {
+                continue;
+            }
+            let bytes = &bytes[from - page_start..to - page_start];
+            match runs.last_mut() {
+                Some((start, run)) if *start + run.len() == from => run.extend_from_slice(bytes),
+                _ => runs.push((from, bytes.to_vec())),
+            }
+        }
+        runs
+    }
+
+    /// Indices of the loaded pages
+    pub fn loaded(&self) -> Vec<usize> {
+        self.pages
+            .iter()
+            .filter(|(_, page)| matches!(page, Page::Loaded(_)))
+            .map(|(&index, _)| index)
+            .collect()
+    }
+
+    fn touch(&mut self, index: usize) {
+        self.lru.retain(|&i| i != index);
+        self.lru.push_back(index);
+    }
+
+    fn evict(&mut self) {
+        while self.lru.len() > CACHED_PAGES {
+            if let Some(index) = self.lru.pop_front()
+                && let Some(Page::Loaded(_)) = self.pages.remove(&index)
+            {
+                self.loaded -= 1;
+            }
+        }
+    }
+}
+
+/// A local file written into target memory a chunk at a time, once its preview is confirmed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryLoad {
+    pub path: String,
+    pub addr: u64,
+    pub bytes: Vec<u8>,
+    /// Target bytes overwritten by the start of the file
+    pub preview: Page,
+    /// Bytes written so far, `None` until confirmed
+    pub written: Option<usize>,
+}
+
+impl MemoryLoad {
+    pub fn new(path: String, addr: u64, bytes: Vec<u8>) -> Self {
+        Self { path, addr, bytes, preview: Page::Pending, written: None }
+    }
+
+    /// Length of the preview read
+    pub fn preview_len(&self) -> usize {
+        self.bytes.len().min(LOAD_PREVIEW)
+    }
+
+    /// Address and bytes of the next chunk to write
+    pub fn next_chunk(&self) -> Option<(u64, &[u8])> {
+        let written = self.written?;
+        let chunk = self.bytes.get(written..)?.chunks(LOAD_CHUNK).next()?;
+        Some((self.addr + written as u64, chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_pages() {
+        let mut memory = PagedMemory::new(0x1000, PAGE_LEN * 3 + 0x10);
+        assert_eq!(memory.page_count(), 4);
+        assert_eq!(memory.request(0x10..PAGE_LEN + 1), vec![0, 1]);
+        // already in flight
+        assert_eq!(memory.request(0..PAGE_LEN * 10), vec![2, 3]);
+        assert_eq!(memory.page_range(3), (0x1000 + PAGE_LEN as u64 * 3, 0x10));
+        assert_eq!(memory.page_at(0x1000 + PAGE_LEN as u64), Some(1));
+        assert_eq!(memory.page_at(0x1001), None);
+        assert_eq!(memory.page_at(0x800), None);
+
+        memory.drop_pending();
+        assert_eq!(memory.request(0..1), vec![0]);
+    }
+
+    #[test]
+    fn test_pages_loaded_and_unreadable() {
+        let mut memory = PagedMemory::new(0x1000, PAGE_LEN * 3);
+        memory.insert(0, vec![0x41; PAGE_LEN]);
+        memory.set_unreadable(1);
+        assert!(!memory.is_settled());
+        memory.insert(2, vec![0x42; PAGE_LEN]);
+        assert_eq!(memory.get(5), Some(0x41));
+        assert_eq!(memory.get(PAGE_LEN), None);
+        assert!(memory.is_unreadable(PAGE_LEN + 5));
+        assert!(memory.is_settled());
+        assert_eq!(memory.contiguous(), None);
+        let runs = memory.runs();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].0, PAGE_LEN * 2);
+        assert_eq!(memory.loaded(), vec![0, 2]);
+        assert!(!memory.is_complete());
+        let runs = memory.runs_in(PAGE_LEN - 2..PAGE_LEN * 2 + 3);
+        assert_eq!(runs, vec![(PAGE_LEN - 2, vec![0x41; 2]), (PAGE_LEN * 2, vec![0x42; 3])]);
+        memory.insert(1, vec![0x43; PAGE_LEN]);
+        assert!(memory.is_complete());
+        memory.insert(1, vec![0x43; PAGE_LEN]);
+        assert!(memory.is_complete());
+        memory.set_unreadable(1);
+        assert!(!memory.is_complete());
+
+        memory.write(PAGE_LEN - 1, &[1, 2]);
+        assert_eq!(memory.get(PAGE_LEN - 1), Some(1));
+        assert_eq!(memory.get(PAGE_LEN), None);
+    }
+
+    #[test]
+    fn test_least_recently_used_evicted() {
+        let mut memory = PagedMemory::new(0, PAGE_LEN * (CACHED_PAGES + 1));
+        for index in 0..CACHED_PAGES {
+            memory.insert(index, vec![0; PAGE_LEN]);
+        }
+        // page 0 is shown again, so page 1 is the least recently used
+        memory.request(0..1);
+        memory.insert(CACHED_PAGES, vec![0; PAGE_LEN]);
+        assert_eq!(memory.loaded().len(), CACHED_PAGES);
+        assert!(!memory.is_complete());
+        assert!(memory.get(0).is_some());
+        assert!(memory.page(1).is_none());
+        assert_eq!(memory.request(PAGE_LEN..PAGE_LEN + 1), vec![1]);
+    }
+
+    #[test]
+    fn test_load_chunks() {
+        let mut load = MemoryLoad::new("a".to_string(), 0x1000, vec![1; LOAD_CHUNK + 3]);
+        assert_eq!(load.preview_len(), LOAD_PREVIEW);
+        assert_eq!(load.next_chunk(), None);
+        load.written = Some(0);
+        assert_eq!(load.next_chunk().map(|(addr, c)| (addr, c.len())), Some((0x1000, LOAD_CHUNK)));
+        load.written = Some(LOAD_CHUNK);
+        assert_eq!(load.next_chunk(), Some((0x1000 + LOAD_CHUNK as u64, &[1, 1, 1][..])));
+        load.written = Some(LOAD_CHUNK + 3);
+        assert_eq!(load.next_chunk(), None);
+    }
+
+    #[test]
+    fn test_contiguous() {
+        let bytes: Vec<u8> = (0..PAGE_LEN + 3).map(|i| i as u8).collect();
+        let memory = PagedMemory::from_bytes(0x1000, &bytes);
+        assert!(memory.is_eager());
+        assert_eq!(memory.contiguous(), Some(bytes));
+    }
+}
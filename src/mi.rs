@@ -183,11 +183,30 @@ pub fn parse_key_value_pairs(input: &str) -> HashMap<String, String> {
     let mut current_value = String::new();
     let mut inside_quotes = false;
     let mut bracket_count = 0;
+    let mut escaped = false;
 
     let mut is_parsing_value = false;
 
     for c in input.chars() {
+        // an escaped char inside a c-string (e.g. `\"`) never ends the string
+        if escaped {
+            escaped = false;
+            if is_parsing_value {
+                current_value.push(c);
+            } else {
+                current_key.push(c);
+            }
+            continue;
+        }
         match c {
+            '\\' if inside_quotes => {
+                escaped = true;
+                if is_parsing_value {
+                    current_value.push(c);
+                } else {
+                    current_key.push(c);
+                }
+            }
             '=' if !inside_quotes && bracket_count == 0 => {
                 // Start parsing the value
                 is_parsing_value = true;
@@ -201,13 +220,13 @@ pub fn parse_key_value_pairs(input: &str) -> HashMap<String, String> {
                 current_value.clear();
                 is_parsing_value = false;
             }
-            '[' if !inside_quotes => {
-                // Start of a bracketed value
+            '[' | '{' if !inside_quotes => {
+                // Start of a bracketed value (list or tuple)
                 bracket_count += 1;
                 current_value.push(c);
             }
-            ']' if !inside_quotes => {
-                // End of a bracketed value
+            ']' | '}' if !inside_quotes => {
+                // End of a bracketed value (list or tuple)
                 bracket_count -= 1;
                 current_value.push(c);
             }
@@ -239,6 +258,304 @@ pub fn parse_key_value_pairs(input: &str) -> HashMap<String, String> {
     map
 }
 
+/// Parse a MI tuple such as `{number="1",type="breakpoint"}` into its key-value pairs
+pub fn parse_tuple(input: &str) -> HashMap<String, String> {
+    let inner = input.trim();
+    let inner = inner.strip_prefix('{').unwrap_or(inner);
+    let inner = inner.strip_suffix('}').unwrap_or(inner);
+    parse_key_value_pairs(inner)
+}
+
+/// Parse a "0x" prefixed address
+fn parse_addr(input: &str) -> Option<u64> {
+    u64::from_str_radix(input.strip_prefix("0x")?, 16).ok()
+}
+
+/// Info from a MI "`frame`" tuple
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Frame {
+    pub level: u32,
+    pub addr: u64,
+    pub func: Option<String>,
+    pub file: Option<String>,
+    pub fullname: Option<String>,
+    pub line: Option<u32>,
+    /// Shared library the frame is within, when there is no debug info
+    pub from: Option<String>,
+}
+
+impl Frame {
+    /// "file:line" when known, falling back to the library
+    pub fn location(&self) -> Option<String> {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => Some(format!("{file}:{line}")),
+            _ => self.from.clone(),
+        }
+    }
+}
+
+pub fn parse_frame(input: &str) -> Frame {
+    let data = parse_tuple(input);
+    Frame {
+        level: data.get("level").and_then(|l| l.parse().ok()).unwrap_or(0),
+        addr: data.get("addr").and_then(|a| parse_addr(a)).unwrap_or(0),
+        func: data.get("func").cloned(),
+        file: data.get("file").cloned(),
+        fullname: data.get("fullname").cloned(),
+        line: data.get("line").and_then(|l| l.parse().ok()),
+        from: data.get("from").cloned(),
+    }
+}
+
+/// A local or argument from `-stack-list-arguments` or `-stack-list-variables`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variable {
+    pub name: String,
+    pub type_: Option<String>,
+    /// With `--simple-values`, not reported for structs, unions and arrays
+    pub value: Option<String>,
+    pub arg: bool,
+}
+
+/// Parse a list of variable tuples, such as the `args` of a frame
+pub fn parse_variables(input: &str) -> Vec<Variable> {
+    match_inner_items(input)
+        .filter_map(|capture| {
+            let data = parse_tuple(&capture[0]);
+            Some(Variable {
+                name: data.get("name")?.clone(),
+                type_: data.get("type").cloned(),
+                value: data.get("value").cloned(),
+                arg: data.get("arg").is_some_and(|a| a == "1"),
+            })
+        })
+        .collect()
+}
+
+/// Parse the "`stack-args`" of `-stack-list-arguments`, into the args of each frame level
+pub fn parse_stack_args(input: &str) -> Vec<(u32, Vec<Variable>)> {
+    match_inner_items(input)
+        .filter_map(|capture| {
+            let data = parse_tuple(&capture[0]);
+            let level = data.get("level")?.parse().ok()?;
+            let args = data.get("args").map(|a| parse_variables(a)).unwrap_or_default();
+            Some((level, args))
+        })
+        .collect()
+}
+
+/// Info from a `-thread-info` thread tuple
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thread {
+    pub id: String,
+    pub target_id: String,
+    pub name: Option<String>,
+    /// "stopped" or "running"
+    pub state: String,
+    /// Not reported for running threads
+    pub frame: Option<Frame>,
+    pub core: Option<String>,
+}
+
+/// Parse the "`threads`" list from `-thread-info`
+pub fn parse_threads(input: &str) -> Vec<Thread> {
+    let mut threads = vec![];
+    for capture in match_inner_items(input) {
+        let data = parse_tuple(&capture[0]);
+        let Some(id) = data.get("id") else {
+            continue;
+        };
+        threads.push(Thread {
+            id: id.clone(),
+            target_id: data.get("target-id").cloned().unwrap_or_default(),
+            name: data.get("name").cloned(),
+            state: data.get("state").cloned().unwrap_or_default(),
+            frame: data.get("frame").map(|f| parse_frame(f)),
+            core: data.get("core").cloned(),
+        });
+    }
+    threads
+}
+
+/// Info from a MI breakpoint tuple "`bkpt`"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub number: String,
+    /// gdb `type`, such as "breakpoint", "hw watchpoint" or "read watchpoint"
+    pub kind: String,
+    pub enabled: bool,
+    /// `None` for pending or multiple location breakpoints
+    pub addr: Option<u64>,
+    pub func: Option<String>,
+    pub file: Option<String>,
+    pub fullname: Option<String>,
+    pub line: Option<u32>,
+    pub cond: Option<String>,
+    /// Watched expression of a watchpoint
+    pub what: Option<String>,
+    /// Hit count
+    pub times: u32,
+}
+
+impl Breakpoint {
+    pub fn is_watchpoint(&self) -> bool {
+        self.kind.ends_with("watchpoint")
+    }
+
+    /// Breakpoint is set at `line` of the source file `file`
+    ///
+    /// `file` is the fullname from a stop event, but may fall back to the short filename
+    pub fn is_at_line(&self, file: &str, line: u32) -> bool {
+        if self.line != Some(line) {
+            return false;
+        }
+        match (&self.fullname, &self.file) {
+            (Some(fullname), _) if fullname == file => true,
+            (_, Some(short)) => file.ends_with(short.as_str()),
+            _ => false,
+        }
+    }
+}
+
+/// Parse a "`bkpt`" tuple, from `-break-insert`, `-break-list` or a `=breakpoint-*` notify
+pub fn parse_breakpoint(input: &str) -> Option<Breakpoint> {
+    let data = parse_tuple(input);
+    let number = data.get("number")?.clone();
+    let addr = data.get("addr").and_then(|a| parse_addr(a));
+    Some(Breakpoint {
+        number,
+        kind: data.get("type").cloned().unwrap_or_else(|| "breakpoint".to_string()),
+        enabled: data.get("enabled").is_none_or(|e| e == "y"),
+        addr,
+        func: data.get("func").cloned(),
+        file: data.get("file").cloned(),
+        fullname: data.get("fullname").cloned(),
+        line: data.get("line").and_then(|l| l.parse().ok()),
+        cond: data.get("cond").cloned(),
+        what: data.get("what").cloned(),
+        times: data.get("times").and_then(|t| t.parse().ok()).unwrap_or(0),
+    })
+}
+
+/// Access that triggers a watchpoint, see `-break-watch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    /// `watch`
+    Write,
+    /// `rwatch`
+    Read,
+    /// `awatch`
+    Access,
+}
+
+impl WatchKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WatchKind::Write => "watch",
+            WatchKind::Read => "rwatch",
+            WatchKind::Access => "awatch",
+        }
+    }
+}
+
+/// Value change reported by a `*stopped` watchpoint trigger
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchpointHit {
+    pub number: String,
+    pub exp: String,
+    /// Not reported for read watchpoints, or access watchpoints that only read
+    pub old: Option<String>,
+    pub new: Option<String>,
+    pub pc: u64,
+    pub func: Option<String>,
+}
+
+/// Parse a `watchpoint-trigger`, `read-watchpoint-trigger` or `access-watchpoint-trigger`
+/// from the key-values of a `*stopped` record
+pub fn parse_watchpoint_hit(kv: &HashMap<String, String>) -> Option<WatchpointHit> {
+    if !kv.get("reason")?.ends_with("watchpoint-trigger") {
+        return None;
+    }
+    let wpt = ["wpt", "hw-rwpt", "hw-awpt"].iter().find_map(|k| kv.get(*k))?;
+    let wpt = parse_tuple(wpt);
+    let value = kv.get("value").map(|v| parse_tuple(v)).unwrap_or_default();
+    let frame = kv.get("frame").map(|f| parse_tuple(f)).unwrap_or_default();
+    Some(WatchpointHit {
+        number: wpt.get("number")?.clone(),
+        exp: wpt.get("exp").cloned().unwrap_or_default(),
+        old: value.get("old").cloned(),
+        new: value.get("new").or_else(|| value.get("value")).cloned(),
+        pc: frame.get("addr").and_then(|a| parse_addr(a)).unwrap_or(0),
+        func: frame.get("func").cloned(),
+    })
+}
+
+/// Parse the `body` of a "`BreakpointTable`" from `-break-list`
+pub fn parse_breakpoint_table(input: &str) -> Vec<Breakpoint> {
+    let data = parse_tuple(input);
+    let Some(body) = data.get("body") else {
+        return vec![];
+    };
+    match_inner_items(body).filter_map(|capture| parse_breakpoint(&capture[0])).collect()
+}
+
+/// A gdb variable object, from `-var-create` or a `child` of `-var-list-children`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VarObj {
+    /// Name of the object within gdb, children are named "`parent.exp`"
+    pub name: String,
+    /// Expression of the object, the variable name for roots and the field or index for children
+    pub exp: String,
+    pub type_: Option<String>,
+    /// Aggregates are reported as "{...}"
+    pub value: Option<String>,
+    pub numchild: usize,
+}
+
+/// Parse the key-values of a variable object
+pub fn parse_var_obj(data: &HashMap<String, String>) -> Option<VarObj> {
+    Some(VarObj {
+        name: data.get("name")?.clone(),
+        exp: data.get("exp").cloned().unwrap_or_default(),
+        type_: data.get("type").cloned(),
+        value: data.get("value").cloned(),
+        numchild: data.get("numchild").and_then(|n| n.parse().ok()).unwrap_or(0),
+    })
+}
+
+/// Parse the "`children`" list from `-var-list-children`
+pub fn parse_var_children(input: &str) -> Vec<VarObj> {
+    match_inner_items(input)
+        .filter_map(|capture| parse_var_obj(&parse_tuple(&capture[0])))
+        .collect()
+}
+
+/// Entry of the "`changelist`" from `-var-update`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarChange {
+    pub name: String,
+    /// Not reported when out of scope
+    pub value: Option<String>,
+    pub in_scope: bool,
+    /// Reported when the children changed, such as a new type
+    pub new_num_children: Option<usize>,
+}
+
+/// Parse the "`changelist`" from `-var-update`
+pub fn parse_var_changelist(input: &str) -> Vec<VarChange> {
+    match_inner_items(input)
+        .filter_map(|capture| {
+            let data = parse_tuple(&capture[0]);
+            Some(VarChange {
+                name: data.get("name")?.clone(),
+                value: data.get("value").cloned(),
+                in_scope: data.get("in_scope").is_none_or(|s| s == "true"),
+                new_num_children: data.get("new_num_children").and_then(|n| n.parse().ok()),
+            })
+        })
+        .collect()
+}
+
 pub fn join_registers(
     register_names: &Vec<String>,
     registers: &[Option<Register>],
@@ -473,14 +790,25 @@ pub fn read_pc_value() -> String {
     "-data-evaluate-expression $pc".to_string()
 }
 
-pub fn data_read_sp_bytes(hex_offset: u64, len: u64) -> String {
-    format!("-data-read-memory-bytes $sp+0x{hex_offset:02x} {len}")
+/// Read `len` bytes `hex_offset` past the stack pointer expression `sp`, below it when the stack
+/// grows `up`
+pub fn data_read_sp_bytes(sp: &str, up: bool, hex_offset: u64, len: u64) -> String {
+    let sign = if up { '-' } else { '+' };
+    if sp.starts_with('$') && sp[1..].chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        format!("-data-read-memory-bytes {sp}{sign}0x{hex_offset:02x} {len}")
+    } else {
+        format!("-data-read-memory-bytes ({sp}){sign}0x{hex_offset:02x} {len}")
+    }
 }
 
 pub fn data_read_memory_bytes(addr: u64, hex_offset: u64, len: u64) -> String {
     format!("-data-read-memory-bytes 0x{addr:02x}+0x{hex_offset:02x} {len}")
 }
 
+pub fn data_write_memory_bytes(addr: u64, bytes: &[u8]) -> String {
+    format!("-data-write-memory-bytes 0x{addr:02x} {}", hex::encode(bytes))
+}
+
 pub fn data_disassemble_pc(before: usize, amt: usize) -> String {
     format!("-data-disassemble -s $pc-{before} -e $pc+{amt} -- 0")
 }
@@ -496,6 +824,124 @@ pub fn data_disassemble_function(name: &str) -> String {
     format!(r#"-data-disassemble -n "{name}" -- 0"#)
 }
 
+/// Location of a source line, for `-break-insert`
+pub fn linespec(file: &str, line: u32) -> String {
+    format!("\"{file}:{line}\"")
+}
+
+/// Location of an instruction address, for `-break-insert`
+pub fn addrspec(addr: u64) -> String {
+    format!("*0x{addr:02x}")
+}
+
+pub fn break_insert(location: &str) -> String {
+    format!("-break-insert {location}")
+}
+
+/// Breakpoint that gdb deletes once it is hit
+pub fn break_insert_temporary(location: &str) -> String {
+    format!("-break-insert -t {location}")
+}
+
+pub fn break_delete(number: &str) -> String {
+    format!("-break-delete {number}")
+}
+
+pub fn break_list() -> String {
+    "-break-list".to_string()
+}
+
+pub fn break_watch(kind: WatchKind, expr: &str) -> String {
+    let flag = match kind {
+        WatchKind::Write => "",
+        WatchKind::Read => "-r ",
+        WatchKind::Access => "-a ",
+    };
+    let expr = expr.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("-break-watch {flag}\"{expr}\"")
+}
+
+pub fn break_enable(number: &str) -> String {
+    format!("-break-enable {number}")
+}
+
+pub fn break_disable(number: &str) -> String {
+    format!("-break-disable {number}")
+}
+
+pub fn exec_continue() -> String {
+    "-exec-continue".to_string()
+}
+
+pub fn stack_list_frames() -> String {
+    "-stack-list-frames".to_string()
+}
+
+pub fn stack_list_arguments() -> String {
+    "-stack-list-arguments --simple-values".to_string()
+}
+
+pub fn stack_select_frame(level: usize) -> String {
+    format!("-stack-select-frame {level}")
+}
+
+pub fn thread_info() -> String {
+    "-thread-info".to_string()
+}
+
+pub fn thread_select(id: &str) -> String {
+    format!("-thread-select {id}")
+}
+
+/// Select an inferior without threads, `-thread-select` selects the others
+pub fn inferior_select(num: &str) -> String {
+    format!(r#"-interpreter-exec console "inferior {num}""#)
+}
+
+pub fn stack_list_variables() -> String {
+    "-stack-list-variables --simple-values".to_string()
+}
+
+/// Create the variable object `name` for `exp`, bound to the selected frame
+pub fn var_create(name: &str, exp: &str) -> String {
+    let exp = exp.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("-var-create {name} * \"{exp}\"")
+}
+
+/// Create the variable object `name` for `exp`, re-evaluated in the selected frame on update
+pub fn var_create_floating(name: &str, exp: &str) -> String {
+    let exp = exp.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("-var-create {name} @ \"{exp}\"")
+}
+
+pub fn var_delete(name: &str) -> String {
+    format!("-var-delete {name}")
+}
+
+pub fn var_list_children(name: &str) -> String {
+    format!("-var-list-children --all-values {name}")
+}
+
+pub fn var_update() -> String {
+    "-var-update --all-values *".to_string()
+}
+
+pub fn var_assign(name: &str, value: &str) -> String {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("-var-assign {name} \"{value}\"")
+}
+
+/// Assign `value` to register `name`, answered with its new value
+pub fn register_assign(name: &str, value: &str) -> String {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("-data-evaluate-expression \"${name} = {value}\"")
+}
+
+/// Target description XML of the registers and their types, on the console stream
+pub fn maint_print_xml_tdesc() -> String {
+    r#"-interpreter-exec console "maint print xml-tdesc""#.to_string()
+}
+
 pub fn info_functions() -> String {
     r#"-interpreter-exec console "info functions""#.to_string()
 }
@@ -504,6 +950,38 @@ pub fn info_address(symbol: &str) -> String {
     format!(r#"-interpreter-exec console "info address {symbol}""#)
 }
 
+/// Layout of a struct or union, with the offset and size of each member
+pub fn ptype_offsets(ty: &str) -> String {
+    let ty = ty.replace('\\', "\\\\").replace('"', "\\\"");
+    format!(r#"-interpreter-exec console "ptype /o {ty}""#)
+}
+
+/// Frame `level` with its canonical frame address, `frame at 0x..`
+pub fn info_frame(level: usize) -> String {
+    format!(r#"-interpreter-exec console "info frame level {level}""#)
+}
+
+/// Locals of frame `level` of the thread `thread`, or of the selected one
+pub fn stack_list_locals(thread: Option<&str>, level: usize) -> String {
+    match thread {
+        Some(thread) => {
+            format!("-stack-list-locals --thread {thread} --frame {level} --simple-values")
+        }
+        None => format!("-stack-list-locals --frame {level} --simple-values"),
+    }
+}
+
+/// Addresses of the variables `names` of frame `level`, on one line. Nothing is printed when one
+/// has no address, such as a variable in a register
+pub fn frame_var_addrs(level: usize, names: &[String]) -> String {
+    let formats = vec!["%p"; names.len()].join(" ");
+    let addrs: Vec<String> = names.iter().map(|name| format!("&{name}")).collect();
+    let cmd =
+        format!(r#"frame apply level {level} -q -s printf "{formats}\n", {}"#, addrs.join(", "));
+    let cmd = cmd.replace('\\', "\\\\").replace('"', "\\\"");
+    format!(r#"-interpreter-exec console "{cmd}""#)
+}
+
 #[allow(dead_code)]
 pub fn disassemble_function(name: &str) -> String {
     format!(r#"-interpreter-exec console "disassemble /r {name}""#)
@@ -674,6 +1152,19 @@ mod tests {
         assert_eq!(cmd, r#"-interpreter-exec console "disassemble /r foo_bar_123""#);
     }
 
+    #[test]
+    fn test_frame_var_addrs() {
+        let names = ["buf".to_string(), "argc".to_string()];
+        assert_eq!(
+            frame_var_addrs(1, &names),
+            r#"-interpreter-exec console "frame apply level 1 -q -s printf \"%p %p\\n\", &buf, &argc""#
+        );
+        assert_eq!(
+            stack_list_locals(Some("2"), 1),
+            "-stack-list-locals --thread 2 --frame 1 --simple-values"
+        );
+    }
+
     #[test]
     fn test_recursive_parsing() {
         let input = "*stopped,reason=\"breakpoint-hit\",disp=\"keep\",bkptno=\"1\",frame={addr=\"0x00007ffff7e04c48\",func=\"printf\",args=[],from=\"/usr/lib/libc.so.6\",arch=\"i386:x86-64\"},thread-id=\"1\",stopped-threads=\"all\",core=\"1\"";
@@ -684,7 +1175,10 @@ mod tests {
             assert_eq!(data.get("reason"), Some(&"breakpoint-hit".to_string()));
             assert_eq!(data.get("disp"), Some(&"keep".to_string()));
             assert_eq!(data.get("bkptno"), Some(&"1".to_string()));
-            // TODO: fix frame
+            let frame = parse_tuple(&data["frame"]);
+            assert_eq!(frame.get("func"), Some(&"printf".to_string()));
+            assert_eq!(frame.get("args"), Some(&"[]".to_string()));
+            assert_eq!(data.get("thread-id"), Some(&"1".to_string()));
         } else {
             panic!("Unexpected MIResponse type");
         }
@@ -710,7 +1204,9 @@ mod tests {
                 assert_eq!(data.get("thread-id"), Some(&"1".to_string()));
                 assert_eq!(data.get("stopped-threads"), Some(&"all".to_string()));
                 assert_eq!(data.get("core"), Some(&"2".to_string()));
-                // TODO: fix frame
+                let frame = parse_tuple(&data["frame"]);
+                assert_eq!(frame.get("addr"), Some(&"0x00007ffff7e04c48".to_string()));
+                assert_eq!(frame.get("arch"), Some(&"i386:x86-64".to_string()));
             }
             _ => panic!("Failed to parse AsyncRecord"),
         }
@@ -906,6 +1402,12 @@ Non-debugging symbols:
         assert!(cmd.contains("10"));
     }
 
+    #[test]
+    fn test_data_write_memory_bytes() {
+        let cmd = data_write_memory_bytes(0x601040, &[0xde, 0xad, 0x00]);
+        assert_eq!(cmd, "-data-write-memory-bytes 0x601040 dead00");
+    }
+
     #[test]
     fn test_data_read_memory_bytes() {
         let cmd = data_read_memory_bytes(0x7fffffffa000, 0, 16);
@@ -915,10 +1417,15 @@ Non-debugging symbols:
 
     #[test]
     fn test_data_read_sp_bytes() {
-        let cmd = data_read_sp_bytes(0x100, 8);
+        let cmd = data_read_sp_bytes("$sp", false, 0x100, 8);
         assert!(cmd.contains("$sp"));
         assert!(cmd.contains("0x100"));
         assert!(cmd.contains('8'));
+        assert_eq!(data_read_sp_bytes("$psp", true, 0x8, 4), "-data-read-memory-bytes $psp-0x08 4");
+        assert_eq!(
+            data_read_sp_bytes("$r13 & ~7", false, 0, 4),
+            "-data-read-memory-bytes ($r13 & ~7)+0x00 4"
+        );
     }
 
     #[test]
@@ -931,6 +1438,257 @@ Non-debugging symbols:
         assert_eq!(pairs.get("bkptno"), Some(&"1".to_string()));
     }
 
+    #[test]
+    fn test_parse_key_value_pairs_escaped_quote() {
+        let input = r#"cond="name == \"x,y\"",times="0""#;
+        let pairs = parse_key_value_pairs(input);
+
+        assert_eq!(pairs.get("cond"), Some(&r#"name == \"x,y\""#.to_string()));
+        assert_eq!(pairs.get("times"), Some(&"0".to_string()));
+    }
+
+    #[test]
+    fn test_parse_key_value_pairs_tuple() {
+        let input = r#"reason="end-stepping-range",frame={addr="0x401136",func="main",args=[],file="test.c",line="5"},thread-id="1""#;
+        let pairs = parse_key_value_pairs(input);
+
+        assert_eq!(pairs.get("thread-id"), Some(&"1".to_string()));
+        let frame = parse_tuple(&pairs["frame"]);
+        assert_eq!(frame.get("func"), Some(&"main".to_string()));
+        assert_eq!(frame.get("file"), Some(&"test.c".to_string()));
+        assert_eq!(frame.get("line"), Some(&"5".to_string()));
+    }
+
+    #[test]
+    fn test_parse_breakpoint() {
+        let input = r#"^done,bkpt={number="2",type="breakpoint",disp="keep",enabled="n",addr="0x0000000000401136",func="main",file="test.c",fullname="/home/user/test.c",line="11",cond="x > 1",thread-groups=["i1"],times="0",original-location="test.c:11"}"#;
+        if let MIResponse::ExecResult(_status, kv) = parse_mi_response(input) {
+            let bkpt = parse_breakpoint(&kv["bkpt"]).unwrap();
+            assert_eq!(bkpt.number, "2");
+            assert!(!bkpt.enabled);
+            assert_eq!(bkpt.addr, Some(0x401136));
+            assert_eq!(bkpt.func.as_deref(), Some("main"));
+            assert_eq!(bkpt.line, Some(11));
+            assert_eq!(bkpt.cond.as_deref(), Some("x > 1"));
+            assert!(bkpt.is_at_line("/home/user/test.c", 11));
+            assert!(!bkpt.is_at_line("/home/user/test.c", 12));
+        } else {
+            panic!("Expected ExecResult");
+        }
+    }
+
+    #[test]
+    fn test_parse_breakpoint_pending() {
+        let bkpt = parse_breakpoint(
+            r#"{number="1",type="breakpoint",disp="keep",enabled="y",addr="<PENDING>",pending="foo",times="0"}"#,
+        )
+        .unwrap();
+        assert_eq!(bkpt.addr, None);
+        assert!(bkpt.enabled);
+        assert!(!bkpt.is_at_line("test.c", 1));
+    }
+
+    #[test]
+    fn test_parse_breakpoint_watchpoint() {
+        let bkpt = parse_breakpoint(
+            r#"{number="3",type="hw watchpoint",disp="keep",enabled="y",addr="",what="counter",thread-groups=["i1"],times="2"}"#,
+        )
+        .unwrap();
+        assert!(bkpt.is_watchpoint());
+        assert_eq!(bkpt.what.as_deref(), Some("counter"));
+        assert_eq!(bkpt.times, 2);
+        assert_eq!(bkpt.addr, None);
+    }
+
+    #[test]
+    fn test_parse_breakpoint_table() {
+        let input = r#"^done,BreakpointTable={nr_rows="2",nr_cols="6",hdr=[{width="7",alignment="-1",col_name="number",colhdr="Num"}],body=[bkpt={number="1",type="breakpoint",disp="keep",enabled="y",addr="0x000000000040182d",func="main",file="test.c",line="11",thread-groups=["i1"],times="1"},bkpt={number="2",type="breakpoint",disp="del",enabled="y",addr="0x0000000000401808",func="this",file="test.c",line="6",thread-groups=["i1"],times="0"}]}"#;
+        if let MIResponse::ExecResult(_status, kv) = parse_mi_response(input) {
+            let bkpts = parse_breakpoint_table(&kv["BreakpointTable"]);
+            assert_eq!(bkpts.len(), 2);
+            assert_eq!(bkpts[0].addr, Some(0x40182d));
+            assert_eq!(bkpts[1].number, "2");
+            // no fullname, fall back to the short filename
+            assert!(bkpts[1].is_at_line("/src/test.c", 6));
+        } else {
+            panic!("Expected ExecResult");
+        }
+    }
+
+    #[test]
+    fn test_break_commands() {
+        assert_eq!(break_insert(&linespec("/src/a b.c", 3)), r#"-break-insert "/src/a b.c:3""#);
+        assert_eq!(break_insert(&addrspec(0x401000)), "-break-insert *0x401000");
+        assert_eq!(break_delete("4"), "-break-delete 4");
+        assert_eq!(break_insert_temporary(&addrspec(0x401000)), "-break-insert -t *0x401000");
+        assert_eq!(exec_continue(), "-exec-continue");
+    }
+
+    #[test]
+    fn test_break_watch() {
+        assert_eq!(break_watch(WatchKind::Write, "counter"), r#"-break-watch "counter""#);
+        assert_eq!(
+            break_watch(WatchKind::Read, "*(int *)0x20000000"),
+            r#"-break-watch -r "*(int *)0x20000000""#
+        );
+        assert_eq!(
+            break_watch(WatchKind::Access, r#"s == "a""#),
+            r#"-break-watch -a "s == \"a\"""#
+        );
+        assert_eq!(break_enable("2"), "-break-enable 2");
+        assert_eq!(break_disable("2"), "-break-disable 2");
+    }
+
+    #[test]
+    fn test_parse_watchpoint_hit() {
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(
+            r#"*stopped,reason="watchpoint-trigger",wpt={number="2",exp="counter"},value={old="0",new="1"},frame={addr="0x0000000000401136",func="bump",args=[],file="test.c",fullname="/src/test.c",line="5",arch="i386:x86-64"},thread-id="1",stopped-threads="all",core="3""#,
+        ) else {
+            panic!("Expected AsyncRecord");
+        };
+        let hit = parse_watchpoint_hit(&kv).unwrap();
+        assert_eq!(
+            hit,
+            WatchpointHit {
+                number: "2".to_string(),
+                exp: "counter".to_string(),
+                old: Some("0".to_string()),
+                new: Some("1".to_string()),
+                pc: 0x401136,
+                func: Some("bump".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_watchpoint_hit_read() {
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(
+            r#"*stopped,reason="read-watchpoint-trigger",hw-rwpt={number="3",exp="flags"},value={value="7"},frame={addr="0x0000000000401150",func="main",args=[]},thread-id="1""#,
+        ) else {
+            panic!("Expected AsyncRecord");
+        };
+        let hit = parse_watchpoint_hit(&kv).unwrap();
+        assert_eq!(hit.number, "3");
+        assert_eq!(hit.old, None);
+        assert_eq!(hit.new.as_deref(), Some("7"));
+
+        let MIResponse::AsyncRecord(_, kv) = parse_mi_response(
+            r#"*stopped,reason="breakpoint-hit",disp="keep",bkptno="1",frame={addr="0x0000000000401150",func="main",args=[]}"#,
+        ) else {
+            panic!("Expected AsyncRecord");
+        };
+        assert_eq!(parse_watchpoint_hit(&kv), None);
+    }
+
+    #[test]
+    fn test_parse_threads() {
+        let input = r#"^done,threads=[{id="2",target-id="Thread 0x7ffff7d89640 (LWP 4242)",name="worker",frame={level="0",addr="0x00007ffff7e9a4b5",func="__lll_lock_wait",args=[],from="/lib/libc.so.6",arch="i386:x86-64"},state="stopped",core="1"},{id="1",target-id="Thread 0x7ffff7d8a740 (LWP 4241)",name="a.out",frame={level="0",addr="0x0000000000401136",func="main",args=[],file="test.c",fullname="/src/test.c",line="11",arch="i386:x86-64"},state="stopped",core="3"}],current-thread-id="1""#;
+        let MIResponse::ExecResult(_, kv) = parse_mi_response(input) else {
+            panic!("Expected ExecResult");
+        };
+        assert_eq!(kv["current-thread-id"], "1");
+        let threads = parse_threads(&kv["threads"]);
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].id, "2");
+        assert_eq!(threads[0].name.as_deref(), Some("worker"));
+        assert_eq!(threads[0].core.as_deref(), Some("1"));
+        let frame = threads[0].frame.as_ref().unwrap();
+        assert_eq!(frame.addr, 0x7ffff7e9a4b5);
+        assert_eq!(frame.location().as_deref(), Some("/lib/libc.so.6"));
+        let frame = threads[1].frame.as_ref().unwrap();
+        assert_eq!(frame.func.as_deref(), Some("main"));
+        assert_eq!(frame.location().as_deref(), Some("test.c:11"));
+    }
+
+    #[test]
+    fn test_parse_stack_args() {
+        let input = r#"^done,stack-args=[frame={level="0",args=[{name="n",type="int",value="3"},{name="s",type="struct point"}]},frame={level="1",args=[]}]"#;
+        let MIResponse::ExecResult(_, kv) = parse_mi_response(input) else {
+            panic!("Expected ExecResult");
+        };
+        let args = parse_stack_args(&kv["stack-args"]);
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0].0, 0);
+        assert_eq!(
+            args[0].1,
+            vec![
+                Variable {
+                    name: "n".to_string(),
+                    type_: Some("int".to_string()),
+                    value: Some("3".to_string()),
+                    arg: false
+                },
+                Variable {
+                    name: "s".to_string(),
+                    type_: Some("struct point".to_string()),
+                    value: None,
+                    arg: false
+                },
+            ]
+        );
+        assert_eq!(args[1], (1, vec![]));
+    }
+
+    #[test]
+    fn test_parse_var_children() {
+        let input = r#"^done,numchild="2",children=[child={name="local0.x",exp="x",numchild="0",value="1",type="int",thread-id="1"},child={name="local0.next",exp="next",numchild="2",value="0x4052a0",type="struct node *",thread-id="1"}],has_more="0""#;
+        let MIResponse::ExecResult(_, kv) = parse_mi_response(input) else {
+            panic!("Expected ExecResult");
+        };
+        let children = parse_var_children(&kv["children"]);
+        assert_eq!(
+            children,
+            vec![
+                VarObj {
+                    name: "local0.x".to_string(),
+                    exp: "x".to_string(),
+                    type_: Some("int".to_string()),
+                    value: Some("1".to_string()),
+                    numchild: 0,
+                },
+                VarObj {
+                    name: "local0.next".to_string(),
+                    exp: "next".to_string(),
+                    type_: Some("struct node *".to_string()),
+                    value: Some("0x4052a0".to_string()),
+                    numchild: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_var_changelist() {
+        let input = r#"^done,changelist=[{name="local0",value="2",in_scope="true",type_changed="false",has_more="0"},{name="local1",in_scope="false",type_changed="false",has_more="0"},{name="local2",value="{...}",in_scope="true",type_changed="true",new_type="int [3]",new_num_children="3",has_more="0"}]"#;
+        let MIResponse::ExecResult(_, kv) = parse_mi_response(input) else {
+            panic!("Expected ExecResult");
+        };
+        let changes = parse_var_changelist(&kv["changelist"]);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].value.as_deref(), Some("2"));
+        assert!(changes[0].in_scope);
+        assert!(!changes[1].in_scope);
+        assert_eq!(changes[1].value, None);
+        assert_eq!(changes[2].new_num_children, Some(3));
+    }
+
+    #[test]
+    fn test_var_commands() {
+        assert_eq!(var_create("local0", "p->next"), r#"-var-create local0 * "p->next""#);
+        assert_eq!(var_assign("local0.x", "5"), r#"-var-assign local0.x "5""#);
+        assert_eq!(var_assign("local1", r#""hi""#), r#"-var-assign local1 "\"hi\"""#);
+        assert_eq!(var_list_children("local0"), "-var-list-children --all-values local0");
+    }
+
+    #[test]
+    fn test_parse_threads_running() {
+        let threads = parse_threads(
+            r#"[{id="1",target-id="Thread 0x7ffff7d8a740 (LWP 4241)",name="a.out",state="running",core="0"}]"#,
+        );
+        assert_eq!(threads[0].state, "running");
+        assert_eq!(threads[0].frame, None);
+    }
+
     #[test]
     fn test_match_inner_items() {
         let input = r#"[{address="0x1234",inst="mov"},{address="0x5678",inst="add"}]"#;
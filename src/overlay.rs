@@ -0,0 +1,257 @@
+use std::ops::Range;
+
+/// Member of a struct or union from `ptype /o`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeField {
+    /// Byte offset from the start of the outermost type
+    pub offset: usize,
+    pub size: usize,
+    /// Bit position in the byte at `offset` and width of a bitfield
+    pub bits: Option<(usize, usize)>,
+    pub name: String,
+    /// Type as gdb prints it, arrays as `char [16]`
+    pub ty: String,
+    /// Nesting in anonymous or named inner structs and unions, 0 for the members of the type
+    pub depth: usize,
+    /// An inner struct or union, whose members follow it
+    pub aggregate: bool,
+}
+
+impl TypeField {
+    /// Bytes the field covers, only those holding its bits for a bitfield
+    pub fn range(&self) -> Range<usize> {
+        match self.bits {
+            Some((pos, width)) => self.offset..self.offset + (pos + width).div_ceil(8),
+            None => self.offset..self.offset + self.size,
+        }
+    }
+}
+
+/// Layout of a C type over the hexdump from `addr`, repeated for each element of an array
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeOverlay {
+    pub name: String,
+    pub addr: u64,
+    /// Elements of the array, `None` repeats it to the end of the hexdump
+    pub count: Option<usize>,
+    /// Size of one element
+    pub size: usize,
+    pub fields: Vec<TypeField>,
+}
+
+impl TypeOverlay {
+    /// Overlay of `input` at `addr`, a type name optionally followed by `[count]`, or `[]` to
+    /// repeat it to the end of the hexdump. Its layout is read from gdb next
+    pub fn new(input: &str, addr: u64) -> Result<Self, String> {
+        let input = input.trim();
+        let (name, count) = match input.strip_suffix(']').and_then(|s| s.rsplit_once('[')) {
+            Some((name, "")) => (name, None),
+            Some((name, count)) => {
+                let count = crate::parse_number(count.trim())
+                    .filter(|c| *c != 0)
+                    .ok_or_else(|| format!("invalid count {count}"))?;
+                (name, Some(count as usize))
+            }
+            None => (input, Some(1)),
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("expected a type name".to_string());
+        }
+        Ok(Self { name: name.to_string(), addr, count, size: 0, fields: vec![] })
+    }
+
+    /// Element of the array at `addr`, and the offset into it
+    pub fn element_at(&self, addr: u64) -> Option<(usize, usize)> {
+        if self.size == 0 {
+            return None;
+        }
+        let offset = usize::try_from(addr.checked_sub(self.addr)?).ok()?;
+        let index = offset / self.size;
+        if self.count.is_some_and(|count| index >= count) {
+            return None;
+        }
+        Some((index, offset % self.size))
+    }
+
+    /// Index of the innermost member covering `offset` of an element
+    pub fn field_at(&self, offset: usize) -> Option<usize> {
+        self.fields.iter().rposition(|f| !f.aggregate && f.range().contains(&offset))
+    }
+}
+
+/// Bit position and byte offset of `/*   16: 5   |` or of a union member without an offset
+fn parse_offset(offset: &str) -> Option<(usize, Option<usize>)> {
+    let (byte, bit) = match offset.split_once(':') {
+        Some((byte, bit)) => (byte, Some(bit.trim().parse().ok()?)),
+        None => (offset, None),
+    };
+    Some((byte.trim().parse().ok()?, bit))
+}
+
+/// Name and type of a member declaration such as `char *name;`, `char buf[16];`,
+/// `void (*cb)(int);` or `unsigned int flag : 1;`, and the width of a bitfield
+fn parse_declaration(decl: &str) -> (String, String, Option<usize>) {
+    let decl = decl.trim().trim_end_matches(';').trim();
+    let (decl, width) = match decl.rsplit_once(" : ") {
+        Some((decl, width)) => (decl.trim(), width.trim().parse().ok()),
+        None => (decl, None),
+    };
+    if let Some(start) = decl.find("(*") {
+        let rest = &decl[start + 2..];
+        let end = rest.find(')').unwrap_or(rest.len());
+        return (rest[..end].to_string(), decl.to_string(), width);
+    }
+    let (decl, array) = match decl.find('[') {
+        Some(i) => (&decl[..i], &decl[i..]),
+        None => (decl, ""),
+    };
+    let split = decl.rfind(|c: char| c.is_whitespace() || c == '*').map_or(0, |i| i + 1);
+    let (ty, name) = decl.split_at(split);
+    let mut ty = ty.trim().to_string();
+    if !array.is_empty() {
+        ty.push(' ');
+        ty.push_str(array);
+    }
+    (name.to_string(), ty, width)
+}
+
+/// Size and members of a struct or union from the output of `ptype /o`, `None` for other types
+pub fn parse_ptype_offsets(output: &str) -> Option<(usize, Vec<TypeField>)> {
+    let mut fields: Vec<TypeField> = vec![];
+    let mut size = None;
+    // offset of each open struct or union, and its field, `None` for the type itself
+    let mut open: Vec<(usize, Option<usize>)> = vec![];
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(total) = trimmed.strip_prefix("/* total size (bytes):") {
+            size = total.trim_end_matches("*/").trim().parse().ok();
+            continue;
+        }
+        if trimmed.starts_with("/* XXX") {
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix('}') {
+            if let Some((_, Some(index))) = open.pop() {
+                fields[index].name = name.trim().trim_end_matches(';').trim().to_string();
+            }
+            continue;
+        }
+        let Some((comment, decl)) = trimmed.strip_prefix("/*").and_then(|s| s.split_once("*/"))
+        else {
+            continue;
+        };
+        let decl = decl.trim();
+        if decl.starts_with("type =") {
+            if decl.ends_with('{') {
+                open.push((0, None));
+            }
+            continue;
+        }
+        let inherited = open.last().map_or(0, |(offset, _)| *offset);
+        let parsed = match comment.split_once('|') {
+            Some((offset, size)) => parse_offset(offset)
+                .and_then(|(offset, bit)| Some((offset, bit, size.trim().parse().ok()?))),
+            None => comment.trim().parse().ok().map(|size| (inherited, None, size)),
+        };
+        let Some((offset, bit, size)) = parsed else {
+            continue;
+        };
+        let depth = open.len().saturating_sub(1);
+        if let Some(ty) = decl.strip_suffix('{') {
+            open.push((offset, Some(fields.len())));
+            fields.push(TypeField {
+                offset,
+                size,
+                bits: None,
+                name: String::new(),
+                ty: ty.trim().to_string(),
+                depth,
+                aggregate: true,
+            });
+        } else {
+            let (name, ty, width) = parse_declaration(decl);
+            let bits = width.map(|width| (bit.unwrap_or(0), width));
+            fields.push(TypeField { offset, size, bits, name, ty, depth, aggregate: false });
+        }
+    }
+    let size = size?;
+    (size != 0 && !fields.is_empty()).then_some((size, fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PTYPE: &str = "/* offset      |    size */  type = struct packet_hdr {
+/*      0      |       2 */    uint16_t magic;
+/*      2: 0   |       1 */    unsigned char version : 4;
+/*      2: 4   |       1 */    unsigned char flags : 4;
+/* XXX  1-byte hole      */
+/*      4      |       4 */    uint32_t len;
+/*      8      |       8 */    char *payload;
+/*     16      |       8 */    struct inner {
+/*     16      |       4 */        int a;
+/*     20      |       4 */        int b;
+
+                                   /* total size (bytes):    8 */
+                               } in;
+/*     24      |       4 */    union {
+/*                     4 */        float f;
+/*                     4 */        uint32_t u;
+
+                                   /* total size (bytes):    4 */
+                               };
+/*     28      |       4 */    char tag[4];
+/*     32      |       8 */    void (*cb)(int);
+
+                               /* total size (bytes):   40 */
+                             }
+";
+
+    #[test]
+    fn test_parse_ptype_offsets() {
+        let (size, fields) = parse_ptype_offsets(PTYPE).unwrap();
+        assert_eq!(size, 40);
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "magic", "version", "flags", "len", "payload", "in", "a", "b", "", "f", "u", "tag",
+                "cb"
+            ]
+        );
+        assert_eq!(fields[2].bits, Some((4, 4)));
+        assert_eq!(fields[2].range(), 2..3);
+        assert_eq!(fields[4].ty, "char *");
+        assert!(fields[5].aggregate);
+        assert_eq!(fields[5].ty, "struct inner");
+        assert_eq!((fields[7].offset, fields[7].depth), (20, 1));
+        // union members have the offset of the union
+        assert_eq!((fields[10].offset, fields[10].size), (24, 4));
+        assert_eq!(fields[11].ty, "char [4]");
+        assert_eq!(fields[12].ty, "void (*cb)(int)");
+
+        assert_eq!(parse_ptype_offsets("type = int"), None);
+    }
+
+    #[test]
+    fn test_overlay_elements() {
+        let mut overlay = TypeOverlay::new("struct packet_hdr [2]", 0x1000).unwrap();
+        assert_eq!((overlay.name.as_str(), overlay.count), ("struct packet_hdr", Some(2)));
+        assert_eq!(overlay.element_at(0x1000), None);
+        (overlay.size, overlay.fields) = parse_ptype_offsets(PTYPE).unwrap();
+        assert_eq!(overlay.element_at(0x1000 + 45), Some((1, 5)));
+        assert_eq!(overlay.element_at(0x1000 + 80), None);
+        assert_eq!(overlay.element_at(0xfff), None);
+        assert_eq!(overlay.field_at(5), Some(3));
+        // in the hole
+        assert_eq!(overlay.field_at(3), None);
+        assert_eq!(overlay.field_at(26), Some(10));
+
+        assert_eq!(TypeOverlay::new("foo_t[]", 0).unwrap().count, None);
+        assert_eq!(TypeOverlay::new("foo_t", 0).unwrap().count, Some(1));
+        assert!(TypeOverlay::new("foo_t[x]", 0).is_err());
+        assert!(TypeOverlay::new(" ", 0).is_err());
+    }
+}
@@ -11,4 +11,10 @@ impl RegisterStorage {
     pub fn new(name: String, register: Option<Register>, deref: Deref) -> Self {
         Self { name, register, deref }
     }
+
+    /// Value shown in the Registers pane, when the register is set and a hex number
+    pub fn shown_value(&self) -> Option<u64> {
+        let value = self.register.as_ref().filter(|r| r.is_set())?.value.as_ref()?;
+        u64::from_str_radix(value.strip_prefix("0x")?, 16).ok()
+    }
 }
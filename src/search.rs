@@ -0,0 +1,441 @@
+use std::collections::VecDeque;
+
+use regex::bytes::{Regex, RegexBuilder};
+
+use crate::PtrSize;
+use crate::memory::{PAGE_LEN, PagedMemory};
+
+/// Bytes read per `-data-read-memory-bytes` of the `find` command
+pub const FIND_CHUNK_LEN: u64 = 0x10000;
+
+/// Bytes read past the end of each chunk, so matches crossing into the next chunk are found.
+/// Fixed patterns use their own length, regex matches longer than this can be missed
+pub const FIND_OVERLAP: u64 = 0x100;
+
+/// Chunk reads of the `find` command sent to gdb at a time, the next is sent as one comes back
+pub const FIND_IN_FLIGHT: usize = 4;
+
+/// Search pattern of the hexdump search and the `find` command
+#[derive(Debug, Clone)]
+pub enum Pattern {
+    /// Byte sequence, `None` matches any byte
+    Bytes(Vec<Option<u8>>),
+    Regex(Regex),
+}
+
+impl Pattern {
+    /// Parse a search query:
+    /// - `de ?? be ef`: hex bytes, `??` matches any byte
+    /// - `"text"`: ascii string, `u"text"`: utf-16 string
+    /// - `le:0x1234`, `be32:4660`: integer, pointer sized unless a width of 16, 32 or 64 is given
+    /// - `/regex/`: regex over the raw bytes
+    ///
+    /// Anything else is searched as text
+    pub fn parse(query: &str, ptr_size: PtrSize) -> Result<Self, String> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err("empty pattern".to_string());
+        }
+        let pattern = Self::parse_query(query, ptr_size)?;
+        if pattern.len() == Some(0) {
+            return Err("empty pattern".to_string());
+        }
+        Ok(pattern)
+    }
+
+    fn parse_query(query: &str, ptr_size: PtrSize) -> Result<Self, String> {
+        if let Some(re) = query.strip_prefix('/').and_then(|q| q.strip_suffix('/')) {
+            return RegexBuilder::new(re)
+                .unicode(false)
+                .build()
+                .map(Pattern::Regex)
+                .map_err(|e| e.to_string());
+        }
+        if let Some(text) = query.strip_prefix("u\"").and_then(|q| q.strip_suffix('"')) {
+            let bytes = text.encode_utf16().flat_map(u16::to_le_bytes).map(Some).collect();
+            return Ok(Pattern::Bytes(bytes));
+        }
+        if let Some(text) = query.strip_prefix('"').and_then(|q| q.strip_suffix('"')) {
+            return Ok(Pattern::Bytes(text.bytes().map(Some).collect()));
+        }
+        if let Some((kind, value)) = query.split_once(':')
+            && let Some(bytes) = parse_integer(kind, value, ptr_size)?
+        {
+            return Ok(Pattern::Bytes(bytes.into_iter().map(Some).collect()));
+        }
+        if let Some(bytes) = parse_hex_bytes(query) {
+            return Ok(Pattern::Bytes(bytes));
+        }
+        Ok(Pattern::Bytes(query.bytes().map(Some).collect()))
+    }
+
+    /// Length of every match, unknown for a regex
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Pattern::Bytes(bytes) => Some(bytes.len()),
+            Pattern::Regex(_) => None,
+        }
+    }
+
+    /// Non-overlapping matches in `buffer`, as (offset, length)
+    pub fn find_all(&self, buffer: &[u8]) -> Vec<(usize, usize)> {
+        match self {
+            Pattern::Bytes(bytes) => {
+                let mut matches = Vec::new();
+                let mut offset = 0;
+                while offset + bytes.len() <= buffer.len() {
+                    let window = &buffer[offset..offset + bytes.len()];
+                    if bytes.iter().zip(window).all(|(p, b)| p.is_none_or(|p| p == *b)) {
+                        matches.push((offset, bytes.len()));
+                        offset += bytes.len().max(1);
+                    } else {
+                        offset += 1;
+                    }
+                }
+                matches
+            }
+            Pattern::Regex(re) => re
+                .find_iter(buffer)
+                .filter(|m| !m.is_empty())
+                .map(|m| (m.start(), m.len()))
+                .collect(),
+        }
+    }
+}
+
+/// `le:`/`be:` followed by an optional width in bits, `None` when `kind` is not one of them
+fn parse_integer(kind: &str, value: &str, ptr_size: PtrSize) -> Result<Option<Vec<u8>>, String> {
+    let (big, width) = if let Some(width) = kind.strip_prefix("le") {
+        (false, width)
+    } else if let Some(width) = kind.strip_prefix("be") {
+        (true, width)
+    } else {
+        return Ok(None);
+    };
+    let len = match width {
+        "" if ptr_size == PtrSize::Size32 => 4,
+        "" => 8,
+        "16" => 2,
+        "32" => 4,
+        "64" => 8,
+        _ => return Ok(None),
+    };
+
+    let value = value.trim();
+    let parsed = if let Some(hex) = value.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+    } else {
+        value.parse::<u64>()
+    };
+    let value = parsed.map_err(|e| format!("invalid integer {value}: {e}"))?;
+    if len < 8 && value >> (len * 8) != 0 {
+        return Err(format!("0x{value:x} does not fit in {} bits", len * 8));
+    }
+
+    let bytes = if big {
+        value.to_be_bytes()[8 - len..].to_vec()
+    } else {
+        value.to_le_bytes()[..len].to_vec()
+    };
+    Ok(Some(bytes))
+}
+
+/// Whitespace separated hex bytes such as `de ad` or `dead`, with `??` for any byte
+fn parse_hex_bytes(query: &str) -> Option<Vec<Option<u8>>> {
+    let mut bytes = Vec::new();
+    for token in query.split_whitespace() {
+        if token.len() % 2 != 0 {
+            return None;
+        }
+        for pair in token.as_bytes().chunks(2) {
+            let pair = std::str::from_utf8(pair).ok()?;
+            if pair == "??" {
+                bytes.push(None);
+            } else {
+                bytes.push(Some(u8::from_str_radix(pair, 16).ok()?));
+            }
+        }
+    }
+    Some(bytes)
+}
+
+/// Search of the loaded pages of the hexdump
+#[derive(Debug, Clone)]
+pub struct HexdumpSearch {
+    /// As typed by the user
+    pub query: String,
+    pub pattern: Pattern,
+    /// Matches in the hexdump, as (offset, length) ordered by offset
+    pub matches: Vec<(usize, usize)>,
+    /// Index into `matches` of the selected match
+    pub current: usize,
+}
+
+impl HexdumpSearch {
+    /// Search the `runs` of consecutive loaded bytes, as (offset, bytes)
+    pub fn new(query: &str, pattern: Pattern, runs: &[(usize, Vec<u8>)]) -> Self {
+        let mut search = Self { query: query.to_string(), pattern, matches: vec![], current: 0 };
+        search.update(runs);
+        search
+    }
+
+    /// Search again, after pages of the hexdump were loaded. The selected match stays selected
+    /// when it is still found
+    pub fn update(&mut self, runs: &[(usize, Vec<u8>)]) {
+        let selected = self.current_offset();
+        self.matches = runs
+            .iter()
+            .flat_map(|(offset, bytes)| {
+                self.pattern
+                    .find_all(bytes)
+                    .into_iter()
+                    .map(move |(start, len)| (offset + start, len))
+            })
+            .collect();
+        self.select(selected);
+    }
+
+    /// Search again around page `index` of `memory` only, after it was loaded or refreshed.
+    /// Matches that could include a byte of the page are replaced, the others are kept
+    pub fn update_page(&mut self, memory: &PagedMemory, index: usize) {
+        let selected = self.current_offset();
+        let overlap = self.pattern.len().map_or(FIND_OVERLAP as usize, |len| len.saturating_sub(1));
+        let start = (index * PAGE_LEN).saturating_sub(overlap);
+        let end = (index + 1) * PAGE_LEN;
+        self.matches.retain(|&(offset, _)| !(start..end).contains(&offset));
+        for (offset, bytes) in memory.runs_in(start..end + overlap) {
+            self.matches.extend(
+                self.pattern
+                    .find_all(&bytes)
+                    .into_iter()
+                    .map(|(at, len)| (offset + at, len))
+                    .filter(|&(at, _)| at < end),
+            );
+        }
+        self.matches.sort_unstable();
+        self.select(selected);
+    }
+
+    /// Select the match at `offset`, or the first one
+    fn select(&mut self, offset: Option<usize>) {
+        self.current = offset.and_then(|o| self.matches.iter().position(|m| m.0 == o)).unwrap_or(0);
+    }
+
+    /// Select the next match, wrapping around
+    pub fn next(&mut self) {
+        if !self.matches.is_empty() {
+            self.current = (self.current + 1) % self.matches.len();
+        }
+    }
+
+    /// Select the previous match, wrapping around
+    pub fn prev(&mut self) {
+        if !self.matches.is_empty() {
+            self.current = self.current.checked_sub(1).unwrap_or(self.matches.len() - 1);
+        }
+    }
+
+    /// Offset of the selected match
+    pub fn current_offset(&self) -> Option<usize> {
+        self.matches.get(self.current).map(|m| m.0)
+    }
+
+    /// Whether the byte at `offset` is part of a match, and if that match is the selected one
+    pub fn at(&self, offset: usize) -> Option<bool> {
+        let index = self.matches.partition_point(|&(start, len)| start + len <= offset);
+        let &(start, _) = self.matches.get(index)?;
+        (start <= offset).then_some(index == self.current)
+    }
+}
+
+/// Search of a range of target memory from the `find` command, read in chunks
+#[derive(Debug, Clone)]
+pub struct FindJob {
+    /// Matches of another job are ignored
+    pub id: usize,
+    pub query: String,
+    pub pattern: Pattern,
+    /// End of the searched range, exclusive
+    pub end: u64,
+    /// Chunks not read yet, as (start, bytes to read)
+    pub chunks: VecDeque<(u64, u64)>,
+    /// Chunk reads not answered yet
+    pub pending: usize,
+    /// Addresses of the matches, ordered once all chunks are in
+    pub matches: Vec<u64>,
+}
+
+impl FindJob {
+    /// Record the matches of a chunk read at `begin`, ignoring those that start in the overlap
+    /// with the next chunk
+    pub fn add_chunk(&mut self, begin: u64, bytes: &[u8]) {
+        let chunk_end = begin.saturating_add(FIND_CHUNK_LEN).min(self.end);
+        for (offset, _) in self.pattern.find_all(bytes) {
+            let addr = begin.saturating_add(offset as u64);
+            if addr < chunk_end {
+                self.matches.push(addr);
+            }
+        }
+        self.pending = self.pending.saturating_sub(1);
+    }
+
+    /// Next chunk to read, while fewer than [`FIND_IN_FLIGHT`] are waiting for an answer
+    pub fn next_chunk(&mut self) -> Option<(u64, u64)> {
+        if self.pending >= FIND_IN_FLIGHT {
+            return None;
+        }
+        let chunk = self.chunks.pop_front()?;
+        self.pending += 1;
+        Some(chunk)
+    }
+}
+
+/// Start of each chunk of `start..end`, with the number of bytes to read for `pattern`
+pub fn find_chunks(start: u64, end: u64, pattern: &Pattern) -> VecDeque<(u64, u64)> {
+    let overlap = pattern.len().map_or(FIND_OVERLAP, |len| len.saturating_sub(1) as u64);
+    (start..end)
+        .step_by(FIND_CHUNK_LEN as usize)
+        .map(|begin| (begin, (FIND_CHUNK_LEN + overlap).min(end - begin)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(query: &str) -> Vec<Option<u8>> {
+        match Pattern::parse(query, PtrSize::Size64).unwrap() {
+            Pattern::Bytes(bytes) => bytes,
+            Pattern::Regex(_) => panic!("Expected Bytes"),
+        }
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!(bytes("de ?? be ef"), vec![Some(0xde), None, Some(0xbe), Some(0xef)]);
+        assert_eq!(bytes("dead"), vec![Some(0xde), Some(0xad)]);
+        assert_eq!(bytes(r#""GET""#), vec![Some(b'G'), Some(b'E'), Some(b'T')]);
+        assert_eq!(bytes(r#"u"Hi""#), vec![Some(b'H'), Some(0), Some(b'i'), Some(0)]);
+        assert_eq!(bytes("le32:0x1234"), vec![Some(0x34), Some(0x12), Some(0), Some(0)]);
+        assert_eq!(bytes("be16:4660"), vec![Some(0x12), Some(0x34)]);
+        assert_eq!(bytes("le:1").len(), 8);
+        assert_eq!(
+            Pattern::parse("le:1", PtrSize::Size32).unwrap().len(),
+            Some(4),
+            "pointer sized"
+        );
+        // not hex, searched as text
+        assert_eq!(bytes("hello"), b"hello".iter().copied().map(Some).collect::<Vec<_>>());
+
+        assert!(Pattern::parse("le16:0x10000", PtrSize::Size64).is_err());
+        assert!(Pattern::parse("/(/", PtrSize::Size64).is_err());
+        assert!(Pattern::parse("  ", PtrSize::Size64).is_err());
+        // quotes or hex without any byte in them
+        assert!(Pattern::parse(r#""""#, PtrSize::Size64).is_err());
+        assert!(Pattern::parse(r#"u"""#, PtrSize::Size64).is_err());
+    }
+
+    #[test]
+    fn test_find_all() {
+        let buffer = b"\xde\xad\xbe\xef\x00\xde\x00\xbe\xef";
+        let pattern = Pattern::parse("de ?? be ef", PtrSize::Size64).unwrap();
+        assert_eq!(pattern.find_all(buffer), vec![(0, 4), (5, 4)]);
+
+        let pattern = Pattern::parse(r"/\x7fELF[\x01\x02]/", PtrSize::Size64).unwrap();
+        assert_eq!(pattern.find_all(b"..\x7fELF\x02.."), vec![(2, 5)]);
+    }
+
+    #[test]
+    fn test_hexdump_search_navigation() {
+        let pattern = Pattern::parse(r#""ab""#, PtrSize::Size64).unwrap();
+        let mut search = HexdumpSearch::new("ab", pattern, &[(0, b"ab..ab..ab".to_vec())]);
+        assert_eq!(search.matches.len(), 3);
+        assert_eq!(search.at(1), Some(true));
+        assert_eq!(search.at(5), Some(false));
+        assert_eq!(search.at(2), None);
+
+        search.prev();
+        assert_eq!(search.current_offset(), Some(8));
+        search.next();
+        search.next();
+        assert_eq!(search.current_offset(), Some(4));
+        assert_eq!(search.at(5), Some(true));
+
+        // another page loaded, the selected match is kept
+        search.update(&[(0, b"ab..ab".to_vec()), (0x1000, b"..ab".to_vec())]);
+        assert_eq!(search.matches, vec![(0, 2), (4, 2), (0x1002, 2)]);
+        assert_eq!(search.current, 1);
+        search.update(&[(0, b"..ab".to_vec())]);
+        assert_eq!(search.current_offset(), Some(2));
+    }
+
+    #[test]
+    fn test_hexdump_search_update_page() {
+        let pattern = Pattern::parse(r#""ab""#, PtrSize::Size64).unwrap();
+        let mut memory = PagedMemory::new(0x1000, PAGE_LEN * 3);
+        let mut page = vec![0; PAGE_LEN];
+        page[0x10..0x12].copy_from_slice(b"ab");
+        page[PAGE_LEN - 1] = b'a';
+        memory.insert(0, page);
+        let mut search = HexdumpSearch::new("ab", pattern, &memory.runs());
+        assert_eq!(search.matches, vec![(0x10, 2)]);
+
+        // a match across the page boundary is found once the next page is in
+        let mut page = vec![0; PAGE_LEN];
+        page[0] = b'b';
+        page[0x20..0x22].copy_from_slice(b"ab");
+        memory.insert(1, page);
+        search.update_page(&memory, 1);
+        assert_eq!(search.matches, vec![(0x10, 2), (PAGE_LEN - 1, 2), (PAGE_LEN + 0x20, 2)]);
+        search.next();
+        search.next();
+
+        // the page changed at a stop, the selected match is kept
+        let mut page = vec![0; PAGE_LEN];
+        page[0] = b'b';
+        page[0x20..0x22].copy_from_slice(b"ab");
+        page[0x30..0x32].copy_from_slice(b"ab");
+        memory.insert(1, page);
+        search.update_page(&memory, 1);
+        assert_eq!(search.matches.len(), 4);
+        assert_eq!(search.current_offset(), Some(PAGE_LEN + 0x20));
+        assert_eq!(search.matches, {
+            let mut all = HexdumpSearch::new("ab", search.pattern.clone(), &memory.runs());
+            all.matches.sort_unstable();
+            all.matches
+        });
+    }
+
+    #[test]
+    fn test_find_chunks() {
+        let pattern = Pattern::parse("de ad be ef", PtrSize::Size64).unwrap();
+        let chunks = find_chunks(0x1000, 0x1000 + FIND_CHUNK_LEN + 0x10, &pattern);
+        assert_eq!(chunks, vec![(0x1000, FIND_CHUNK_LEN + 3), (0x1000 + FIND_CHUNK_LEN, 0x10)]);
+
+        // a match crossing the chunk boundary is found once
+        let mut job = FindJob {
+            id: 0,
+            query: String::new(),
+            pattern,
+            end: 0x1000 + FIND_CHUNK_LEN + 0x10,
+            chunks,
+            pending: 0,
+            matches: vec![],
+        };
+        assert_eq!(job.next_chunk(), Some((0x1000, FIND_CHUNK_LEN + 3)));
+        assert_eq!(job.next_chunk(), Some((0x1000 + FIND_CHUNK_LEN, 0x10)));
+        assert_eq!(job.next_chunk(), None);
+        let mut first = vec![0u8; FIND_CHUNK_LEN as usize + 3];
+        first[FIND_CHUNK_LEN as usize - 1..].copy_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+        job.add_chunk(0x1000, &first);
+        job.add_chunk(0x1000 + FIND_CHUNK_LEN, &[0xad, 0xbe, 0xef, 0, 0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(job.matches, vec![0x1000 + FIND_CHUNK_LEN - 1, 0x1000 + FIND_CHUNK_LEN + 4]);
+        assert_eq!(job.pending, 0);
+
+        // the last chunk below the top of the address space
+        job.matches.clear();
+        job.end = u64::MAX;
+        job.add_chunk(u64::MAX - 8, &[0, 0, 0xde, 0xad, 0xbe, 0xef, 0, 0]);
+        assert_eq!(job.matches, vec![u64::MAX - 6]);
+    }
+}
@@ -0,0 +1,545 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::mi::MemoryMapping;
+use crate::parse_number;
+
+/// Slots read past `$sp` at a stop, and each time the Stack pane is scrolled near its end
+pub const STACK_PAGE: u64 = 32;
+
+/// Slots the Stack pane reads up to without a `[stack]` mapping to end at
+pub const DEFAULT_STACK_SLOTS: u64 = 1024;
+
+/// How far past `$sp` the Stack pane reads, set with `stack-depth`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StackDepth {
+    /// At most this many slots
+    Slots(u64),
+    /// Up to the end of the `[stack]` mapping
+    #[default]
+    Mapping,
+}
+
+/// Depth of `<slots>` or `mapping`, the argument of `stack-depth`
+pub fn parse_stack_depth(arg: &str) -> Result<StackDepth, String> {
+    match arg.trim() {
+        "mapping" => Ok(StackDepth::Mapping),
+        slots => match parse_number(slots) {
+            Some(slots) if slots > 0 => Ok(StackDepth::Slots(slots)),
+            _ => {
+                Err(format!("expected 'stack-depth <slots>' or 'stack-depth mapping', got {slots}"))
+            }
+        },
+    }
+}
+
+/// Which way the stack grows as frames are pushed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Growth {
+    /// Towards lower addresses, the slots of the callers are past `$sp`
+    #[default]
+    Down,
+    /// Towards higher addresses, the slots of the callers are below `$sp`
+    Up,
+}
+
+impl Growth {
+    pub fn name(self) -> &'static str {
+        match self {
+            Growth::Down => "down",
+            Growth::Up => "up",
+        }
+    }
+
+    /// Address of slot `index` counted from `sp` towards the callers
+    pub fn slot_addr(self, sp: u64, index: u64, slot_len: u64) -> u64 {
+        match self {
+            Growth::Down => sp.wrapping_add(index.wrapping_mul(slot_len)),
+            Growth::Up => sp.wrapping_sub(index.wrapping_mul(slot_len)),
+        }
+    }
+
+    /// Bytes from `sp` to `addr` towards the callers, none when `addr` is on the other side
+    pub fn offset(self, sp: u64, addr: u64) -> Option<u64> {
+        match self {
+            Growth::Down => addr.checked_sub(sp),
+            Growth::Up => sp.checked_sub(addr),
+        }
+    }
+
+    /// Whether the slot at `addr` belongs to the frame of `cfa` or one of its callees
+    pub fn in_frame(self, addr: u64, cfa: u64) -> bool {
+        match self {
+            Growth::Down => addr < cfa,
+            Growth::Up => addr >= cfa,
+        }
+    }
+}
+
+/// Linker symbols of the lowest address of the stack, the first one found is used
+pub const STACK_LOW_SYMBOLS: [&str; 4] =
+    ["_sstack", "__stack_start", "__StackLimit", "_stack_bottom"];
+
+/// Linker symbols of the end of the stack, the first one found is used
+pub const STACK_HIGH_SYMBOLS: [&str; 4] = ["_estack", "__stack_end", "__StackTop", "_stack_top"];
+
+/// End of the stack a linker symbol was looked up for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackEnd {
+    Low,
+    High,
+}
+
+/// Where the stack is and which way it grows, set with `stack-model`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StackModel {
+    /// Expression of the stack pointer the slots are read from
+    pub sp: String,
+    pub growth: Growth,
+    /// Addresses of the stack set with `stack-model bounds`, over the `[stack]` mapping
+    pub bounds: Option<Range<u64>>,
+    /// Ends of the stack from the linker symbols, for targets without a `[stack]` mapping
+    pub linker_low: Option<u64>,
+    pub linker_high: Option<u64>,
+    /// The linker symbols were looked up
+    pub symbols_read: bool,
+}
+
+impl Default for StackModel {
+    fn default() -> Self {
+        Self {
+            sp: "$sp".to_string(),
+            growth: Growth::Down,
+            bounds: None,
+            linker_low: None,
+            linker_high: None,
+            symbols_read: false,
+        }
+    }
+}
+
+impl StackModel {
+    /// Register read for the stack pointer, when the expression is a single register. `$sp` is
+    /// whichever of `rsp`, `esp` or `sp` the target has
+    pub fn sp_registers(&self) -> Vec<&str> {
+        match self.sp.strip_prefix('$') {
+            Some("sp") => vec!["rsp", "esp", "sp"],
+            Some(reg) if reg.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') => vec![reg],
+            _ => vec![],
+        }
+    }
+
+    /// Addresses of the stack: the configured bounds, or else the `[stack]` mapping, or else the
+    /// linker symbols. With a single linker symbol the stack spans from `sp` up to it
+    pub fn stack_bounds(&self, mapping: Option<Range<u64>>, sp: Option<u64>) -> Option<Range<u64>> {
+        if let Some(bounds) = &self.bounds {
+            return Some(bounds.clone());
+        }
+        if mapping.is_some() {
+            return mapping;
+        }
+        match (self.linker_low, self.linker_high, sp) {
+            (Some(low), Some(high), _) if low < high => Some(low..high),
+            (None, Some(high), Some(sp)) if self.growth == Growth::Down && sp < high => {
+                Some(sp..high)
+            }
+            (Some(low), None, Some(sp)) if self.growth == Growth::Up && low <= sp => {
+                Some(low..sp.saturating_add(1))
+            }
+            _ => None,
+        }
+    }
+
+    /// Where the bounds of [`Self::stack_bounds`] come from
+    pub fn bounds_source(&self, mapping: bool) -> &'static str {
+        if self.bounds.is_some() {
+            "stack-model bounds"
+        } else if mapping {
+            "the [stack] mapping"
+        } else {
+            "the linker symbols"
+        }
+    }
+
+    /// Apply the arguments of `stack-model`: `sp <expr>`, `grows up|down`,
+    /// `bounds <low> <high>` or `bounds auto`
+    pub fn apply(&mut self, args: &str) -> Result<(), String> {
+        const USAGE: &str = "expected 'stack-model sp <expr>', 'stack-model grows up|down' or 'stack-model bounds <low> <high>|auto'";
+        let (key, value) = args.trim().split_once(' ').ok_or(USAGE)?;
+        let value = value.trim();
+        match key {
+            "sp" if !value.is_empty() => self.sp = value.to_string(),
+            "grows" => {
+                self.growth = match value {
+                    "down" => Growth::Down,
+                    "up" => Growth::Up,
+                    _ => return Err(USAGE.to_string()),
+                }
+            }
+            "bounds" if value == "auto" => self.bounds = None,
+            "bounds" => {
+                let ends: Vec<Option<u64>> = value.split_whitespace().map(parse_number).collect();
+                let [Some(low), Some(high)] = ends[..] else {
+                    return Err(USAGE.to_string());
+                };
+                if low >= high {
+                    return Err(format!("stack bounds 0x{low:x}..0x{high:x} are empty"));
+                }
+                self.bounds = Some(low..high);
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+        Ok(())
+    }
+}
+
+static RE_FRAME_AT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"frame at (0x[0-9a-fA-F]+)").unwrap());
+
+/// Canonical frame address of the `info frame` output, from `Stack level N, frame at 0x..:`
+pub fn parse_frame_cfa(output: &str) -> Option<u64> {
+    let caps = RE_FRAME_AT.captures(output)?;
+    parse_number(&caps[1])
+}
+
+/// Frames of the backtrace read with [`FrameLayout`], the outer ones are too deep to matter
+pub const MAX_FRAME_LAYOUTS: usize = 16;
+
+/// Frame of the backtrace as laid out on the stack, from `info frame` and the addresses of its
+/// variables
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FrameLayout {
+    pub level: usize,
+    /// Function of the frame, from the backtrace
+    pub func: Option<String>,
+    /// The frame has a source location, so its code likely keeps a frame pointer
+    pub has_source: bool,
+    /// Canonical frame address, where the frame ends and its caller's begins
+    pub cfa: u64,
+    /// Slots of the registers the frame saved, such as the return address and frame pointer
+    pub saved: Vec<(String, u64)>,
+    /// Homes of the locals and arguments
+    pub vars: Vec<(String, u64)>,
+}
+
+static RE_SAVED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\w+) at (0x[0-9a-fA-F]+)").unwrap());
+
+/// Layout of frame `level` from the output of `info frame`
+pub fn parse_info_frame(level: usize, output: &str) -> Option<FrameLayout> {
+    let cfa = parse_frame_cfa(output)?;
+    let saved = match output.split_once("Saved registers:") {
+        Some((_, saved)) => RE_SAVED
+            .captures_iter(saved)
+            .filter_map(|caps| Some((caps[1].to_string(), parse_number(&caps[2])?)))
+            .collect(),
+        None => vec![],
+    };
+    Some(FrameLayout { level, cfa, saved, ..FrameLayout::default() })
+}
+
+/// Addresses of `names` from the `printf "%p %p\n", &a, &b` of [`crate::mi::frame_var_addrs`],
+/// nothing when it failed
+pub fn parse_var_addrs(names: &[String], output: &str) -> Vec<(String, u64)> {
+    let addrs: Vec<&str> = output.split_whitespace().collect();
+    if addrs.len() != names.len() {
+        return vec![];
+    }
+    names
+        .iter()
+        .zip(addrs)
+        .filter_map(|(name, addr)| Some((name.clone(), parse_number(addr)?)))
+        .collect()
+}
+
+/// Registers `info frame` names for the saved return address
+pub const PC_REGS: [&str; 4] = ["rip", "eip", "pc", "lr"];
+
+/// Registers `info frame` names for the saved frame pointer
+pub const FP_REGS: [&str; 4] = ["rbp", "ebp", "x29", "fp"];
+
+/// Frame pointers below whose saved slot gcc and clang place the canary
+const CANARY_FP_REGS: [&str; 2] = ["rbp", "ebp"];
+
+/// Slot that held the stack protector canary at a stop, in the frame of `cfa` running `func`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanarySlot {
+    pub addr: u64,
+    pub cfa: u64,
+    pub func: Option<String>,
+}
+
+/// Expression of the stack protector canary: the TLS slot of glibc on x86, or else the
+/// `__stack_chk_guard` global
+pub fn canary_expression(register_names: &[String], ptr_64: bool) -> &'static str {
+    let has = |name: &str| register_names.iter().any(|r| r == name);
+    if ptr_64 && has("fs_base") {
+        "(void*)*(void**)($fs_base+0x28)"
+    } else if !ptr_64 && has("gs_base") {
+        "(void*)*(void**)($gs_base+0x14)"
+    } else {
+        "(void*)*(void**)&__stack_chk_guard"
+    }
+}
+
+/// Slots of the canary read at the last stop, keeping those of `previous` whose frame is still
+/// in `layouts`
+pub fn canary_slots(
+    previous: &[CanarySlot],
+    layouts: &[FrameLayout],
+    slots: impl Iterator<Item = (u64, u64)>,
+    canary: u64,
+    growth: Growth,
+) -> Vec<CanarySlot> {
+    let live =
+        |cfa: u64, func: &Option<String>| layouts.iter().any(|l| l.cfa == cfa && l.func == *func);
+    let mut canaries: Vec<CanarySlot> =
+        previous.iter().filter(|c| live(c.cfa, &c.func)).cloned().collect();
+    for (addr, val) in slots {
+        if val != canary || canaries.iter().any(|c| c.addr == addr) {
+            continue;
+        }
+        // the innermost frame holding the slot
+        if let Some(layout) = layouts.iter().find(|l| growth.in_frame(addr, l.cfa)) {
+            canaries.push(CanarySlot { addr, cfa: layout.cfa, func: layout.func.clone() });
+        }
+    }
+    canaries
+}
+
+/// Slots of the canary in the layout of the x86 frames with a source location: the word below the
+/// saved frame pointer, unless a variable lives there. These are checked from the first stop, before
+/// the canary was seen in them
+pub fn frame_canary_slots(layouts: &[FrameLayout], slot_len: u64) -> Vec<CanarySlot> {
+    layouts
+        .iter()
+        .filter(|layout| layout.has_source)
+        .filter_map(|layout| {
+            let (_, fp) =
+                layout.saved.iter().find(|(reg, _)| CANARY_FP_REGS.contains(&reg.as_str()))?;
+            let addr = fp.checked_sub(slot_len)?;
+            if layout.vars.iter().any(|(_, var)| *var == addr) {
+                return None;
+            }
+            Some(CanarySlot { addr, cfa: layout.cfa, func: layout.func.clone() })
+        })
+        .collect()
+}
+
+/// Slots that look smashed, with why: a saved return address outside the code, a saved frame
+/// pointer outside the stack in a frame with a source location, or a canary that changed since
+/// a previous stop. Nothing is checked against mappings when there are none, nor against the
+/// stack without its bounds
+pub fn smashed_slots(
+    layouts: &[FrameLayout],
+    slot_value: impl Fn(u64) -> Option<u64>,
+    mappings: &[MemoryMapping],
+    stack: Option<Range<u64>>,
+    canary: Option<u64>,
+    canaries: &[CanarySlot],
+) -> BTreeMap<u64, String> {
+    let mut smashed = BTreeMap::new();
+    let in_code = |val: u64| mappings.iter().any(|m| m.contains(val) && m.is_exec());
+    for layout in layouts {
+        let level = layout.level;
+        for (reg, addr) in &layout.saved {
+            let Some(val) = slot_value(*addr) else {
+                continue;
+            };
+            if PC_REGS.contains(&reg.as_str()) && !mappings.is_empty() && !in_code(val) {
+                smashed
+                    .insert(*addr, format!("saved {reg} of #{level} is 0x{val:x}, outside code"));
+            } else if FP_REGS.contains(&reg.as_str())
+                && layout.has_source
+                && val != 0
+                && stack.as_ref().is_some_and(|stack| !stack.contains(&val))
+            {
+                smashed.insert(
+                    *addr,
+                    format!("saved {reg} of #{level} is 0x{val:x}, outside the stack"),
+                );
+            }
+        }
+    }
+    if let Some(canary) = canary {
+        for slot in canaries {
+            let Some(layout) = layouts.iter().find(|l| l.cfa == slot.cfa && l.func == slot.func)
+            else {
+                continue;
+            };
+            if let Some(val) = slot_value(slot.addr).filter(|val| *val != canary) {
+                let level = layout.level;
+                smashed.insert(slot.addr, format!("canary of #{level} is 0x{val:x}"));
+            }
+        }
+    }
+    smashed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stack_depth() {
+        assert_eq!(parse_stack_depth("mapping"), Ok(StackDepth::Mapping));
+        assert_eq!(parse_stack_depth(" 0x40 "), Ok(StackDepth::Slots(0x40)));
+        assert!(parse_stack_depth("0").is_err());
+        assert!(parse_stack_depth("deep").is_err());
+    }
+
+    #[test]
+    fn test_stack_model() {
+        let mut model = StackModel::default();
+        assert_eq!(model.sp_registers(), ["rsp", "esp", "sp"]);
+        assert_eq!(model.stack_bounds(Some(0x1000..0x2000), Some(0x1800)), Some(0x1000..0x2000));
+        assert_eq!(model.stack_bounds(None, Some(0x1800)), None);
+
+        // a Cortex-M with only `_estack`
+        assert!(model.apply("sp $psp").is_ok());
+        assert_eq!(model.sp_registers(), ["psp"]);
+        model.linker_high = Some(0x2002_0000);
+        assert_eq!(model.stack_bounds(None, Some(0x2001_ff00)), Some(0x2001_ff00..0x2002_0000));
+        model.linker_low = Some(0x2001_c000);
+        assert_eq!(model.stack_bounds(None, None), Some(0x2001_c000..0x2002_0000));
+
+        assert!(model.apply("bounds 0x3000 0x4000").is_ok());
+        assert_eq!(model.stack_bounds(Some(0x1000..0x2000), None), Some(0x3000..0x4000));
+        assert!(model.apply("bounds 0x4000 0x3000").is_err());
+        assert!(model.apply("bounds auto").is_ok());
+        assert_eq!(model.bounds, None);
+
+        assert!(model.apply("grows up").is_ok());
+        assert_eq!(model.growth, Growth::Up);
+        assert_eq!(model.growth.slot_addr(0x1000, 2, 4), 0xff8);
+        assert_eq!(model.growth.offset(0x1000, 0xff0), Some(0x10));
+        assert_eq!(model.growth.offset(0x1000, 0x1010), None);
+        // a garbage sp at the top of the address space
+        model.linker_high = None;
+        assert_eq!(model.stack_bounds(None, Some(u64::MAX)), Some(0x2001_c000..u64::MAX));
+        assert_eq!(model.growth.slot_addr(0x1000, u64::MAX, 8), 0x1008);
+        assert!(model.apply("grows sideways").is_err());
+        assert!(model.apply("sp").is_err());
+        assert!(model.apply("sp ($r13 & ~7)").is_ok());
+        assert!(model.sp_registers().is_empty());
+    }
+
+    #[test]
+    fn test_parse_frame_cfa() {
+        let output = "Stack level 1, frame at 0x7fffffffe0f0:
+ rip = 0x401136 in main (main.c:12); saved rip = 0x7ffff7dd6d90
+ caller of frame at 0x7fffffffe0d0
+ source language c.
+ Arglist at 0x7fffffffe0e0, args:
+ Locals at 0x7fffffffe0e0, Previous frame's sp is 0x7fffffffe0f0
+ Saved registers:
+  rbp at 0x7fffffffe0e0, rip at 0x7fffffffe0e8
+";
+        assert_eq!(parse_frame_cfa(output), Some(0x7fffffffe0f0));
+        assert_eq!(parse_frame_cfa("No stack.\n"), None);
+
+        let layout = parse_info_frame(1, output).unwrap();
+        assert_eq!(layout.cfa, 0x7fffffffe0f0);
+        assert_eq!(
+            layout.saved,
+            [("rbp".to_string(), 0x7fffffffe0e0), ("rip".to_string(), 0x7fffffffe0e8)]
+        );
+    }
+
+    #[test]
+    fn test_parse_var_addrs() {
+        let names = ["buf".to_string(), "argc".to_string()];
+        assert_eq!(
+            parse_var_addrs(&names, "0x7fffffffe0a0 0x7fffffffe09c\n"),
+            [("buf".to_string(), 0x7fffffffe0a0), ("argc".to_string(), 0x7fffffffe09c)]
+        );
+        assert!(parse_var_addrs(&names, "").is_empty());
+    }
+
+    fn mapping(start: u64, end: u64, permissions: &str, path: &str) -> MemoryMapping {
+        MemoryMapping {
+            start_address: start,
+            end_address: end,
+            size: end - start,
+            offset: 0,
+            permissions: Some(permissions.to_string()),
+            path: Some(path.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_smashed_slots() {
+        let mappings = [
+            mapping(0x400000, 0x401000, "r-xp", "/tmp/a.out"),
+            mapping(0x7ffffffde000, 0x7ffffffff000, "rw-p", "[stack]"),
+        ];
+        let layout = FrameLayout {
+            level: 0,
+            func: Some("parse".to_string()),
+            has_source: true,
+            cfa: 0x7fffffffe0f0,
+            saved: vec![("rbp".to_string(), 0x7fffffffe0e0), ("rip".to_string(), 0x7fffffffe0e8)],
+            vars: vec![],
+        };
+        let layouts = [layout];
+        let canary = 0x1122334455667700;
+        let mut stack = BTreeMap::from([
+            (0x7fffffffe0d8, canary),
+            (0x7fffffffe0e0, 0x7fffffffe110),
+            (0x7fffffffe0e8, 0x400123),
+        ]);
+        let canaries = canary_slots(&[], &layouts, stack.clone().into_iter(), canary, Growth::Down);
+        assert_eq!(canaries.len(), 1);
+        let check = |stack: &BTreeMap<u64, u64>| {
+            smashed_slots(
+                &layouts,
+                |addr| stack.get(&addr).copied(),
+                &mappings,
+                Some(0x7ffffffde000..0x7ffffffff000),
+                Some(canary),
+                &canaries,
+            )
+        };
+        assert!(check(&stack).is_empty());
+
+        // an overflow of a buffer below the canary
+        stack.insert(0x7fffffffe0d8, 0x4141414141414141);
+        stack.insert(0x7fffffffe0e0, 0x4141414141414141);
+        stack.insert(0x7fffffffe0e8, 0x4141414141414141);
+        let smashed = check(&stack);
+        assert_eq!(smashed[&0x7fffffffe0d8], "canary of #0 is 0x4141414141414141");
+        assert_eq!(
+            smashed[&0x7fffffffe0e0],
+            "saved rbp of #0 is 0x4141414141414141, outside the stack"
+        );
+        assert_eq!(smashed[&0x7fffffffe0e8], "saved rip of #0 is 0x4141414141414141, outside code");
+
+        // the canary in the layout of the frame is checked from the first stop
+        let frame_canaries = frame_canary_slots(&layouts, 8);
+        assert_eq!(frame_canaries, canaries);
+        let slot_value = |addr| stack.get(&addr).copied();
+        let smashed = smashed_slots(&layouts, slot_value, &[], None, Some(canary), &frame_canaries);
+        assert_eq!(smashed[&0x7fffffffe0d8], "canary of #0 is 0x4141414141414141");
+        // unless a variable lives below the saved frame pointer
+        let mut with_var = layouts[0].clone();
+        with_var.vars = vec![("len".to_string(), 0x7fffffffe0d8)];
+        assert!(frame_canary_slots(&[with_var], 8).is_empty());
+
+        // the canary of a returned frame is forgotten
+        assert!(canary_slots(&canaries, &[], std::iter::empty(), canary, Growth::Down).is_empty());
+        // without mappings nor stack bounds only the canary is checked
+        let slot_value = |addr| stack.get(&addr).copied();
+        let smashed = smashed_slots(&layouts, slot_value, &[], None, Some(canary), &canaries);
+        assert_eq!(smashed.len(), 1);
+    }
+
+    #[test]
+    fn test_canary_expression() {
+        let names = ["rip".to_string(), "fs_base".to_string()];
+        assert_eq!(canary_expression(&names, true), "(void*)*(void**)($fs_base+0x28)");
+        assert_eq!(canary_expression(&[], false), "(void*)*(void**)&__stack_chk_guard");
+    }
+}
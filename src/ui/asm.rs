@@ -1,12 +1,32 @@
+use std::sync::LazyLock;
+
 use ratatui::layout::Constraint;
-use ratatui::prelude::Stylize;
+use ratatui::style::Color;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Cell, Table, TableState};
 use ratatui::{Frame, layout::Rect, style::Style, widgets::Row};
+use regex::Regex;
 
-use super::{GREEN, PURPLE, effective_mode, pane_block};
+use super::{DARK_GRAY, GREEN, LABEL_COLOR, PURPLE, breakpoint_marker, effective_mode, pane_block};
 
 use crate::{Mode, State};
 
+static RE_ADDR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"0x[0-9a-fA-F]+").unwrap());
+
+/// Labels of the addresses among the operands of `inst`
+fn operand_labels(state: &State, inst: &str) -> Vec<String> {
+    let mut names = vec![];
+    for m in RE_ADDR.find_iter(inst) {
+        if let Ok(addr) = u64::from_str_radix(&m.as_str()[2..], 16)
+            && let Some(name) = state.labels.describe(addr)
+            && !names.contains(&name)
+        {
+            names.push(name);
+        }
+    }
+    names
+}
+
 pub fn draw_asm(state: &mut State, f: &mut Frame, asm: Rect) {
     // Asm
     // TODO: cache the pc_index if this doesn't change
@@ -16,24 +36,33 @@ pub fn draw_asm(state: &mut State, f: &mut Frame, asm: Rect) {
     let mut tallest_function_len = 0;
 
     // Display asm, this will already be in a sorted order
-    for (index, a) in state.asm.iter().enumerate() {
-        if a.address == state.current_pc {
+    for (index, a) in state.view.asm.iter().enumerate() {
+        let is_pc = a.address == state.view.current_pc;
+        // stripped code has no functions, but may have labels
+        let label = if a.func_name.is_none() { state.labels.describe(a.address) } else { None };
+        if is_pc {
             pc_index = Some(index);
-            if let Some(func_name) = &a.func_name {
+            if let Some(func_name) = a.func_name.as_ref().or(label.as_ref()) {
                 function_name = Some(func_name.clone());
                 if func_name.len() > tallest_function_len {
                     tallest_function_len = func_name.len();
                 }
             }
         }
+        let pc_marker =
+            if is_pc { Span::styled(">>", Style::new().fg(GREEN)) } else { "  ".into() };
+        let gutter =
+            Line::from(vec![breakpoint_marker(state.breakpoint_at_addr(a.address)), pc_marker]);
         let addr_cell =
             Cell::from(format!("0x{:02x}", a.address)).style(Style::default().fg(PURPLE));
-        let mut row = vec![addr_cell];
+        let mut row = vec![Cell::from(gutter), addr_cell];
 
         if let Some(function_name) = &a.func_name {
             let function_cell = Cell::from(format!("{function_name}+{:02x}", a.offset))
                 .style(Style::default().fg(PURPLE));
             row.push(function_cell);
+        } else if let Some(label) = label {
+            row.push(Cell::from(label).style(Style::default().fg(LABEL_COLOR)));
         } else {
             row.push(Cell::from(""));
         }
@@ -46,31 +75,39 @@ pub fn draw_asm(state: &mut State, f: &mut Frame, asm: Rect) {
             a.inst.clone()
         };
 
-        let inst_cell = if let Some(pc_index) = pc_index {
-            if pc_index == index { Cell::from(inst).fg(GREEN) } else { Cell::from(inst).white() }
+        let inst_color = if let Some(pc_index) = pc_index {
+            if pc_index == index { GREEN } else { Color::White }
         } else {
-            Cell::from(inst).dark_gray()
+            Color::DarkGray
         };
-        row.push(inst_cell);
+        let labels = operand_labels(state, &inst);
+        let mut inst_spans = vec![Span::styled(inst, Style::new().fg(inst_color))];
+        for name in labels {
+            inst_spans.push(Span::styled(format!("  <{name}>"), Style::new().fg(LABEL_COLOR)));
+        }
+        row.push(Cell::from(Line::from(inst_spans)));
 
         rows.push(Row::new(row));
     }
 
     let active = matches!(effective_mode(state), Mode::OnlyInstructions);
-    let block = pane_block("Instructions", function_name, "", active);
+    let hints = if active { "b break  u until" } else { "" };
+    let block = pane_block("Instructions", function_name, hints, active);
     if let Some(pc_index) = pc_index {
         let widths = [
+            Constraint::Length(3),
             Constraint::Length(16),
             Constraint::Length(tallest_function_len as u16 + 5),
             Constraint::Fill(1),
         ];
-        let table = Table::new(rows, widths)
-            .block(block)
-            .row_highlight_style(Style::new().fg(GREEN))
-            .highlight_symbol(">>");
-        let start_offset = pc_index.saturating_sub(5);
-        let mut table_state =
-            TableState::default().with_offset(start_offset).with_selected(pc_index);
+        let mut table = Table::new(rows, widths).block(block);
+        let mut table_state = TableState::default().with_offset(pc_index.saturating_sub(5));
+        // The table scrolls from the pc view just enough to keep the cursor shown
+        if let Some(cursor) = state.asm_cursor.filter(|_| active) {
+            let cursor = cursor.min(state.view.asm.len() - 1);
+            table = table.row_highlight_style(Style::new().bg(DARK_GRAY));
+            table_state = table_state.with_selected(cursor);
+        }
         f.render_stateful_widget(table, asm, &mut table_state);
     } else {
         f.render_widget(block, asm);
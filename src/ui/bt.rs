@@ -1,21 +1,26 @@
+use ratatui::layout::Constraint;
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Cell, Paragraph, Row, Scrollbar, ScrollbarOrientation, Table};
 use ratatui::{Frame, layout::Rect, style::Style};
 
-use super::{ORANGE, PURPLE, pane_block};
+use super::{BLUE, GRAY_FG, GREEN, ORANGE, PURPLE, effective_mode, pane_block};
 
-use crate::State;
+use crate::{Bt, Mode, State};
 
 pub fn draw_bt(state: &mut State, f: &mut Frame, bt_rect: Rect) {
     let block = pane_block("Backtrace", None, "", false);
     let mut lines = vec![];
-    for b in &state.bt {
-        let loc_span = Span::from(format!("  {:08x}", b.location,)).style(Style::new().fg(PURPLE));
+    for (level, b) in state.view.bt.iter().enumerate() {
+        let loc_span =
+            Span::from(format!("  {:08x}", b.frame.addr,)).style(Style::new().fg(PURPLE));
 
-        let func_span = Span::from(b.function.clone().unwrap_or(String::new()).clone())
+        let func_span = Span::from(b.frame.func.clone().unwrap_or(String::new()).clone())
             .style(Style::new().fg(ORANGE));
         let spans = vec![loc_span, Span::from(" → "), func_span];
-        let line = Line::from(spans);
+        let mut line = Line::from(spans);
+        if level == state.bt_selected {
+            line = line.style(Style::new().bold());
+        }
         lines.push(line);
     }
 
@@ -23,3 +28,141 @@ pub fn draw_bt(state: &mut State, f: &mut Frame, bt_rect: Rect) {
     let paragraph = Paragraph::new(text).block(block);
     f.render_widget(paragraph, bt_rect);
 }
+
+/// "func(arg=value, ...)", aggregates without a simple value are shown as "..."
+fn call(bt: &Bt) -> String {
+    let func = bt.frame.func.clone().unwrap_or_else(|| "??".to_string());
+    let args: Vec<String> = bt
+        .args
+        .iter()
+        .map(|a| format!("{}={}", a.name, a.value.as_deref().unwrap_or("...")))
+        .collect();
+    format!("{func}({})", args.join(", "))
+}
+
+/// Full backtrace tab, with frame selection
+pub fn draw_backtrace(state: &mut State, f: &mut Frame, area: Rect) {
+    let header =
+        Row::new(["", "#", "Address", "Function", "Location"]).style(Style::new().fg(BLUE).bold());
+
+    let len = state.view.bt.len();
+    state.bt_cursor = state.bt_cursor.min(len.saturating_sub(1));
+    // Account for top border and pinned header row
+    let max = area.height.saturating_sub(2) as usize;
+    state.bt_scroll.set_max_scroll(len.saturating_sub(max));
+    state.bt_scroll.keep_visible(state.bt_cursor, max);
+    let skip = state.bt_scroll.scroll;
+
+    let rows: Vec<Row> = state
+        .view
+        .bt
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(max)
+        .map(|(level, b)| {
+            let is_selected = level == state.bt_selected;
+            let row = Row::new(vec![
+                Cell::from(if is_selected { ">" } else { "" }),
+                Cell::from(level.to_string()),
+                Cell::from(format!("0x{:02x}", b.frame.addr)).style(Style::new().fg(PURPLE)),
+                Cell::from(call(b)),
+                Cell::from(b.frame.location().unwrap_or_default()).style(Style::new().fg(GRAY_FG)),
+            ]);
+            if level == state.bt_cursor {
+                row.style(Style::new().fg(ORANGE).bold())
+            } else if is_selected {
+                row.style(Style::new().fg(GREEN))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(1),
+        Constraint::Length(3),
+        Constraint::Length(18),
+        Constraint::Fill(2),
+        Constraint::Fill(1),
+    ];
+    let active = matches!(effective_mode(state), Mode::OnlyBacktrace);
+    let context = (state.bt_selected != 0).then(|| format!("frame #{}", state.bt_selected));
+    let block = pane_block("Backtrace", context, "⏎ select frame", active);
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area,
+        &mut state.bt_scroll.state,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mi::{Variable, parse_frame};
+    use crate::{Args, PtrSize};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_call_with_args() {
+        let bt = Bt {
+            frame: parse_frame(r#"{level="0",addr="0x401136",func="add"}"#),
+            args: vec![
+                Variable { name: "a".into(), type_: None, value: Some("1".into()), arg: true },
+                Variable { name: "p".into(), type_: None, value: None, arg: true },
+            ],
+        };
+        assert_eq!(call(&bt), "add(a=1, p=...)");
+    }
+
+    #[test]
+    fn test_draw_backtrace_selected_frame() {
+        let mut state = create_test_state();
+        state.view.bt = vec![
+            Bt {
+                frame: parse_frame(
+                    r#"{level="0",addr="0x401136",func="add",file="test.c",line="3"}"#,
+                ),
+                args: vec![],
+            },
+            Bt {
+                frame: parse_frame(
+                    r#"{level="1",addr="0x401180",func="main",file="test.c",line="11"}"#,
+                ),
+                args: vec![],
+            },
+        ];
+        state.bt_selected = 1;
+        state.bt_cursor = 1;
+
+        let backend = TestBackend::new(100, 8);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_backtrace(&mut state, f, area);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| (0..100).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(0).contains("frame #1"));
+        assert!(row(3).starts_with("> 1"));
+        assert!(row(3).contains("main()"));
+        assert!(row(3).contains("test.c:11"));
+    }
+}
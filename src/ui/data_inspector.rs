@@ -0,0 +1,224 @@
+use chrono::DateTime;
+use deku::ctx::Endian;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::{BLUE, GRAY_FG, apply_val_color, pane_block};
+use crate::{PtrSize, State};
+
+/// Width of the data inspector sidebar next to the hexdump
+pub const DATA_INSPECTOR_WIDTH: u16 = 48;
+
+/// Longest C string preview, in bytes
+const STR_PREVIEW: usize = 32;
+
+/// Unsigned value of the first `size` bytes, or `None` past the end of the dump
+pub fn unsigned(bytes: &[u8], size: usize, big: bool) -> Option<u64> {
+    let bytes = bytes.get(..size)?;
+    let fold = |acc: u64, b: &u8| (acc << 8) | u64::from(*b);
+    Some(if big { bytes.iter().fold(0, fold) } else { bytes.iter().rev().fold(0, fold) })
+}
+
+/// Sign extend the low `size` bytes of `val`
+pub fn signed(val: u64, size: usize) -> i64 {
+    let shift = 64 - size * 8;
+    ((val << shift) as i64) >> shift
+}
+
+/// The value of `ty` decoded from `bytes`, "-" if the dump ends first
+fn decode(ty: &str, bytes: &[u8], big: bool) -> String {
+    let size = match ty {
+        "u8" | "i8" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" | "f32" => 4,
+        _ => 8,
+    };
+    let Some(val) = unsigned(bytes, size, big) else {
+        return "-".to_string();
+    };
+    match ty {
+        "f32" => f32::from_bits(val as u32).to_string(),
+        "f64" => f64::from_bits(val).to_string(),
+        _ if ty.starts_with('i') => signed(val, size).to_string(),
+        _ => val.to_string(),
+    }
+}
+
+/// Bytes up to the first NUL, with non printable bytes escaped
+pub fn c_string(bytes: &[u8]) -> String {
+    let mut s = String::new();
+    for b in bytes.iter().take_while(|b| **b != 0).take(STR_PREVIEW) {
+        match b {
+            b'"' => s.push_str("\\\""),
+            b'\\' => s.push_str("\\\\"),
+            b'\n' => s.push_str("\\n"),
+            b'\t' => s.push_str("\\t"),
+            b' '..=b'~' => s.push(*b as char),
+            _ => s.push_str(&format!("\\x{b:02x}")),
+        }
+    }
+    let truncated = bytes.iter().take_while(|b| **b != 0).count() > STR_PREVIEW;
+    format!("\"{s}\"{}", if truncated { "…" } else { "" })
+}
+
+/// Symbol `val` points into as "name+0xoff", else the path of its mapping
+fn symbol_at(state: &State, val: u64) -> Option<String> {
+    let mapping = state.view.memory_map.as_ref()?.iter().find(|m| m.contains(val))?;
+    let symbol = state
+        .symbols
+        .iter()
+        .filter(|s| !s.needs_address_resolution && s.address <= val && mapping.contains(s.address))
+        .max_by_key(|s| s.address);
+    match symbol {
+        Some(s) if s.address == val => Some(s.name.clone()),
+        Some(s) => Some(format!("{}+0x{:x}", s.name, val - s.address)),
+        None => mapping.path.as_ref().map(|p| p.rsplit('/').next().unwrap_or(p).to_string()),
+    }
+}
+
+/// Decoded values of `bytes`, the hexdump from the cursor on: integers and floats in both
+/// endiannesses with the target's highlighted, then a pointer, C string and time_t
+fn inspector_lines(state: &State, bytes: &[u8]) -> Vec<Line<'static>> {
+    let big = state.view.endian == Some(Endian::Big);
+    let (le_style, be_style) = if big {
+        (Style::new().fg(GRAY_FG), Style::new())
+    } else {
+        (Style::new(), Style::new().fg(GRAY_FG))
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{:<4} {:>20} {:>20}", "type", "le", "be"),
+        Style::new().fg(BLUE).bold(),
+    ))];
+    for ty in ["u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "f32", "f64"] {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{ty:<4} "), Style::new().fg(BLUE)),
+            Span::styled(format!("{:>20} ", decode(ty, bytes, false)), le_style),
+            Span::styled(format!("{:>20}", decode(ty, bytes, true)), be_style),
+        ]));
+    }
+    lines.push(Line::default());
+
+    let size = if state.view.ptr_size == PtrSize::Size32 { 4 } else { 8 };
+    let label = |name: &str| Span::styled(format!("{name:<4} "), Style::new().fg(BLUE));
+    let Some(ptr) = unsigned(bytes, size, big) else {
+        return lines;
+    };
+    let filepath = state.view.filepath.clone().unwrap_or_default();
+    let (is_stack, is_heap, is_text) = state.classify_val(ptr, &filepath.to_string_lossy());
+    let mut span = Span::raw(format!("0x{ptr:x}"));
+    apply_val_color(&mut span, is_stack, is_heap, is_text);
+    lines.push(Line::from(vec![label("ptr"), span]));
+    if let Some(symbol) = symbol_at(state, ptr) {
+        lines.push(Line::from(vec![label("sym"), Span::raw(symbol)]));
+    }
+    lines.push(Line::from(vec![label("str"), Span::raw(c_string(bytes))]));
+    let time = DateTime::from_timestamp(signed(ptr, size), 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    lines.push(Line::from(vec![label("time"), Span::raw(time)]));
+    lines
+}
+
+pub fn draw_data_inspector(state: &mut State, f: &mut Frame, area: Rect) {
+    let Some(memory) = &state.view.hexdump else {
+        return;
+    };
+    let cursor = state.view.hexdump_cursor.min(memory.len.saturating_sub(1));
+    // Only as many bytes as the widest value, up to a page not loaded, with pending edits applied
+    let bytes: Vec<u8> = (cursor..memory.len)
+        .take(STR_PREVIEW + 1)
+        .map_while(|offset| state.hexdump_byte(offset))
+        .collect();
+    let pos = format!("0x{:x}", memory.start + cursor as u64);
+    let paragraph = Paragraph::new(inspector_lines(state, &bytes)).block(pane_block(
+        "Inspect",
+        Some(pos),
+        "h/l move",
+        false,
+    ));
+    f.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mi::MemoryMapping;
+    use crate::{Args, Symbol};
+
+    fn test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    fn line(lines: &[Line], name: &str) -> String {
+        lines.iter().map(|l| l.to_string()).find(|l| l.starts_with(name)).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_decode() {
+        let bytes = [0xfe, 0xff, 0x00, 0x00, 0x00, 0x00, 0xf0, 0x3f];
+        assert_eq!(decode("u8", &bytes, false), "254");
+        assert_eq!(decode("i8", &bytes, false), "-2");
+        assert_eq!(decode("u16", &bytes, false), "65534");
+        assert_eq!(decode("u16", &bytes, true), "65279");
+        assert_eq!(decode("i16", &bytes, false), "-2");
+        assert_eq!(decode("i32", &bytes, true), "-16842752");
+        assert_eq!(decode("f64", &[0, 0, 0, 0, 0, 0, 0xf0, 0x3f], false), "1");
+        assert_eq!(decode("f32", &[0x00, 0x00, 0x80, 0x3f], false), "1");
+        assert_eq!(decode("u64", &bytes[..4], false), "-");
+    }
+
+    #[test]
+    fn test_c_string() {
+        assert_eq!(c_string(b"hi\n\x01\0junk"), "\"hi\\n\\x01\"");
+        assert_eq!(c_string(&[b'a'; 40]), format!("\"{}\"…", "a".repeat(STR_PREVIEW)));
+    }
+
+    #[test]
+    fn test_inspector_pointer() {
+        let mut state = test_state();
+        state.view.memory_map = Some(vec![MemoryMapping {
+            start_address: 0x400000,
+            end_address: 0x401000,
+            size: 0x1000,
+            offset: 0,
+            permissions: Some("r-xp".to_string()),
+            path: Some("/tmp/a.out".to_string()),
+        }]);
+        state.symbols.push(Symbol {
+            address: 0x400100,
+            name: "main".to_string(),
+            needs_address_resolution: false,
+        });
+        let lines = inspector_lines(&state, &0x400110_u64.to_le_bytes());
+        assert_eq!(line(&lines, "ptr"), "ptr  0x400110");
+        assert_eq!(line(&lines, "sym"), "sym  main+0x10");
+        assert_eq!(line(&lines, "time"), "time 1970-02-18 13:09:36 UTC");
+
+        let lines = inspector_lines(&state, &0x400010_u64.to_le_bytes());
+        assert_eq!(line(&lines, "sym"), "sym  a.out");
+
+        state.view.endian = Some(Endian::Big);
+        let lines = inspector_lines(&state, &0x400110_u64.to_be_bytes());
+        assert_eq!(line(&lines, "sym"), "sym  main+0x10");
+    }
+
+    #[test]
+    fn test_inspector_short_dump() {
+        let state = test_state();
+        let lines = inspector_lines(&state, &[0x41, 0x42]);
+        assert!(line(&lines, "u16").ends_with("16961                16706"));
+        assert_eq!(line(&lines, "ptr"), "");
+    }
+}
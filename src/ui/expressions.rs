@@ -0,0 +1,119 @@
+use ratatui::layout::Constraint;
+use ratatui::widgets::{Cell, Scrollbar, ScrollbarOrientation, Table};
+use ratatui::{Frame, layout::Rect, style::Style, widgets::Row};
+
+use super::draw_input_popup;
+use super::{BLUE, GRAY_FG, ORANGE, RED, effective_mode, pane_block};
+use crate::{Mode, State};
+
+pub fn draw_expressions(state: &mut State, f: &mut Frame, area: Rect, popup: bool) {
+    let header = Row::new(["", "Expression", "Fmt", "Value"]).style(Style::new().fg(BLUE).bold());
+
+    let len = state.view.expressions.len();
+    state.expressions_selected = state.expressions_selected.min(len.saturating_sub(1));
+    // Account for top border and pinned header row
+    let max = area.height.saturating_sub(2) as usize;
+    state.expressions_scroll.set_max_scroll(len.saturating_sub(max));
+    state.expressions_scroll.keep_visible(state.expressions_selected, max);
+    let skip = state.expressions_scroll.scroll;
+
+    let rows: Vec<Row> = state
+        .view
+        .expressions
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(max)
+        .map(|(index, expression)| {
+            let value = match (&expression.value, &expression.error) {
+                (_, Some(error)) => Cell::from(error.clone()).style(Style::new().fg(GRAY_FG)),
+                (Some(value), None) => Cell::from(expression.format.apply(value)),
+                (None, None) => Cell::from(""),
+            };
+            let value = if expression.changed { value.style(Style::new().fg(RED)) } else { value };
+            let row = Row::new([
+                Cell::from(if expression.changed { "*" } else { "" }).style(Style::new().fg(RED)),
+                Cell::from(expression.text.clone()),
+                Cell::from(expression.format.name()).style(Style::new().fg(GRAY_FG)),
+                value,
+            ]);
+            if index == state.expressions_selected {
+                row.style(Style::new().fg(ORANGE).bold())
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths =
+        [Constraint::Length(1), Constraint::Fill(1), Constraint::Length(4), Constraint::Fill(1)];
+    let active = state.expressions_focus
+        && matches!(effective_mode(state), Mode::OnlyLocals | Mode::OnlyExpressionsPopup);
+    let block = pane_block("Expressions", None, "a add  d delete  f format", active);
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area,
+        &mut state.expressions_scroll.state,
+    );
+
+    if popup {
+        draw_input_popup(f, area, "Expression", state.expressions_popup.value().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Args, Expression, PtrSize, ValueFormat};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_draw_expressions() {
+        let mut state = create_test_state();
+        state.view.expressions = vec![
+            Expression {
+                name: "expr0".to_string(),
+                text: "ctx->state".to_string(),
+                value: Some("10".to_string()),
+                format: ValueFormat::Hex,
+                changed: true,
+                ..Expression::default()
+            },
+            Expression {
+                name: "expr1".to_string(),
+                text: "nope".to_string(),
+                error: Some("No symbol \"nope\" in current context.".to_string()),
+                ..Expression::default()
+            },
+        ];
+
+        let backend = TestBackend::new(80, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_expressions(&mut state, f, area, false);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(2).starts_with("* ctx->state"));
+        assert!(row(2).contains("hex  0xa"));
+        assert!(row(3).contains("No symbol"));
+    }
+}
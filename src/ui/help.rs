@@ -31,9 +31,14 @@ fn popup_area(area: Rect, width: u16, height: u16) -> Rect {
 pub fn draw_help(f: &mut Frame) {
     let left = vec![
         header("Global"),
-        entry("F1-F9", "switch pane"),
+        entry("F1-F10", "switch pane"),
+        entry("F12", "backtrace pane"),
+        entry("Alt+t", "threads pane"),
+        entry("Alt+l", "locals pane"),
         entry("Tab", "next pane"),
         entry("i", "command input"),
+        entry("p", "inspect expression"),
+        entry("I", "next inferior"),
         entry("Ctrl+C", "interrupt gdb"),
         entry("q", "quit"),
         entry("?", "toggle this help"),
@@ -43,6 +48,23 @@ pub fn draw_help(f: &mut Frame) {
         entry("J / K", "down / up 50"),
         entry("g / G", "top / bottom"),
         Line::default(),
+        header("Source / Instructions"),
+        entry("b", "toggle breakpoint"),
+        entry("u", "run to cursor"),
+        Line::default(),
+        header("Registers"),
+        entry("j / k", "select"),
+        entry("e", "edit, ⏎ write"),
+        Line::default(),
+        header("Stack"),
+        entry("J / K", "down / up a page"),
+        entry("f", "goto selected frame"),
+        Line::default(),
+        header("Inspector"),
+        entry("⏎ / Space", "expand / collapse"),
+        entry("H / t", "hexdump / telescope"),
+        entry("i", "new expression"),
+        Line::default(),
         header("Colors"),
         Line::from(vec![
             Span::raw("  "),
@@ -59,10 +81,38 @@ pub fn draw_help(f: &mut Frame) {
         entry("H", "hexdump region"),
         Line::default(),
         header("Hexdump"),
-        entry("S", "save to file"),
+        entry("S", "save to file, Tab format"),
+        entry("v", "select to save"),
+        entry("L", "load file into memory"),
+        entry("t", "overlay a C type"),
+        entry("m", "bookmark the cursor"),
+        entry("'", "list bookmarks and labels"),
+        entry("e", "edit, ⏎ write"),
+        entry("/ n N", "search, next / prev"),
+        entry("c", "changed rows only"),
+        entry("h / l", "inspect prev / next byte"),
+        entry("w / u", "row width / word size"),
+        entry("z / a", "zero rows / text encoding"),
+        entry("n N", "diff: next / prev difference"),
         entry("H", "goto heap"),
         entry("T", "goto stack"),
         Line::default(),
+        header("Watchpoints"),
+        entry("w / r / a", "watch / rwatch / awatch"),
+        entry("e / d", "enable / delete"),
+        Line::default(),
+        header("Threads"),
+        entry("⏎", "switch thread"),
+        Line::default(),
+        header("Backtrace"),
+        entry("⏎", "select frame"),
+        Line::default(),
+        header("Locals"),
+        entry("h / l", "locals / expressions"),
+        entry("⏎ / Space", "expand / collapse"),
+        entry("e", "edit value"),
+        entry("a / d / f", "add / delete / format"),
+        Line::default(),
         header("Symbols"),
         entry("/", "search (fuzzy)"),
         entry("r", "refresh"),
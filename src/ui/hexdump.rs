@@ -1,3 +1,7 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use deku::ctx::Endian;
 use ratatui::{
     Frame,
     layout::{Constraint, Flex, Layout, Rect},
@@ -6,13 +10,152 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation},
 };
 
-use crate::{PtrSize, State};
+use crate::memory::{MemoryLoad, Page, PagedMemory};
+use crate::{Mode, PtrSize, State};
 
-use super::{BLUE, DARK_GRAY, GREEN, ORANGE, YELLOW, effective_mode, pane_block};
+use super::data_inspector::{DATA_INSPECTOR_WIDTH, draw_data_inspector};
+use super::labels::draw_bookmarks;
+use super::type_overlay::{draw_type_overlay, overlay_color};
+use super::{
+    BLUE, DARK_GRAY, GRAY_FG, GREEN, LABEL_COLOR, ORANGE, PURPLE, RED, YELLOW, draw_input_popup,
+    effective_mode, pane_block,
+};
 
+/// Bytes per row of the default layout
 pub const HEXDUMP_WIDTH: usize = 16;
 
-/// One display line of the hexdump: either a real 16-byte row (by row index)
+/// Row widths an auto width is picked from, widest first
+const AUTO_WIDTHS: [usize; 4] = [64, 32, 16, 8];
+
+/// Bytes per row of the hexdump, fixed or the widest fitting the pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowWidth {
+    Fixed(usize),
+    Auto,
+}
+
+impl RowWidth {
+    /// 8, 16, 32, auto, and back to 8
+    pub fn next(self) -> Self {
+        match self {
+            Self::Fixed(8) => Self::Fixed(16),
+            Self::Fixed(16) => Self::Fixed(32),
+            Self::Fixed(_) => Self::Auto,
+            Self::Auto => Self::Fixed(8),
+        }
+    }
+}
+
+/// How the bytes of the text column are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    /// Printable ascii, `.` for the rest
+    Ascii,
+    /// Every byte as its code page 437 glyph
+    Cp437,
+    /// `.` for every byte, only the colors are left
+    Dots,
+}
+
+impl TextEncoding {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Ascii => Self::Cp437,
+            Self::Cp437 => Self::Dots,
+            Self::Dots => Self::Ascii,
+        }
+    }
+
+    pub fn char(self, byte: u8) -> char {
+        match self {
+            Self::Ascii if byte.is_ascii_graphic() => byte as char,
+            Self::Ascii | Self::Dots => '.',
+            Self::Cp437 => CP437.chars().nth(byte as usize).unwrap_or('.'),
+        }
+    }
+}
+
+/// Glyphs of code page 437, by byte
+const CP437: &str = concat!(
+    " ☺☻♥♦♣♠•◘○◙♂♀♪♫☼►◄↕‼¶§▬↨↑↓→←∟↔▲▼",
+    " !\"#$%&'()*+,-./0123456789:;<=>?",
+    "@ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_",
+    "`abcdefghijklmnopqrstuvwxyz{|}~⌂",
+    "ÇüéâäàåçêëèïîìÄÅÉæÆôöòûùÿÖÜ¢£¥₧ƒ",
+    "áíóúñÑªº¿⌐¬½¼¡«»░▒▓│┤╡╢╖╕╣║╗╝╜╛┐",
+    "└┴┬├─┼╞╟╚╔╩╦╠═╬╧╨╤╥╙╘╒╓╫╪┘┌█▄▌▐▀",
+    "αßΓπΣσµτΦΘΩδ∞φε∩≡±≥≤⌠⌡÷≈°∙·√ⁿ²■\u{a0}",
+);
+
+/// Runtime layout of the hexdump rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HexdumpLayout {
+    pub width: RowWidth,
+    /// Bytes shown together as one word in the target's byte order: 1, 2, 4 or 8
+    pub group: usize,
+    /// Collapse runs of zero rows into a `*`
+    pub collapse: bool,
+    pub encoding: TextEncoding,
+}
+
+impl Default for HexdumpLayout {
+    fn default() -> Self {
+        Self {
+            width: RowWidth::Fixed(HEXDUMP_WIDTH),
+            group: 1,
+            collapse: true,
+            encoding: TextEncoding::Ascii,
+        }
+    }
+}
+
+impl HexdumpLayout {
+    /// 1, 2, 4, 8, and back to 1
+    pub fn next_group(self) -> usize {
+        if self.group >= 8 { 1 } else { self.group * 2 }
+    }
+
+    /// The parts of the layout that differ from the default, for the title
+    fn describe(&self, width: usize) -> String {
+        let mut parts = vec![];
+        match self.width {
+            RowWidth::Auto => parts.push(format!("auto {width}")),
+            RowWidth::Fixed(w) if w != HEXDUMP_WIDTH => parts.push(format!("{w}")),
+            RowWidth::Fixed(_) => {}
+        }
+        if self.group > 1 {
+            parts.push(format!("u{}", self.group * 8));
+        }
+        if !self.collapse {
+            parts.push("zeros".to_string());
+        }
+        match self.encoding {
+            TextEncoding::Ascii => {}
+            TextEncoding::Cp437 => parts.push("cp437".to_string()),
+            TextEncoding::Dots => parts.push("dots".to_string()),
+        }
+        parts.join(" ")
+    }
+}
+
+/// Columns of a row of `width` bytes in words of `group`: the offset, the words, and the text
+pub fn row_columns(width: usize, group: usize) -> usize {
+    10 + width / group * (group * 2 + 1) + 2 + width
+}
+
+/// Widest auto width whose rows fit in `columns`
+pub fn fit_width(columns: usize, group: usize) -> usize {
+    AUTO_WIDTHS.into_iter().find(|&w| row_columns(w, group) <= columns).unwrap_or(8)
+}
+
+/// What the display lines depend on: the bytes per row, and if zero rows collapse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Geometry {
+    pub width: usize,
+    pub collapse: bool,
+}
+
+/// One display line of the hexdump: either a real row (by row index)
 /// or the `*` marker standing in for the rest of a run of zero rows
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DisplayRow {
@@ -23,11 +166,18 @@ enum DisplayRow {
 /// Collapse runs of all-zero rows: the first row of a run is kept, the rest
 /// become a single `*` marker, like hexyl. Scrolling operates on this list so
 /// one scroll step always moves one visual line
+#[cfg(test)]
 fn display_rows(buffer: &[u8]) -> Vec<DisplayRow> {
+    display_rows_keeping(buffer, HEXDUMP_WIDTH, None)
+}
+
+/// [`display_rows`] of `width` byte rows, with row `keep` never collapsed, such as the row of
+/// the edit cursor
+fn display_rows_keeping(buffer: &[u8], width: usize, keep: Option<usize>) -> Vec<DisplayRow> {
     let mut rows = Vec::new();
     let mut zero_run = 0;
-    for (i, chunk) in buffer.chunks(HEXDUMP_WIDTH).enumerate() {
-        if chunk.iter().all(|&b| b == 0x00) {
+    for (i, chunk) in buffer.chunks(width).enumerate() {
+        if Some(i) != keep && chunk.iter().all(|&b| b == 0x00) {
             zero_run += 1;
             match zero_run {
                 1 => rows.push(DisplayRow::Row(i)),
@@ -42,12 +192,10 @@ fn display_rows(buffer: &[u8]) -> Vec<DisplayRow> {
     rows
 }
 
-/// Display index of the line showing `row`; rows hidden inside a collapsed
-/// run land on the run's `*` marker. Used to jump to an address
-pub fn display_index_of_row(buffer: &[u8], row: usize) -> usize {
+fn display_index(rows: &[DisplayRow], row: usize) -> usize {
     let mut index = 0;
     let mut prev_row = 0;
-    for (i, display_row) in display_rows(buffer).iter().enumerate() {
+    for (i, display_row) in rows.iter().enumerate() {
         let first_row = match display_row {
             DisplayRow::Row(r) => *r,
             DisplayRow::Collapsed => prev_row + 1,
@@ -61,13 +209,114 @@ pub fn display_index_of_row(buffer: &[u8], row: usize) -> usize {
     index
 }
 
-/// Render the given `rows` slice of the hexdump display lines
-fn to_hexdump_str<'a>(
-    state: &mut State,
-    pos: u64,
-    buffer: &[u8],
-    rows: &[DisplayRow],
-) -> Vec<Line<'a>> {
+fn row_at(rows: &[DisplayRow], index: usize) -> usize {
+    let mut row = 0;
+    for display_row in rows.iter().take(index + 1) {
+        row = match display_row {
+            DisplayRow::Row(r) => *r,
+            DisplayRow::Collapsed => row + 1,
+        };
+    }
+    row
+}
+
+/// Rows with a byte in `changed`, for the changes only filter
+fn changed_rows(changed: &BTreeSet<usize>, width: usize) -> Vec<DisplayRow> {
+    let mut rows: Vec<DisplayRow> =
+        changed.iter().map(|offset| DisplayRow::Row(offset / width)).collect();
+    rows.dedup();
+    rows
+}
+
+/// Display lines of the hexdump. Once a range read in full is loaded, its runs of zero rows are
+/// collapsed, unless collapsing is off. A paged range shows every row, most of its bytes are not
+/// known
+enum DisplayLines {
+    Rows(Vec<DisplayRow>),
+    All(usize),
+}
+
+impl DisplayLines {
+    fn new(memory: &PagedMemory, geometry: Geometry, keep: Option<usize>) -> Self {
+        if geometry.collapse
+            && memory.is_eager()
+            && let Some(buffer) = memory.contiguous()
+        {
+            Self::Rows(display_rows_keeping(&buffer, geometry.width, keep))
+        } else {
+            Self::All(memory.len.div_ceil(geometry.width))
+        }
+    }
+
+    /// Only the rows of `width` bytes with a byte in `changed`, even those collapsed as zero
+    fn changed(self, changed: &BTreeSet<usize>, width: usize) -> Self {
+        Self::Rows(changed_rows(changed, width))
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Rows(rows) => rows.len(),
+            Self::All(len) => *len,
+        }
+    }
+
+    fn slice(&self, skip: usize, take: usize) -> Vec<DisplayRow> {
+        let range = skip.min(self.len())..(skip + take).min(self.len());
+        match self {
+            Self::Rows(rows) => rows[range].to_vec(),
+            Self::All(_) => range.map(DisplayRow::Row).collect(),
+        }
+    }
+
+    fn index_of(&self, row: usize) -> usize {
+        match self {
+            Self::Rows(rows) => display_index(rows, row),
+            Self::All(_) => row,
+        }
+    }
+
+    fn row_at(&self, index: usize) -> usize {
+        match self {
+            Self::Rows(rows) => row_at(rows, index),
+            Self::All(_) => index,
+        }
+    }
+}
+
+/// Display index of the line showing `row`; rows hidden inside a collapsed
+/// run land on the run's `*` marker. Used to jump to an address
+pub fn display_index_of_row(memory: &PagedMemory, geometry: Geometry, row: usize) -> usize {
+    DisplayLines::new(memory, geometry, None).index_of(row)
+}
+
+/// Display index of `row`, with `row` itself never collapsed, such as the row of a search match
+pub fn display_index_keeping(memory: &PagedMemory, geometry: Geometry, row: usize) -> usize {
+    DisplayLines::new(memory, geometry, Some(row)).index_of(row)
+}
+
+/// First row shown by the display line `index`, the inverse of [`display_index_of_row`]
+pub fn row_at_display_index(memory: &PagedMemory, geometry: Geometry, index: usize) -> usize {
+    DisplayLines::new(memory, geometry, None).row_at(index)
+}
+
+/// Byte offsets shown by `rows` of `width` bytes, to read their pages
+fn rows_range(rows: &[DisplayRow], width: usize) -> Option<Range<usize>> {
+    let mut shown = rows.iter().rev().filter_map(|row| match row {
+        DisplayRow::Row(r) => Some(*r),
+        DisplayRow::Collapsed => None,
+    });
+    let last = shown.next()?;
+    let first = shown.next_back().unwrap_or(last);
+    Some(first * width..(last + 1) * width)
+}
+
+/// Render the given `rows` slice of the hexdump display lines, in the layout of `state`. Bytes of
+/// pages still being read are blank, and those of unreadable pages are `??`
+fn to_hexdump_str<'a>(state: &State, memory: &PagedMemory, rows: &[DisplayRow]) -> Vec<Line<'a>> {
+    let pos = memory.start;
+    let width = state.hexdump_width;
+    let layout = state.hexdump_layout;
+    let big = state.view.endian == Some(Endian::Big);
     let mut lines = Vec::new();
     for display_row in rows {
         let row = match display_row {
@@ -77,26 +326,49 @@ fn to_hexdump_str<'a>(
             }
             DisplayRow::Row(row) => *row,
         };
-        let start = row * HEXDUMP_WIDTH;
-        let chunk = &buffer[start..(start + HEXDUMP_WIDTH).min(buffer.len())];
+        let start = row * width;
+        let offsets = start..(start + width).min(memory.len);
+        let unknown = |offset: usize| {
+            if memory.is_unreadable(offset) {
+                ("??", Style::default().fg(GRAY_FG))
+            } else {
+                ("  ", Style::default())
+            }
+        };
 
         let mut hex_spans = Vec::new();
-        // bytes
-        for byte in chunk {
-            let color = color(*byte);
-            hex_spans.push(Span::styled(format!("{byte:02x} "), Style::default().fg(color)));
+        // bytes, as words in the target's byte order, padded to a full row
+        for word in (start..start + width).step_by(layout.group) {
+            let word = word..word + layout.group;
+            let word: Vec<usize> = if big { word.collect() } else { word.rev().collect() };
+            for offset in word {
+                if offset >= memory.len {
+                    hex_spans.push(Span::raw("  "));
+                } else if let Some(byte) = memory.get(offset) {
+                    let (byte, style) = byte_style(state, offset, byte, false);
+                    hex_spans.push(Span::styled(format!("{byte:02x}"), style));
+                } else {
+                    let (text, style) = unknown(offset);
+                    hex_spans.push(Span::styled(text, style));
+                }
+            }
+            hex_spans.push(Span::raw(" "));
         }
 
         // ascii
         hex_spans.push(Span::raw("| "));
-        for byte in chunk {
-            let ascii_char = if byte.is_ascii_graphic() { *byte as char } else { '.' };
-            let color = color(*byte);
-            hex_spans.push(Span::styled(ascii_char.to_string(), Style::default().fg(color)));
+        for offset in offsets {
+            if let Some(byte) = memory.get(offset) {
+                let (byte, style) = byte_style(state, offset, byte, true);
+                hex_spans.push(Span::styled(layout.encoding.char(byte).to_string(), style));
+            } else {
+                let (text, style) = unknown(offset);
+                hex_spans.push(Span::styled(&text[..1], style));
+            }
         }
 
         // check if value has a register reference
-        let thirty = state.ptr_size == PtrSize::Size32;
+        let thirty = state.view.ptr_size == PtrSize::Size32;
 
         let mut ref_spans = Vec::new();
 
@@ -106,7 +378,7 @@ fn to_hexdump_str<'a>(
         //deref_bytes_to_registers(&endian, chunk, thirty, &mut ref_spans, &registers);
 
         let windows = if thirty { 4 } else { 8 };
-        for r in &state.registers {
+        for r in &state.view.registers {
             if let Some(reg) = &r.register {
                 if !reg.is_set() {
                     continue;
@@ -115,7 +387,7 @@ fn to_hexdump_str<'a>(
                     && let Ok(val) = u64::from_str_radix(&reg_value[2..], 16)
                 {
                     for n in 0..=windows {
-                        if val as usize == pos as usize + (row * HEXDUMP_WIDTH + n) {
+                        if val as usize == pos as usize + (row * width + n) {
                             ref_spans.push(Span::raw(format!(
                                 "← ${}(0x{:02x}) ",
                                 r.name.clone(),
@@ -127,7 +399,16 @@ fn to_hexdump_str<'a>(
             }
         }
 
-        let line = vec![Span::raw(format!("{:08x}: ", row * HEXDUMP_WIDTH)), Span::raw("")]
+        // labels starting in the row, such as bookmarks
+        let row_addr = pos + (row * width) as u64;
+        let mut header = Span::raw(format!("{:08x}: ", row * width));
+        for label in state.labels.starting_in(row_addr..row_addr + width as u64) {
+            header = header.style(Style::default().fg(LABEL_COLOR));
+            ref_spans
+                .push(Span::styled(format!("← {} ", label.name), Style::default().fg(LABEL_COLOR)));
+        }
+
+        let line = vec![header, Span::raw("")]
             .into_iter()
             .chain(hex_spans)
             .chain(ref_spans)
@@ -139,6 +420,43 @@ fn to_hexdump_str<'a>(
     lines
 }
 
+/// Byte at `offset` with its pending edit, and its style:
+/// - pending edits in purple, writes that did not stick in red
+/// - bytes changed since the previous stop on red
+/// - fields of the type overlay on their color
+/// - the cursor underlined, reversed in the column being edited
+fn byte_style(state: &State, offset: usize, byte: u8, ascii: bool) -> (u8, Style) {
+    let edit = state.view.hexdump_edits.get(&offset).copied();
+    let byte = edit.unwrap_or(byte);
+    let mut style = if edit.is_some() {
+        Style::default().fg(PURPLE).bold().underlined()
+    } else if state.view.hexdump_failed.contains(&offset) {
+        Style::default().fg(RED).bold()
+    } else if state.view.hexdump_changed.contains(&offset) {
+        Style::default().fg(Color::Black).bg(RED)
+    } else if let Some(bg) = overlay_color(state, offset) {
+        // null bytes would vanish in dark gray
+        let fg = if byte == 0 { GRAY_FG } else { color(byte) };
+        Style::default().fg(fg).bg(bg)
+    } else {
+        Style::default().fg(color(byte))
+    };
+    if state.hexdump_selection_range().is_some_and(|range| range.contains(&offset)) {
+        style = style.fg(Color::Black).bg(BLUE);
+    }
+    if let Some(current) = state.view.hexdump_search.as_ref().and_then(|s| s.at(offset)) {
+        style = style.fg(Color::Black).bg(if current { YELLOW } else { ORANGE });
+    }
+    if offset == state.view.hexdump_cursor {
+        style = if state.mode == Mode::OnlyHexdumpEdit && ascii == state.view.hexdump_cursor_ascii {
+            style.reversed()
+        } else {
+            style.underlined()
+        };
+    }
+    (byte, style)
+}
+
 pub fn color(byte: u8) -> Color {
     if byte == 0x00 {
         DARK_GRAY
@@ -153,47 +471,127 @@ pub fn color(byte: u8) -> Color {
     }
 }
 
-fn popup_area(area: Rect, percent_x: u16) -> Rect {
-    let vertical = Layout::vertical([Constraint::Length(3)]).flex(Flex::Center);
-    let horizontal = Layout::horizontal([Constraint::Percentage(percent_x)]).flex(Flex::Center);
-    let [area] = vertical.areas(area);
-    let [area] = horizontal.areas(area);
-    area
-}
-
 /// Which popup, if any, to overlay on the hexdump pane
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub enum HexdumpPopup {
     None,
     Save,
     Goto,
+    Search,
+    Load,
+    LoadConfirm,
+    Type,
+    Label,
+    Bookmarks,
 }
 
 fn hexdump_block<'a>(state: &State, pos: Option<String>) -> Block<'a> {
     let active = matches!(
         effective_mode(state),
-        crate::Mode::OnlyHexdump
-            | crate::Mode::OnlyHexdumpPopup
-            | crate::Mode::OnlyHexdumpGotoPopup
+        Mode::OnlyHexdump
+            | Mode::OnlyHexdumpPopup
+            | Mode::OnlyHexdumpGotoPopup
+            | Mode::OnlyHexdumpEdit
+            | Mode::OnlyHexdumpSelect
+            | Mode::OnlyHexdumpSearchPopup
+            | Mode::OnlyHexdumpLoadPopup
+            | Mode::OnlyHexdumpLoadConfirm
+            | Mode::OnlyHexdumpTypePopup
+            | Mode::OnlyHexdumpLabelPopup
+            | Mode::OnlyHexdumpBookmarks
     );
-    pane_block("Hexdump", pos, "S save  : goto  H heap  T stack", active)
+    let hints = if state.mode == Mode::OnlyHexdumpEdit {
+        "⏎ write  Esc cancel  Tab hex/ascii  ⌫ undo"
+    } else if state.mode == Mode::OnlyHexdumpSelect {
+        "h/j/k/l extend  g/G start/end  S save  Esc cancel"
+    } else {
+        "e edit  / search  : goto  ? help"
+    };
+    pane_block("Hexdump", pos, hints, active)
 }
 
+/// Narrowest hexdump that still fits a row of bytes, its ascii and a register reference
+const HEXDUMP_MIN_WIDTH: u16 = 80;
+
 pub fn draw_hexdump(state: &mut State, f: &mut Frame, hexdump: Rect, popup: HexdumpPopup) {
-    let hexdump_active = state.hexdump.is_some();
+    let hexdump_active = state.view.hexdump.is_some();
 
     if hexdump_active {
-        let r = state.hexdump.clone().unwrap();
-        let pos = format!("0x{:02x?}", r.0);
-        let data = &r.1;
+        let hexdump = if hexdump.width >= HEXDUMP_MIN_WIDTH + DATA_INSPECTOR_WIDTH {
+            let [hexdump, inspector] =
+                Layout::horizontal([Constraint::Fill(1), Constraint::Length(DATA_INSPECTOR_WIDTH)])
+                    .spacing(1)
+                    .areas(hexdump);
+            if state.view.type_overlay.is_some() {
+                draw_type_overlay(state, f, inspector);
+            } else {
+                draw_data_inspector(state, f, inspector);
+            }
+            hexdump
+        } else {
+            hexdump
+        };
+        if state.hexdump_layout.width == RowWidth::Auto {
+            // less the scrollbar and the register references
+            let columns = (hexdump.width as usize).saturating_sub(1 + 20);
+            let width = fit_width(columns, state.hexdump_layout.group);
+            if width != state.hexdump_width {
+                state.relayout_hexdump(state.hexdump_layout, width);
+            }
+        }
+        let Some(memory) = &state.view.hexdump else {
+            return;
+        };
+        let mut pos = format!("0x{:02x?}", memory.start);
+        let layout = state.hexdump_layout.describe(state.hexdump_width);
+        if !layout.is_empty() {
+            pos.push_str(&format!("  {layout}"));
+        }
+        if let Some(search) = &state.view.hexdump_search {
+            let current = if search.matches.is_empty() { 0 } else { search.current + 1 };
+            pos.push_str(&format!("  match {current}/{}", search.matches.len()));
+        }
+        if !state.view.hexdump_changed.is_empty() {
+            pos.push_str(&format!("  {} changed", state.view.hexdump_changed.len()));
+        }
+        if state.hexdump_changes_only {
+            pos.push_str("  changes only");
+        }
 
         // account for the top border
         let take = (hexdump.height as usize).saturating_sub(1);
-        let rows = display_rows(data);
-        state.hexdump_scroll.set_max_scroll(rows.len().saturating_sub(take));
-        let skip = state.hexdump_scroll.scroll;
-        let visible = &rows[skip.min(rows.len())..(skip + take).min(rows.len())];
-        let lines = to_hexdump_str(state, r.0, data, visible);
+        state.hexdump_viewport_height = take as u16;
+        let editing = matches!(state.mode, Mode::OnlyHexdumpEdit | Mode::OnlyHexdumpSelect);
+        let geometry = state.hexdump_geometry();
+        let cursor_row = state.view.hexdump_cursor / geometry.width;
+        let keep = if editing {
+            Some(cursor_row)
+        } else {
+            state
+                .view
+                .hexdump_search
+                .as_ref()
+                .and_then(|s| s.current_offset())
+                .map(|o| o / geometry.width)
+        };
+        let lines = DisplayLines::new(memory, geometry, keep);
+        let lines = if state.hexdump_changes_only {
+            lines.changed(&state.view.hexdump_changed, geometry.width)
+        } else {
+            lines
+        };
+        state.view.hexdump_scroll.set_max_scroll(lines.len().saturating_sub(take));
+        if editing {
+            state.view.hexdump_scroll.keep_visible(lines.index_of(cursor_row), take);
+        }
+        let visible = lines.slice(state.view.hexdump_scroll.scroll, take);
+        if let Some(range) = rows_range(&visible, geometry.width) {
+            state.request_hexdump_pages(range);
+        }
+        let Some(memory) = &state.view.hexdump else {
+            return;
+        };
+        let lines = to_hexdump_str(state, memory, &visible);
         let paragraph = Paragraph::new(lines)
             .block(hexdump_block(state, Some(pos)))
             .style(Style::default().fg(Color::White));
@@ -202,34 +600,125 @@ pub fn draw_hexdump(state: &mut State, f: &mut Frame, hexdump: Rect, popup: Hexd
         f.render_stateful_widget(
             Scrollbar::new(ScrollbarOrientation::VerticalRight),
             hexdump,
-            &mut state.hexdump_scroll.state,
+            &mut state.view.hexdump_scroll.state,
         );
-        if popup != HexdumpPopup::None {
-            let (title, value) = match popup {
-                HexdumpPopup::Save => ("Save to", state.hexdump_popup.value().to_string()),
-                HexdumpPopup::Goto => ("Goto", state.hexdump_goto_popup.value().to_string()),
-                HexdumpPopup::None => unreachable!(),
-            };
-            let area = popup_area(hexdump, 60);
-            let txt_input = Paragraph::new(value).style(Style::default()).block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title(title.fg(YELLOW))
-                    .border_style(Style::default().fg(ORANGE)),
-            );
-            f.render_widget(Clear, area);
-            f.render_widget(txt_input, area);
-        }
+        draw_popup(state, f, hexdump, popup);
     } else {
         f.render_widget(Paragraph::new("").block(hexdump_block(state, None)), hexdump);
+        draw_popup(state, f, hexdump, popup);
+    }
+}
+
+fn draw_popup(state: &State, f: &mut Frame, area: Rect, popup: HexdumpPopup) {
+    let (title, value) = match popup {
+        HexdumpPopup::None => return,
+        HexdumpPopup::LoadConfirm => {
+            draw_load_preview(state, f, area);
+            return;
+        }
+        HexdumpPopup::Bookmarks => {
+            draw_bookmarks(state, f, area);
+            return;
+        }
+        HexdumpPopup::Save => {
+            let bytes = match state.hexdump_selection_range() {
+                Some(range) => format!("0x{:x} selected bytes", range.len()),
+                None => "hexdump".to_string(),
+            };
+            let title = format!("Save {bytes} as {} (Tab format) to", state.hexdump_export.name());
+            draw_input_popup(f, area, &title, state.hexdump_popup.value().to_string());
+            return;
+        }
+        HexdumpPopup::Goto => {
+            ("Goto address or label", state.hexdump_goto_popup.value().to_string())
+        }
+        HexdumpPopup::Search => (
+            "Search: de ?? be ef, \"str\", u\"str\", le32:0x1234, /regex/",
+            state.hexdump_search_popup.value().to_string(),
+        ),
+        HexdumpPopup::Load => (
+            "Load file: <path> [<addr>], at the cursor by default",
+            state.hexdump_load_popup.value().to_string(),
+        ),
+        HexdumpPopup::Label => {
+            ("Bookmark the cursor: <name> [<len>]", state.label_popup.value().to_string())
+        }
+        HexdumpPopup::Type => (
+            "Overlay type: <type>, <type>[<count>], <type>[] to the end, empty to remove",
+            state.hexdump_type_popup.value().to_string(),
+        ),
+    };
+    draw_input_popup(f, area, title, value);
+}
+
+/// Rows of the target bytes a file load overwrites, above the bytes of the file. The bytes that
+/// change are red
+fn load_preview_lines<'a>(load: &MemoryLoad) -> Vec<Line<'a>> {
+    let new = &load.bytes[..load.preview_len()];
+    let old = match &load.preview {
+        Page::Loaded(bytes) => Some(bytes.as_slice()),
+        Page::Pending => return vec![Line::from("reading...")],
+        Page::Unreadable => None,
+    };
+    let mut lines = vec![];
+    for (row, chunk) in new.chunks(HEXDUMP_WIDTH).enumerate() {
+        let start = row * HEXDUMP_WIDTH;
+        let addr = load.addr + start as u64;
+        let mut before = vec![Span::styled(format!("{addr:016x} - "), Style::new().fg(GRAY_FG))];
+        let mut after = vec![Span::styled(format!("{:16} + ", ""), Style::new().fg(GRAY_FG))];
+        for (i, byte) in chunk.iter().enumerate() {
+            let old = old.and_then(|old| old.get(start + i));
+            let (text, style) = match old {
+                Some(old) => (format!("{old:02x} "), Style::new().fg(color(*old))),
+                None => ("?? ".to_string(), Style::new().fg(GRAY_FG)),
+            };
+            before.push(Span::styled(text, style));
+            let style = if old == Some(byte) {
+                Style::new().fg(GRAY_FG)
+            } else {
+                Style::new().fg(Color::Black).bg(RED)
+            };
+            after.push(Span::styled(format!("{byte:02x}"), style));
+            after.push(Span::raw(" "));
+        }
+        lines.push(Line::from(before));
+        lines.push(Line::from(after));
     }
+    lines
+}
+
+fn draw_load_preview(state: &State, f: &mut Frame, area: Rect) {
+    let Some(load) = &state.view.memory_load else {
+        return;
+    };
+    let lines = load_preview_lines(load);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let vertical = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(72)]).flex(Flex::Center);
+    let [popup] = vertical.areas(area);
+    let [popup] = horizontal.areas(popup);
+    let more = if load.bytes.len() > load.preview_len() { ", first bytes shown" } else { "" };
+    let title =
+        format!("Load 0x{:x} bytes of {} at 0x{:x}{more}", load.bytes.len(), load.path, load.addr);
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.fg(YELLOW))
+            .title_bottom(Line::from("⏎ write  Esc cancel").right_aligned())
+            .border_style(Style::default().fg(ORANGE)),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory::{EAGER_PAGES, PAGE_LEN};
     use crate::{Args, PtrSize};
 
+    const GEOMETRY: Geometry = Geometry { width: HEXDUMP_WIDTH, collapse: true };
+
     #[test]
     fn test_color_null_byte() {
         assert_eq!(color(0x00), DARK_GRAY);
@@ -279,36 +768,52 @@ mod tests {
 
     #[test]
     fn test_to_hexdump_str_empty() {
-        let mut state = test_state();
+        let state = test_state();
         let buffer: Vec<u8> = vec![];
-        let lines = to_hexdump_str(&mut state, 0x1000, &buffer, &display_rows(&buffer));
+        let lines = to_hexdump_str(
+            &state,
+            &PagedMemory::from_bytes(0x1000, &buffer),
+            &display_rows(&buffer),
+        );
         assert_eq!(lines.len(), 0);
     }
 
     #[test]
     fn test_to_hexdump_str_single_line() {
-        let mut state = test_state();
+        let state = test_state();
         let buffer: Vec<u8> = vec![0x48, 0x65, 0x6c, 0x6c, 0x6f]; // "Hello"
-        let lines = to_hexdump_str(&mut state, 0x1000, &buffer, &display_rows(&buffer));
+        let lines = to_hexdump_str(
+            &state,
+            &PagedMemory::from_bytes(0x1000, &buffer),
+            &display_rows(&buffer),
+        );
         assert_eq!(lines.len(), 1);
     }
 
     #[test]
     fn test_to_hexdump_str_multiple_lines() {
-        let mut state = test_state();
+        let state = test_state();
         // Create 32 bytes which should span 2 lines (16 bytes per line)
         let buffer: Vec<u8> = (0..32).map(|i| i as u8).collect();
-        let lines = to_hexdump_str(&mut state, 0x1000, &buffer, &display_rows(&buffer));
+        let lines = to_hexdump_str(
+            &state,
+            &PagedMemory::from_bytes(0x1000, &buffer),
+            &display_rows(&buffer),
+        );
         assert_eq!(lines.len(), 2);
     }
 
     #[test]
     fn test_to_hexdump_str_collapses_zero_runs() {
-        let mut state = test_state();
+        let state = test_state();
         // 4 rows (64 bytes) of all-zero should collapse to the first row plus a
         // single `*` marker
         let buffer: Vec<u8> = vec![0x00; 64];
-        let lines = to_hexdump_str(&mut state, 0x1000, &buffer, &display_rows(&buffer));
+        let lines = to_hexdump_str(
+            &state,
+            &PagedMemory::from_bytes(0x1000, &buffer),
+            &display_rows(&buffer),
+        );
         assert_eq!(lines.len(), 2);
         assert_eq!(lines.last().unwrap().to_string(), "*");
 
@@ -317,18 +822,22 @@ mod tests {
         let mut buffer: Vec<u8> = vec![0x00; 48];
         buffer.extend_from_slice(&[0x41; 16]); // non-zero row
         buffer.extend_from_slice(&[0x00; 16]); // trailing zero row
-        let lines = to_hexdump_str(&mut state, 0x1000, &buffer, &display_rows(&buffer));
+        let lines = to_hexdump_str(
+            &state,
+            &PagedMemory::from_bytes(0x1000, &buffer),
+            &display_rows(&buffer),
+        );
         assert_eq!(lines.len(), 4);
     }
 
     #[test]
     fn test_to_hexdump_str_window() {
-        let mut state = test_state();
+        let state = test_state();
         // Create 64 bytes which should span 4 lines
         let buffer: Vec<u8> = (0..64).map(|i| i as u8).collect();
         // Skip the first line, take 2 lines
         let rows = display_rows(&buffer);
-        let lines = to_hexdump_str(&mut state, 0x1000, &buffer, &rows[1..3]);
+        let lines = to_hexdump_str(&state, &PagedMemory::from_bytes(0x1000, &buffer), &rows[1..3]);
         assert_eq!(lines.len(), 2);
         // addresses follow the rows, not the window position
         assert!(lines[0].to_string().starts_with("00000010: "));
@@ -337,7 +846,7 @@ mod tests {
 
     #[test]
     fn test_display_rows_scrolls_one_line_per_step() {
-        let mut state = test_state();
+        let state = test_state();
         // 2 data rows, a 10-row zero run, 2 data rows: 6 display lines
         let mut buffer: Vec<u8> = (0..32).map(|i| i as u8 + 1).collect();
         buffer.extend(vec![0u8; 160]);
@@ -356,7 +865,7 @@ mod tests {
         );
 
         // scrolling by one from inside the zero run moves a full visual line
-        let first = to_hexdump_str(&mut state, 0x1000, &buffer, &rows[3..5]);
+        let first = to_hexdump_str(&state, &PagedMemory::from_bytes(0x1000, &buffer), &rows[3..5]);
         assert_eq!(first[0].to_string(), "*");
         assert!(first[1].to_string().starts_with("000000c0: "));
     }
@@ -368,28 +877,166 @@ mod tests {
         buffer.extend(vec![0u8; 160]);
         buffer.extend((0..32).map(|i| i as u8 + 1));
 
-        assert_eq!(display_index_of_row(&buffer, 0), 0);
-        assert_eq!(display_index_of_row(&buffer, 2), 2); // first row of the run
-        assert_eq!(display_index_of_row(&buffer, 5), 3); // inside the run: `*`
-        assert_eq!(display_index_of_row(&buffer, 11), 3);
-        assert_eq!(display_index_of_row(&buffer, 12), 4);
-        assert_eq!(display_index_of_row(&buffer, 13), 5);
+        let buffer = PagedMemory::from_bytes(0, &buffer);
+        assert_eq!(display_index_of_row(&buffer, GEOMETRY, 0), 0);
+        assert_eq!(display_index_of_row(&buffer, GEOMETRY, 2), 2); // first row of the run
+        assert_eq!(display_index_of_row(&buffer, GEOMETRY, 5), 3); // inside the run: `*`
+        assert_eq!(display_index_of_row(&buffer, GEOMETRY, 11), 3);
+        assert_eq!(display_index_of_row(&buffer, GEOMETRY, 12), 4);
+        assert_eq!(display_index_of_row(&buffer, GEOMETRY, 13), 5);
     }
 
     #[test]
-    fn test_popup_area_dimensions() {
-        let area = Rect::new(0, 0, 100, 100);
-        let popup = popup_area(area, 60);
-        assert_eq!(popup.width, 60);
-        assert_eq!(popup.height, 3);
+    fn test_row_at_display_index() {
+        // 2 data rows, a 10-row zero run (rows 2..12), 2 data rows
+        let mut buffer: Vec<u8> = (0..32).map(|i| i as u8 + 1).collect();
+        buffer.extend(vec![0u8; 160]);
+        buffer.extend((0..32).map(|i| i as u8 + 1));
+
+        let buffer = PagedMemory::from_bytes(0, &buffer);
+        for row in [0, 1, 2, 3, 12, 13] {
+            assert_eq!(
+                row_at_display_index(
+                    &buffer,
+                    GEOMETRY,
+                    display_index_of_row(&buffer, GEOMETRY, row)
+                ),
+                row
+            );
+        }
+    }
+
+    #[test]
+    fn test_changed_rows() {
+        let mut buffer = vec![0x00; 64];
+        buffer[40] = 0x41;
+        let buffer = PagedMemory::from_bytes(0, &buffer);
+        let changed = BTreeSet::from([17, 40]);
+        // the row of 17 changed to zero, and is shown although zero rows collapse
+        let lines = DisplayLines::new(&buffer, GEOMETRY, None).changed(&changed, HEXDUMP_WIDTH);
+        assert_eq!(lines.slice(0, 4), vec![DisplayRow::Row(1), DisplayRow::Row(2)]);
+        let changed = BTreeSet::from([0, 40, 41]);
+        assert_eq!(
+            changed_rows(&changed, HEXDUMP_WIDTH),
+            vec![DisplayRow::Row(0), DisplayRow::Row(2)]
+        );
     }
 
     #[test]
-    fn test_popup_area_different_sizes() {
-        let area = Rect::new(0, 0, 200, 50);
-        let popup = popup_area(area, 80);
-        assert_eq!(popup.width, 160); // 80% of 200
-        assert_eq!(popup.height, 3);
+    fn test_paged_lines() {
+        let state = test_state();
+        let mut memory = PagedMemory::new(0x1000, PAGE_LEN * EAGER_PAGES * 2);
+        assert!(!memory.is_eager());
+        let lines = DisplayLines::new(&memory, GEOMETRY, None);
+        assert_eq!(lines.len(), memory.len / HEXDUMP_WIDTH);
+        assert_eq!(lines.index_of(100), 100);
+        assert_eq!(lines.slice(2, 2), vec![DisplayRow::Row(2), DisplayRow::Row(3)]);
+        assert_eq!(rows_range(&lines.slice(2, 2), HEXDUMP_WIDTH), Some(0x20..0x40));
+
+        // zero rows of a paged range are not collapsed
+        memory.insert(0, vec![0; PAGE_LEN]);
+        memory.set_unreadable(1);
+        let rows = [
+            DisplayRow::Row(0),
+            DisplayRow::Row(PAGE_LEN / HEXDUMP_WIDTH),
+            DisplayRow::Row(PAGE_LEN * 2 / HEXDUMP_WIDTH),
+        ];
+        let lines = to_hexdump_str(&state, &memory, &rows);
+        assert!(lines[0].to_string().starts_with("00000000: 00 00"));
+        assert!(lines[1].to_string().starts_with("00001000: ?? ?? "), "{}", lines[1]);
+        assert!(lines[1].to_string().contains("| ????"));
+        assert!(lines[2].to_string().starts_with("00002000:    "));
+
+        let changed = BTreeSet::from([0x21, 0x25, 0x1000]);
+        let lines = DisplayLines::new(&memory, GEOMETRY, None).changed(&changed, HEXDUMP_WIDTH);
+        assert_eq!(lines.slice(0, 10), vec![DisplayRow::Row(2), DisplayRow::Row(0x100)]);
+    }
+
+    #[test]
+    fn test_edit_cursor_row_not_collapsed() {
+        let mut state = test_state();
+        let buffer = vec![0u8; 64];
+        state.mode = Mode::OnlyHexdumpEdit;
+        state.view.hexdump_cursor = 0x21;
+        state.view.hexdump_edits.insert(0x22, 0x41);
+
+        let rows = display_rows_keeping(&buffer, HEXDUMP_WIDTH, Some(2));
+        assert_eq!(
+            rows,
+            vec![DisplayRow::Row(0), DisplayRow::Collapsed, DisplayRow::Row(2), DisplayRow::Row(3)]
+        );
+        let lines = to_hexdump_str(&state, &PagedMemory::from_bytes(0x1000, &buffer), &rows[2..3]);
+        let line = lines[0].to_string();
+        assert!(line.starts_with("00000020: 00 00 41 00"), "{line}");
+        assert!(line.contains("| ..A."));
+        let cursor = lines[0].spans.iter().find(|s| s.content == "00").unwrap();
+        assert!(!cursor.style.add_modifier.contains(ratatui::style::Modifier::REVERSED));
+        let cursor = &lines[0].spans[2 + 2];
+        assert!(cursor.style.add_modifier.contains(ratatui::style::Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_text_encodings() {
+        assert_eq!(CP437.chars().count(), 256);
+        assert_eq!(TextEncoding::Cp437.char(0x01), '☺');
+        assert_eq!(TextEncoding::Cp437.char(b'A'), 'A');
+        assert_eq!(TextEncoding::Cp437.char(0xb0), '░');
+        assert_eq!(TextEncoding::Cp437.char(0xfe), '■');
+        assert_eq!(TextEncoding::Ascii.char(0xb0), '.');
+        assert_eq!(TextEncoding::Dots.char(b'A'), '.');
+    }
+
+    #[test]
+    fn test_grouped_words() {
+        let mut state = test_state();
+        state.hexdump_layout.group = 4;
+        state.hexdump_width = 8;
+        let buffer: Vec<u8> = (1..=10).collect();
+        let memory = PagedMemory::from_bytes(0x1000, &buffer);
+        let rows = [DisplayRow::Row(0), DisplayRow::Row(1)];
+        let lines = to_hexdump_str(&state, &memory, &rows);
+        assert!(lines[0].to_string().starts_with("00000000: 04030201 08070605 | "));
+        // the missing high bytes of the last word are blank
+        assert!(lines[1].to_string().starts_with("00000008:     0a09          | "));
+
+        state.view.endian = Some(Endian::Big);
+        let lines = to_hexdump_str(&state, &memory, &rows);
+        assert!(lines[0].to_string().starts_with("00000000: 01020304 05060708 | "));
+    }
+
+    #[test]
+    fn test_layout_geometry() {
+        // the zero rows are collapsed by 16, 32 bytes rows are not all zero
+        let mut buffer = vec![0u8; 64];
+        buffer[0x30] = 1;
+        let memory = PagedMemory::from_bytes(0, &buffer);
+        assert_eq!(DisplayLines::new(&memory, GEOMETRY, None).len(), 3);
+        let wide = Geometry { width: 32, collapse: true };
+        assert_eq!(DisplayLines::new(&memory, wide, None).len(), 2);
+        let all = Geometry { width: 8, collapse: false };
+        assert_eq!(DisplayLines::new(&memory, all, None).len(), 8);
+
+        assert_eq!(fit_width(200, 1), 32);
+        assert_eq!(fit_width(90, 1), 16);
+        assert_eq!(fit_width(90, 8), 16);
+        assert_eq!(fit_width(20, 1), 8);
+        let layout = HexdumpLayout { width: RowWidth::Auto, group: 4, ..HexdumpLayout::default() };
+        assert_eq!(layout.describe(32), "auto 32 u32");
+        assert_eq!(HexdumpLayout::default().describe(16), "");
+    }
+
+    #[test]
+    fn test_load_preview_lines() {
+        let mut load = MemoryLoad::new("a".to_string(), 0x1000, vec![1, 2, 3]);
+        assert_eq!(load_preview_lines(&load)[0].to_string(), "reading...");
+        load.preview = Page::Loaded(vec![1, 0, 3]);
+        let lines = load_preview_lines(&load);
+        assert_eq!(lines[0].to_string(), "0000000000001000 - 01 00 03 ");
+        assert_eq!(lines[1].to_string(), "                 + 01 02 03 ");
+        assert_eq!(lines[1].spans[3].style.bg, Some(RED));
+        assert_eq!(lines[1].spans[1].style.bg, None);
+        load.preview = Page::Unreadable;
+        assert_eq!(load_preview_lines(&load)[0].to_string(), "0000000000001000 - ?? ?? ?? ");
     }
 
     #[test]
@@ -397,14 +1044,15 @@ mod tests {
         // 10 non-zero rows then 10 zero rows: 12 display lines. With a 4-line
         // viewport, max scroll is 8, and rendering from there fills the pane
         // down to the end of the buffer
-        let mut state = test_state();
+        let state = test_state();
         let mut buffer: Vec<u8> = (0..160).map(|i| (i % 255) as u8 + 1).collect();
         buffer.extend(vec![0u8; 160]);
         let rows = display_rows(&buffer);
         assert_eq!(rows.len(), 12);
 
         let max_scroll = rows.len().saturating_sub(4);
-        let lines = to_hexdump_str(&mut state, 0x1000, &buffer, &rows[max_scroll..]);
+        let lines =
+            to_hexdump_str(&state, &PagedMemory::from_bytes(0x1000, &buffer), &rows[max_scroll..]);
         assert_eq!(lines.len(), 4);
         assert_eq!(lines.last().unwrap().to_string(), "*");
     }
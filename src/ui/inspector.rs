@@ -0,0 +1,141 @@
+use ratatui::layout::{Constraint, Flex, Layout, Position, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Scrollbar, ScrollbarOrientation, Table};
+use ratatui::{Frame, style::Style};
+
+use super::locals::{TREE_WIDTHS, tree_header, tree_rows};
+use super::{GRAY_FG, GREEN, ORANGE, PURPLE, RED, YELLOW, add_deref_to_span};
+use crate::{Mode, PtrSize, State};
+
+fn inspector_area(area: Rect) -> Rect {
+    let vertical = Layout::vertical([Constraint::Percentage(70)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(80)]).flex(Flex::Center);
+    let [area] = vertical.areas(area);
+    let [area] = horizontal.areas(area);
+    area
+}
+
+/// Expression inspector popup, drawn over the current pane
+pub fn draw_inspector(state: &mut State, f: &mut Frame) {
+    let area = inspector_area(f.area());
+    let editing = state.mode == Mode::InspectorInput;
+    let hints = if editing {
+        "⏎ inspect  ↑/↓ history  Esc back"
+    } else {
+        "⏎ expand  H hexdump  t telescope  i edit  Esc close"
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::new().fg(ORANGE))
+        .title(Span::styled("Inspector", Style::new().fg(YELLOW).bold()))
+        .title_bottom(Line::from(Span::styled(hints, Style::new().fg(GRAY_FG))).right_aligned());
+    let inner = block.inner(area);
+    f.render_widget(Clear, area);
+    f.render_widget(block, area);
+
+    let telescope_height = u16::from(state.view.inspector_telescope.is_some());
+    let vertical = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Fill(1),
+        Constraint::Length(telescope_height),
+    ]);
+    let [input_area, tree_area, telescope_area] = vertical.areas(inner);
+
+    let prompt = "p> ";
+    let input = Line::from(vec![
+        Span::styled(prompt, Style::new().fg(GREEN)),
+        Span::raw(state.inspector_input.value().to_string()),
+    ]);
+    f.render_widget(Paragraph::new(input), input_area);
+    if editing {
+        let x = input_area.x + (prompt.len() + state.inspector_input.visual_cursor()) as u16;
+        f.set_cursor_position(Position::new(x.min(input_area.right()), input_area.y));
+    }
+
+    if let Some(error) = &state.inspector_error {
+        let error = Paragraph::new(Span::styled(error.clone(), Style::new().fg(RED)));
+        f.render_widget(error, tree_area);
+    } else {
+        let len = state.view.inspector.len();
+        state.inspector_selected = state.inspector_selected.min(len.saturating_sub(1));
+        // Account for the pinned header row
+        let max = tree_area.height.saturating_sub(1) as usize;
+        state.inspector_scroll.set_max_scroll(len.saturating_sub(max));
+        state.inspector_scroll.keep_visible(state.inspector_selected, max);
+        let skip = state.inspector_scroll.scroll;
+        // no highlight while typing a new expression
+        let selected = if editing { usize::MAX } else { state.inspector_selected };
+        let rows = tree_rows(&state.view.inspector, selected, skip, max);
+        let table = Table::new(rows, TREE_WIDTHS).header(tree_header());
+        f.render_widget(table, tree_area);
+        f.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            tree_area,
+            &mut state.inspector_scroll.state,
+        );
+    }
+
+    if let Some((addr, deref)) = state.view.inspector_telescope.clone() {
+        let width: usize = if state.view.ptr_size == PtrSize::Size32 { 11 } else { 19 };
+        let filepath = state.view.filepath.clone().unwrap_or_default();
+        let filepath = filepath.to_string_lossy();
+        let mut spans = vec![Span::styled(format!("0x{addr:02x} "), Style::new().fg(PURPLE))];
+        let mut longest_cells = 0;
+        add_deref_to_span(&deref, &mut spans, state, &filepath, &mut longest_cells, width);
+        f.render_widget(Paragraph::new(Line::from(spans)), telescope_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deref::Deref;
+    use crate::mi::VarObj;
+    use crate::{Args, Local};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_draw_inspector() {
+        let mut state = create_test_state();
+        state.mode = Mode::Inspector;
+        state.inspector_input = "node".into();
+        state.view.inspector = vec![Local {
+            var: VarObj {
+                name: "inspect0".to_string(),
+                exp: "node".to_string(),
+                type_: Some("struct node *".to_string()),
+                value: Some("0x4052a0".to_string()),
+                numchild: 2,
+            },
+            ..Local::default()
+        }];
+        let mut deref = Deref::new();
+        deref.try_push(0x2a);
+        state.view.inspector_telescope = Some((0x4052a0, deref));
+
+        let backend = TestBackend::new(100, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal.draw(|f| draw_inspector(&mut state, f)).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let area = inspector_area(Rect::new(0, 0, 100, 20));
+        let row = |y: u16| {
+            (area.x + 1..area.right() - 1).map(|x| buffer[(x, y)].symbol()).collect::<String>()
+        };
+        assert!(row(area.y + 1).starts_with("p> node"));
+        assert!(row(area.y + 4).starts_with("▸ node"));
+        assert!(row(area.bottom() - 2).starts_with("0x4052a0 → 0x2a"));
+    }
+}
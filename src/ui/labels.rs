@@ -0,0 +1,78 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use super::{GRAY_FG, LABEL_COLOR, ORANGE, YELLOW};
+use crate::State;
+use crate::labels::Labels;
+
+/// Rows of the bookmarks list, by address, with the `selected` one reversed
+fn bookmark_lines<'a>(labels: &Labels, selected: usize) -> Vec<Line<'a>> {
+    if labels.is_empty() {
+        return vec![Line::from(Span::styled(
+            "no labels, m to bookmark the cursor or `label <addr> [<len>] <name>`",
+            Style::new().fg(GRAY_FG),
+        ))];
+    }
+    labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            let len = if label.len == 0 { String::new() } else { format!("0x{:x}", label.len) };
+            let mut line = Line::from(vec![
+                Span::raw(format!("0x{:016x} ", label.addr)),
+                Span::styled(format!("{len:>10} "), Style::new().fg(GRAY_FG)),
+                Span::styled(label.name.clone(), Style::new().fg(LABEL_COLOR)),
+            ]);
+            if index == selected {
+                line = line.reversed();
+            }
+            line
+        })
+        .collect()
+}
+
+/// Every label over the hexdump, to jump to one
+pub fn draw_bookmarks(state: &State, f: &mut Frame, area: Rect) {
+    let lines = bookmark_lines(&state.labels, state.bookmarks_selected);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let vertical = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(72)]).flex(Flex::Center);
+    let [popup] = vertical.areas(area);
+    let [popup] = horizontal.areas(popup);
+    // keep the selected row in view, inside the borders
+    let rows = height.saturating_sub(2) as usize;
+    let scroll = (state.bookmarks_selected + 1).saturating_sub(rows);
+    let paragraph = Paragraph::new(lines).scroll((scroll as u16, 0)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Bookmarks ({})", state.labels.len()).fg(YELLOW))
+            .title_bottom(Line::from("⏎ goto  d delete  Esc close").right_aligned())
+            .border_style(Style::default().fg(ORANGE)),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::labels::parse_label;
+    use ratatui::style::Modifier;
+
+    #[test]
+    fn test_bookmark_lines() {
+        let mut labels = Labels::default();
+        assert!(bookmark_lines(&labels, 0)[0].to_string().starts_with("no labels"));
+        labels.insert(parse_label("0x20001000 0x100 rx_ring").unwrap());
+        labels.insert(parse_label("0x1000 reset").unwrap());
+        let lines = bookmark_lines(&labels, 1);
+        assert_eq!(lines[0].to_string(), "0x0000000000001000            reset");
+        assert_eq!(lines[1].to_string(), "0x0000000020001000      0x100 rx_ring");
+        assert!(lines[1].style.add_modifier.contains(Modifier::REVERSED));
+    }
+}
@@ -0,0 +1,143 @@
+use ratatui::layout::Constraint;
+use ratatui::widgets::{Cell, Scrollbar, ScrollbarOrientation, Table};
+use ratatui::{Frame, layout::Rect, style::Style, widgets::Row};
+
+use super::draw_input_popup;
+use super::{BLUE, GRAY_FG, ORANGE, PURPLE, RED, effective_mode, pane_block};
+use crate::{Local, Mode, State};
+
+/// Indented name with the expansion marker, array indices shown as "[i]"
+fn tree_name(local: &Local) -> String {
+    let marker = match (local.var.numchild, local.expanded) {
+        (0, _) => " ",
+        (_, true) => "▾",
+        (_, false) => "▸",
+    };
+    let exp = &local.var.exp;
+    let exp = if exp.parse::<usize>().is_ok() { format!("[{exp}]") } else { exp.clone() };
+    format!("{:indent$}{marker} {exp}", "", indent = local.depth * 2)
+}
+
+/// Header of a variable object tree table
+pub fn tree_header() -> Row<'static> {
+    Row::new(["Name", "Type", "Value"]).style(Style::new().fg(BLUE).bold())
+}
+
+pub const TREE_WIDTHS: [Constraint; 3] =
+    [Constraint::Fill(1), Constraint::Fill(1), Constraint::Fill(2)];
+
+/// Rows of a variable object tree table, `max` rows from `skip`
+pub fn tree_rows(tree: &[Local], selected: usize, skip: usize, max: usize) -> Vec<Row<'static>> {
+    tree.iter()
+        .enumerate()
+        .skip(skip)
+        .take(max)
+        .map(|(index, local)| {
+            let name = Cell::from(tree_name(local));
+            let name = if local.arg { name.style(Style::new().fg(PURPLE)) } else { name };
+            let value = match &local.var.value {
+                Some(value) => Cell::from(value.clone()),
+                None => Cell::from("<optimized out>").style(Style::new().fg(GRAY_FG)),
+            };
+            let value = if local.changed { value.style(Style::new().fg(RED)) } else { value };
+            let row = Row::new([
+                name,
+                Cell::from(local.var.type_.clone().unwrap_or_default())
+                    .style(Style::new().fg(GRAY_FG)),
+                value,
+            ]);
+            if index == selected { row.style(Style::new().fg(ORANGE).bold()) } else { row }
+        })
+        .collect()
+}
+
+pub fn draw_locals(state: &mut State, f: &mut Frame, area: Rect, popup: bool) {
+    let len = state.view.locals.len();
+    state.locals_selected = state.locals_selected.min(len.saturating_sub(1));
+    // Account for top border and pinned header row
+    let max = area.height.saturating_sub(2) as usize;
+    state.locals_scroll.set_max_scroll(len.saturating_sub(max));
+    state.locals_scroll.keep_visible(state.locals_selected, max);
+    let skip = state.locals_scroll.scroll;
+
+    let rows = tree_rows(&state.view.locals, state.locals_selected, skip, max);
+    let active = !state.expressions_focus
+        && matches!(effective_mode(state), Mode::OnlyLocals | Mode::OnlyLocalsPopup);
+    let context = state.view.bt.get(state.bt_selected).and_then(|b| b.frame.func.clone());
+    let block = pane_block("Locals", context, "⏎ expand  e edit", active);
+    let table = Table::new(rows, TREE_WIDTHS).header(tree_header()).block(block);
+    f.render_widget(table, area);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area,
+        &mut state.locals_scroll.state,
+    );
+
+    if popup {
+        let exp = state.view.locals.get(state.locals_selected).map(|l| l.var.exp.clone());
+        let title = format!("Set {}", exp.unwrap_or_default());
+        draw_input_popup(f, area, &title, state.locals_popup.value().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mi::VarObj;
+    use crate::{Args, PtrSize};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    fn local(name: &str, exp: &str, value: &str, numchild: usize, depth: usize) -> Local {
+        Local {
+            var: VarObj {
+                name: name.to_string(),
+                exp: exp.to_string(),
+                type_: Some("int".to_string()),
+                value: Some(value.to_string()),
+                numchild,
+            },
+            depth,
+            ..Local::default()
+        }
+    }
+
+    #[test]
+    fn test_draw_locals_tree() {
+        let mut state = create_test_state();
+        state.view.locals = vec![
+            local("local0", "arr", "[2]", 2, 0),
+            local("local0.0", "0", "7", 0, 1),
+            local("local0.1", "1", "8", 0, 1),
+        ];
+        state.view.locals[0].expanded = true;
+        state.view.locals[2].changed = true;
+
+        let backend = TestBackend::new(60, 6);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_locals(&mut state, f, area, false);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(2).starts_with("▾ arr"));
+        assert!(row(3).starts_with("    [0]"));
+        let value_x = row(4).find('8').unwrap() as u16;
+        assert_eq!(buffer[(value_x, 4)].fg, RED);
+    }
+}
@@ -1,8 +1,9 @@
 use ratatui::layout::Constraint;
-use ratatui::widgets::{Scrollbar, ScrollbarOrientation, Table};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Cell, Scrollbar, ScrollbarOrientation, Table};
 use ratatui::{Frame, layout::Rect, style::Style, widgets::Row};
 
-use super::{BLUE, ORANGE, effective_mode, pane_block};
+use super::{BLUE, LABEL_COLOR, ORANGE, effective_mode, pane_block};
 
 use crate::{Mode, State};
 
@@ -10,16 +11,29 @@ pub fn draw_mapping(state: &mut State, f: &mut Frame, mapping_rect: Rect) {
     let header = Row::new(["Start Address", "End Address", "Size", "Offset", "Perms", "Path"])
         .style(Style::new().fg(BLUE).bold());
     let mut rows = vec![];
-    let memory_map = state.memory_map.clone();
+    let memory_map = state.view.memory_map.clone();
     if let Some(memory_map) = memory_map.as_ref() {
         for (index, m) in memory_map.iter().enumerate() {
+            // labels inside the mapping, after its path
+            let mut path = vec![Span::raw(m.path.clone().unwrap_or(String::new()))];
+            let labels: Vec<&str> = state
+                .labels
+                .starting_in(m.start_address..m.end_address)
+                .map(|label| label.name.as_str())
+                .collect();
+            if !labels.is_empty() {
+                path.push(Span::styled(
+                    format!(" <{}>", labels.join(", ")),
+                    Style::new().fg(LABEL_COLOR),
+                ));
+            }
             let mut row = Row::new([
-                format!("0x{:08x}", m.start_address),
-                format!("0x{:08x}", m.end_address),
-                format!("0x{:08x}", m.size),
-                format!("0x{:08x}", m.offset),
-                m.permissions.clone().unwrap_or(String::new()),
-                m.path.clone().unwrap_or(String::new()),
+                Cell::from(format!("0x{:08x}", m.start_address)),
+                Cell::from(format!("0x{:08x}", m.end_address)),
+                Cell::from(format!("0x{:08x}", m.size)),
+                Cell::from(format!("0x{:08x}", m.offset)),
+                Cell::from(m.permissions.clone().unwrap_or(String::new())),
+                Cell::from(Line::from(path)),
             ]);
             // Highlight the selected row
             if index == state.memory_map_selected {
@@ -107,7 +121,7 @@ mod tests {
     #[test]
     fn test_draw_mapping_with_data() {
         let mut state = create_test_state();
-        state.memory_map = Some(vec![create_test_mapping()]);
+        state.view.memory_map = Some(vec![create_test_mapping()]);
 
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -126,7 +140,7 @@ mod tests {
     #[test]
     fn test_draw_mapping_multiple_entries() {
         let mut state = create_test_state();
-        state.memory_map = Some(vec![
+        state.view.memory_map = Some(vec![
             create_test_mapping(),
             MemoryMapping {
                 start_address: 0x500000,
@@ -163,7 +177,7 @@ mod tests {
     #[test]
     fn test_draw_mapping_with_selection() {
         let mut state = create_test_state();
-        state.memory_map = Some(vec![create_test_mapping(), create_test_mapping()]);
+        state.view.memory_map = Some(vec![create_test_mapping(), create_test_mapping()]);
         state.memory_map_selected = 1;
 
         let backend = TestBackend::new(80, 24);
@@ -194,7 +208,7 @@ mod tests {
                 path: Some(format!("/path/to/lib{i}.so")),
             })
             .collect();
-        state.memory_map = Some(mappings);
+        state.view.memory_map = Some(mappings);
         state.memory_map_scroll.scroll = 10;
 
         let backend = TestBackend::new(80, 24);
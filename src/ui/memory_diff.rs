@@ -0,0 +1,151 @@
+use std::ops::Range;
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Paragraph, Scrollbar, ScrollbarOrientation},
+};
+
+use super::hexdump::{HEXDUMP_WIDTH, TextEncoding, color, row_columns};
+use super::{DARK_GRAY, GRAY_FG, RED, YELLOW, pane_block};
+use crate::State;
+use crate::diff::MemoryDiff;
+use crate::memory::PagedMemory;
+
+/// Hex and text columns of the `width` bytes of `memory` from `start`. Differing bytes are on red,
+/// those of the selected run on yellow
+fn side_spans<'a>(
+    diff: &MemoryDiff,
+    memory: &PagedMemory,
+    start: usize,
+    width: usize,
+    encoding: TextEncoding,
+) -> Vec<Span<'a>> {
+    let mut hex = vec![];
+    let mut text = vec![Span::raw("| ")];
+    for offset in start..start + width {
+        let style = |default: Style| match diff.at(offset) {
+            Some(current) => Style::new().fg(Color::Black).bg(if current { YELLOW } else { RED }),
+            None => default,
+        };
+        if offset >= memory.len {
+            hex.push(Span::raw("   "));
+            text.push(Span::raw(" "));
+        } else if let Some(byte) = memory.get(offset) {
+            let style = style(Style::new().fg(color(byte)));
+            hex.push(Span::styled(format!("{byte:02x}"), style));
+            hex.push(Span::raw(" "));
+            text.push(Span::styled(encoding.char(byte).to_string(), style));
+        } else if memory.is_unreadable(offset) {
+            let style = style(Style::new().fg(GRAY_FG));
+            hex.push(Span::styled("??", style));
+            hex.push(Span::raw(" "));
+            text.push(Span::styled("?", style));
+        } else {
+            hex.push(Span::raw("   "));
+            text.push(Span::raw(" "));
+        }
+    }
+    hex.extend(text);
+    hex
+}
+
+/// Lines of `rows` of the diff, the offset then both sides
+fn diff_lines<'a>(
+    diff: &MemoryDiff,
+    left: &PagedMemory,
+    right: &PagedMemory,
+    rows: Range<usize>,
+    encoding: TextEncoding,
+) -> Vec<Line<'a>> {
+    let width = diff.width;
+    rows.map(|row| {
+        let start = row * width;
+        let mut spans = vec![Span::raw(format!("{start:08x}: "))];
+        spans.extend(side_spans(diff, left, start, width, encoding));
+        spans.push(Span::styled(" │ ", Style::new().fg(DARK_GRAY)));
+        spans.extend(side_spans(diff, right, start, width, encoding));
+        Line::from(spans)
+    })
+    .collect()
+}
+
+pub fn draw_memory_diff(state: &mut State, f: &mut Frame, area: Rect) {
+    let hints = "n/N difference  Esc close";
+    let Some(diff) = &mut state.memory_diff else {
+        f.render_widget(Paragraph::new("").block(pane_block("Diff", None, hints, true)), area);
+        return;
+    };
+    let left = state.snapshots.get(&diff.left);
+    let right = match &diff.right {
+        Some(name) => state.snapshots.get(name),
+        None => diff.live.as_ref(),
+    };
+    let (Some(left), Some(right)) = (left, right) else {
+        return;
+    };
+
+    // both sides of 16 bytes, else 8
+    let wide = 2 * row_columns(HEXDUMP_WIDTH, 1) + 3;
+    diff.width = if area.width as usize > wide { HEXDUMP_WIDTH } else { HEXDUMP_WIDTH / 2 };
+    // account for the top border
+    let take = (area.height as usize).saturating_sub(1);
+    let rows = left.len.max(right.len).div_ceil(diff.width);
+    diff.scroll.set_max_scroll(rows.saturating_sub(take));
+    let top = diff.scroll.scroll;
+
+    let mut pos =
+        format!("{} 0x{:x} ↔ {} 0x{:x}", diff.left, left.start, diff.right_name(), right.start);
+    match &diff.differences {
+        Some(differences) if differences.is_empty() => pos.push_str("  identical"),
+        Some(differences) => {
+            pos.push_str(&format!("  difference {}/{}", diff.current + 1, differences.len()));
+        }
+        None => pos.push_str("  reading"),
+    }
+    if left.len != right.len {
+        pos.push_str(&format!("  0x{:x} vs 0x{:x} bytes", left.len, right.len));
+    }
+
+    let lines =
+        diff_lines(diff, left, right, top..(top + take).min(rows), state.hexdump_layout.encoding);
+    let paragraph = Paragraph::new(lines)
+        .block(pane_block("Diff", Some(pos), hints, true))
+        .style(Style::default().fg(Color::White));
+    f.render_widget(paragraph, area);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area,
+        &mut diff.scroll.state,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::differences;
+
+    #[test]
+    fn test_diff_lines() {
+        let mut a: Vec<u8> = (0x41..0x61).collect();
+        let left = PagedMemory::from_bytes(0x1000, &a);
+        a[0x11] = 0;
+        a.truncate(0x18);
+        let right = PagedMemory::from_bytes(0x2000, &a);
+        let mut diff = MemoryDiff::new("a".to_string(), Some("b".to_string()), None);
+        diff.differences = Some(differences(&left, &right));
+        assert_eq!(diff.differences.as_ref().map(|d| d.len()), Some(1));
+        assert_eq!(diff.at(0x11), Some(true));
+
+        let lines = diff_lines(&diff, &left, &right, 0..2, TextEncoding::Ascii);
+        let line = lines[1].to_string();
+        assert!(line.starts_with("00000010: 51 52 53"), "{line}");
+        assert!(line.contains("| QRSTUVWXYZ[\\]^_` │ 51 00 53"), "{line}");
+        // the right side ends at 0x18
+        assert!(line.ends_with("| Q.STUVWX        "), "{line}");
+        let changed = lines[1].spans.iter().find(|s| s.content == "00").unwrap();
+        assert_eq!(changed.style.bg, Some(YELLOW));
+    }
+}
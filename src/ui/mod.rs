@@ -1,40 +1,55 @@
 use asm::draw_asm;
-use bt::draw_bt;
+use bt::{draw_backtrace, draw_bt};
+use expressions::draw_expressions;
 use hexdump::{HexdumpPopup, draw_hexdump};
 use input::draw_input;
+use locals::draw_locals;
 use mapping::draw_mapping;
+use memory_diff::draw_memory_diff;
 use output::draw_output;
 use ratatui::Frame;
-use ratatui::layout::Constraint::{Fill, Length, Min};
-use ratatui::layout::Layout;
+use ratatui::layout::Constraint::{Fill, Length, Min, Percentage};
+use ratatui::layout::{Flex, Layout, Rect};
 use ratatui::prelude::Stylize;
 use ratatui::style::Color;
 use ratatui::style::Style;
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
 use registers::draw_registers;
 use source::draw_source;
 use stack::draw_stack;
 use status_bar::draw_status_bar;
 use symbols::draw_symbols;
+use threads::draw_threads;
 use title::draw_title_area;
+use watchpoints::draw_watchpoints;
 
 use crate::deref::Deref;
+use crate::mi::Breakpoint;
 use crate::{Mode, State};
 
 pub mod asm;
 pub mod bt;
+pub mod data_inspector;
+pub mod expressions;
 pub mod help;
 pub mod hexdump;
 pub mod input;
+pub mod inspector;
+pub mod labels;
+pub mod locals;
 pub mod mapping;
+pub mod memory_diff;
 pub mod output;
 pub mod registers;
 pub mod source;
 pub mod stack;
 pub mod status_bar;
 pub mod symbols;
+pub mod threads;
 pub mod title;
+pub mod type_overlay;
+pub mod watchpoints;
 
 // Ayu bell colors
 const BLUE: Color = Color::Rgb(0x59, 0xc2, 0xff);
@@ -52,22 +67,40 @@ const STACK_COLOR: Color = PURPLE;
 const TEXT_COLOR: Color = RED;
 const STRING_COLOR: Color = YELLOW;
 const ASM_COLOR: Color = ORANGE;
+const LABEL_COLOR: Color = Color::Rgb(0x95, 0xe6, 0xcb);
 
 const SAVED_OUTPUT: usize = 10;
 
-/// Amount of stack addresses we save/display
-pub const SAVED_STACK: u16 = 14;
-
 /// Mode used for pane display decisions, falling back through overlay modes
 /// to the pane that is displayed beneath them
 pub fn effective_mode(state: &State) -> Mode {
-    if matches!(state.mode, Mode::QuitConfirmation | Mode::Help) {
+    if matches!(
+        state.mode,
+        Mode::QuitConfirmation | Mode::Help | Mode::Inspector | Mode::InspectorInput
+    ) {
         state.previous_mode
     } else {
         state.mode
     }
 }
 
+/// Gutter marker for a breakpoint: filled when enabled, diamond when conditional
+pub fn breakpoint_marker(bkpt: Option<&Breakpoint>) -> Span<'static> {
+    match bkpt {
+        None => Span::raw(" "),
+        Some(b) => {
+            let conditional = b.cond.is_some();
+            let (symbol, color) = match (b.enabled, conditional) {
+                (true, false) => ("●", RED),
+                (true, true) => ("◆", YELLOW),
+                (false, false) => ("○", GRAY_FG),
+                (false, true) => ("◇", GRAY_FG),
+            };
+            Span::styled(symbol, Style::new().fg(color))
+        }
+    }
+}
+
 /// Standard pane chrome: top border, bold colored title, optional dim
 /// context (e.g. current function or filter), right-aligned dim hints
 pub fn pane_block<'a>(
@@ -100,7 +133,7 @@ pub fn pane_block<'a>(
 fn draw_mode_content(state: &mut State, f: &mut Frame, top: ratatui::layout::Rect, mode: Mode) {
     match mode {
         Mode::All => {
-            if state.registers.is_empty() {
+            if state.view.registers.is_empty() {
                 let vertical = Layout::vertical([10 + 10 + 1 + 11]);
                 let [register] = vertical.areas(top);
 
@@ -114,7 +147,7 @@ fn draw_mode_content(state: &mut State, f: &mut Frame, top: ratatui::layout::Rec
             let asm_size = Length(11);
 
             // Only show source if we have source information
-            if !state.source_lines.is_empty() && state.current_source_line.is_some() {
+            if !state.view.source_lines.is_empty() && state.view.current_source_line.is_some() {
                 let source_size = Fill(1);
                 let vertical = Layout::vertical([register_size, stack_size, asm_size, source_size]);
                 let [register, stack, asm, source] = vertical.areas(top);
@@ -132,7 +165,7 @@ fn draw_mode_content(state: &mut State, f: &mut Frame, top: ratatui::layout::Rec
                 draw_asm(state, f, asm);
             }
         }
-        Mode::OnlyRegister => {
+        Mode::OnlyRegister | Mode::OnlyRegisterPopup => {
             let vertical = Layout::vertical([Fill(1)]);
             let [all] = vertical.areas(top);
             draw_registers(state, f, all);
@@ -152,7 +185,7 @@ fn draw_mode_content(state: &mut State, f: &mut Frame, top: ratatui::layout::Rec
             let [all] = vertical.areas(top);
             draw_mapping(state, f, all);
         }
-        Mode::OnlyHexdump => {
+        Mode::OnlyHexdump | Mode::OnlyHexdumpEdit | Mode::OnlyHexdumpSelect => {
             let vertical = Layout::vertical([Fill(1)]);
             let [all] = vertical.areas(top);
             draw_hexdump(state, f, all, HexdumpPopup::None);
@@ -167,6 +200,41 @@ fn draw_mode_content(state: &mut State, f: &mut Frame, top: ratatui::layout::Rec
             let [all] = vertical.areas(top);
             draw_hexdump(state, f, all, HexdumpPopup::Goto);
         }
+        Mode::OnlyHexdumpSearchPopup => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_hexdump(state, f, all, HexdumpPopup::Search);
+        }
+        Mode::OnlyHexdumpLoadPopup => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_hexdump(state, f, all, HexdumpPopup::Load);
+        }
+        Mode::OnlyHexdumpLoadConfirm => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_hexdump(state, f, all, HexdumpPopup::LoadConfirm);
+        }
+        Mode::OnlyHexdumpTypePopup => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_hexdump(state, f, all, HexdumpPopup::Type);
+        }
+        Mode::OnlyHexdumpLabelPopup => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_hexdump(state, f, all, HexdumpPopup::Label);
+        }
+        Mode::OnlyHexdumpBookmarks => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_hexdump(state, f, all, HexdumpPopup::Bookmarks);
+        }
+        Mode::OnlyHexdumpDiff => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_memory_diff(state, f, all);
+        }
         Mode::OnlySymbols => {
             let vertical = Layout::vertical([Fill(1)]);
             let [all] = vertical.areas(top);
@@ -177,6 +245,32 @@ fn draw_mode_content(state: &mut State, f: &mut Frame, top: ratatui::layout::Rec
             let [all] = vertical.areas(top);
             draw_source(state, f, all);
         }
+        Mode::OnlyWatchpoints => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_watchpoints(state, f, all, false);
+        }
+        Mode::OnlyWatchpointsPopup => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_watchpoints(state, f, all, true);
+        }
+        Mode::OnlyThreads => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_threads(state, f, all);
+        }
+        Mode::OnlyBacktrace => {
+            let vertical = Layout::vertical([Fill(1)]);
+            let [all] = vertical.areas(top);
+            draw_backtrace(state, f, all);
+        }
+        Mode::OnlyLocals | Mode::OnlyLocalsPopup | Mode::OnlyExpressionsPopup => {
+            let horizontal = Layout::horizontal([Fill(1), Fill(1)]);
+            let [left, right] = horizontal.areas(top);
+            draw_locals(state, f, left, mode == Mode::OnlyLocalsPopup);
+            draw_expressions(state, f, right, mode == Mode::OnlyExpressionsPopup);
+        }
         _ => (),
     }
 }
@@ -190,8 +284,15 @@ fn completion_suffixes(input: &str, completions: &[String]) -> String {
 
 pub fn ui(f: &mut Frame, state: &mut State) {
     let (completions, bt_len, mode, input_val) = {
-        (state.completions.clone(), state.bt.len(), state.mode, state.input.value().to_string())
+        (
+            state.completions.clone(),
+            state.view.bt.len(),
+            state.mode,
+            state.input.value().to_string(),
+        )
     };
+    // The backtrace tab already shows every frame
+    let bt_len = if effective_mode(state) == Mode::OnlyBacktrace { 0 } else { bt_len };
 
     // TODO: register size should depend on arch
     let top_size = Fill(1);
@@ -280,7 +381,10 @@ pub fn ui(f: &mut Frame, state: &mut State) {
         top
     };
 
-    let display_mode = if matches!(mode, Mode::QuitConfirmation | Mode::Help) {
+    let display_mode = if matches!(
+        mode,
+        Mode::QuitConfirmation | Mode::Help | Mode::Inspector | Mode::InspectorInput
+    ) {
         state.previous_mode
     } else {
         mode
@@ -295,6 +399,9 @@ pub fn ui(f: &mut Frame, state: &mut State) {
     if matches!(mode, Mode::Help) {
         help::draw_help(f);
     }
+    if matches!(mode, Mode::Inspector | Mode::InspectorInput) {
+        inspector::draw_inspector(state, f);
+    }
 }
 
 /// Apply color to val
@@ -309,6 +416,12 @@ pub fn apply_val_color(span: &mut Span, is_stack: bool, is_heap: bool, is_text:
     }
 }
 
+/// User label of `val`, as `<name>` or `<name+0xoff>` inside a labeled range
+pub fn label_span(state: &State, val: u64) -> Option<Span<'static>> {
+    let name = state.labels.describe(val)?;
+    Some(Span::styled(format!("<{name}> "), Style::new().fg(LABEL_COLOR)))
+}
+
 /// Add deref value to span
 pub fn add_deref_to_span(
     deref: &Deref,
@@ -350,6 +463,7 @@ pub fn add_deref_to_span(
         let (is_stack, is_heap, is_text) = state.classify_val(*v, filepath);
         apply_val_color(&mut span, is_stack, is_heap, is_text);
         spans.push(span);
+        spans.extend(label_span(state, *v));
     }
     if deref.repeated_pattern {
         spans.push(Span::from("→ [loop detected]").style(Style::new().fg(GRAY)));
@@ -365,18 +479,30 @@ pub fn add_deref_to_span(
     }
 }
 
-fn quit_popup_area(area: ratatui::layout::Rect) -> ratatui::layout::Rect {
-    use ratatui::layout::{Constraint, Flex};
-    let vertical = Layout::vertical([Constraint::Length(3)]).flex(Flex::Center);
-    let horizontal = Layout::horizontal([Constraint::Percentage(60)]).flex(Flex::Center);
+/// Single line area centered over `area`, `percent_x` of its width
+fn popup_area(area: Rect, percent_x: u16) -> Rect {
+    let vertical = Layout::vertical([Length(3)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Percentage(percent_x)]).flex(Flex::Center);
     let [area] = vertical.areas(area);
     let [area] = horizontal.areas(area);
     area
 }
 
+/// Single line text input centered over `area`, shared by the popups of the panes
+pub fn draw_input_popup(f: &mut Frame, area: Rect, title: &str, value: String) {
+    let area = popup_area(area, 60);
+    let txt_input = Paragraph::new(value).style(Style::default()).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(title.to_string().fg(YELLOW))
+            .border_style(Style::default().fg(ORANGE)),
+    );
+    f.render_widget(Clear, area);
+    f.render_widget(txt_input, area);
+}
+
 fn draw_quit_confirmation(f: &mut Frame) {
-    use ratatui::widgets::{Block, Borders, Clear};
-    let area = quit_popup_area(f.area());
+    let area = popup_area(f.area(), 60);
     let message =
         Paragraph::new("Are you sure you want to exit? (Enter to confirm, Esc to cancel)")
             .style(Style::default())
@@ -389,3 +515,24 @@ fn draw_quit_confirmation(f: &mut Frame) {
     f.render_widget(Clear, area);
     f.render_widget(message, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_popup_area_dimensions() {
+        let area = Rect::new(0, 0, 100, 100);
+        let popup = popup_area(area, 60);
+        assert_eq!(popup.width, 60);
+        assert_eq!(popup.height, 3);
+    }
+
+    #[test]
+    fn test_popup_area_different_sizes() {
+        let area = Rect::new(0, 0, 200, 50);
+        let popup = popup_area(area, 80);
+        assert_eq!(popup.width, 160); // 80% of 200
+        assert_eq!(popup.height, 3);
+    }
+}
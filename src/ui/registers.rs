@@ -1,28 +1,75 @@
 use std::path::PathBuf;
 
-use super::{PURPLE, RED, add_deref_to_span, apply_val_color, effective_mode, pane_block};
+use super::draw_input_popup;
+use super::{
+    BLUE, GRAY_FG, PURPLE, RED, YELLOW, add_deref_to_span, apply_val_color, effective_mode,
+    label_span, pane_block,
+};
 
 use ansi_to_tui::IntoText;
 use ratatui::text::{Line, Span, Text};
 use ratatui::widgets::{Paragraph, Scrollbar, ScrollbarOrientation};
-use ratatui::{Frame, layout::Rect, style::Style};
+use ratatui::{Frame, layout::Rect, style::Style, style::Stylize};
 
+use crate::flags::Decoded;
 use crate::register::RegisterStorage;
 use crate::{Mode, PtrSize, State};
 
 const ANSI_BYTES: &[u8] = include_bytes!("../../assets/heretek.txt");
 
+/// ` [ZF CF IF] mode=SVC` of a status register. Changed bits are red, and flags cleared since
+/// the previous stop are crossed out
+fn flag_spans<'a>(decoded: &[Decoded]) -> Vec<Span<'a>> {
+    let mut spans = vec![];
+    let flags: Vec<Span> = decoded
+        .iter()
+        .filter_map(|d| match d {
+            Decoded::Flag { name, set: true, changed: false } => {
+                Some(Span::styled(name.clone(), Style::new().fg(BLUE)))
+            }
+            Decoded::Flag { name, set: true, changed: true } => {
+                Some(Span::styled(name.clone(), Style::new().fg(RED).bold()))
+            }
+            Decoded::Flag { name, set: false, .. } => {
+                Some(Span::styled(name.clone(), Style::new().fg(GRAY_FG).crossed_out()))
+            }
+            Decoded::Field { .. } => None,
+        })
+        .collect();
+    if !flags.is_empty() {
+        spans.push(Span::raw(" ["));
+        for (i, flag) in flags.into_iter().enumerate() {
+            if i != 0 {
+                spans.push(Span::raw(" "));
+            }
+            spans.push(flag);
+        }
+        spans.push(Span::raw("]"));
+    }
+    for d in decoded {
+        if let Decoded::Field { name, value, changed } = d {
+            let style = if *changed { Style::new().fg(RED).bold() } else { Style::new().fg(BLUE) };
+            spans.push(Span::styled(format!(" {name}={value}"), style));
+        }
+    }
+    spans
+}
+
 /// Registers
 pub fn draw_registers(state: &mut State, f: &mut Frame, register: Rect) {
-    let active = matches!(effective_mode(state), Mode::All | Mode::OnlyRegister);
-    let block = pane_block("Registers", None, "", active);
+    let mode = effective_mode(state);
+    let active = matches!(mode, Mode::All | Mode::OnlyRegister | Mode::OnlyRegisterPopup);
+    // rows are selected to edit in the full pane only
+    let selecting = matches!(mode, Mode::OnlyRegister | Mode::OnlyRegisterPopup);
+    let hints = if selecting { "j/k select  e edit" } else { "" };
+    let block = pane_block("Registers", None, hints, active);
 
     let mut lines = vec![];
     let mut longest_register_name = 0;
     let mut longest_extra_val = 0;
 
     // show heretek ansi
-    if state.current_pc == 0 && state.registers.is_empty() {
+    if state.view.current_pc == 0 && state.view.registers.is_empty() {
         let text = ANSI_BYTES.into_text().unwrap();
         let paragraph = Paragraph::new(text).block(block);
         f.render_widget(paragraph, register);
@@ -31,63 +78,66 @@ pub fn draw_registers(state: &mut State, f: &mut Frame, register: Rect) {
 
     // find longest register name
     // TODO: cache this
-    for RegisterStorage { name, register, deref: _ } in &state.registers {
-        if let Some(reg) = register {
-            if !reg.is_set() {
-                continue;
-            }
-            if let Some(reg_value) = &reg.value
-                && u64::from_str_radix(&reg_value[2..], 16).is_ok()
-                && longest_register_name < name.len()
-            {
-                longest_register_name = name.len();
-            }
+    for storage in &state.view.registers {
+        if storage.shown_value().is_some() && longest_register_name < storage.name.len() {
+            longest_register_name = storage.name.len();
         }
     }
-    let width: usize = if state.ptr_size == PtrSize::Size32 { 11 } else { 19 };
+    let width: usize = if state.view.ptr_size == PtrSize::Size32 { 11 } else { 19 };
 
     let empty = PathBuf::from("");
-    let binding = state.filepath.as_ref().unwrap_or(&empty).clone();
+    let binding = state.view.filepath.as_ref().unwrap_or(&empty).clone();
     let filepath = binding.to_string_lossy();
-    let registers = state.registers.clone();
-    for (i, RegisterStorage { name, register, deref }) in registers.iter().enumerate() {
-        if let Some(reg) = register {
-            if !reg.is_set() {
-                continue;
+    let registers = state.view.registers.clone();
+    let shown = state.shown_registers().len();
+    state.registers_selected = state.registers_selected.min(shown.saturating_sub(1));
+    let mut selected_name = None;
+    for (i, storage) in registers.iter().enumerate() {
+        let RegisterStorage { name, register, deref } = storage;
+        if let Some(reg) = register
+            && let Some(val) = storage.shown_value()
+        {
+            let changed = state.view.register_changed.contains(&(i as u16));
+            let selected = selecting && lines.len() == state.registers_selected;
+            let mut reg_name = Span::from(format!("  {name:longest_register_name$}"))
+                .style(Style::new().fg(PURPLE));
+            let (is_stack, is_heap, is_text) = state.classify_val(val, &filepath);
+
+            let mut extra_derefs = Vec::new();
+            add_deref_to_span(
+                deref,
+                &mut extra_derefs,
+                state,
+                &filepath,
+                &mut longest_extra_val,
+                width,
+            );
+
+            let hex_string = reg.value.as_ref().unwrap().clone();
+            let hex_width = hex_string.len();
+            let padding_width = width.saturating_sub(hex_width);
+            let mut span =
+                Span::from(format!("→ {hex_string}{:padding$}", "", padding = padding_width));
+            apply_val_color(&mut span, is_stack, is_heap, is_text);
+
+            // Apply color to reg name, an edit outranks a change
+            if state.view.registers_edited.contains(name) {
+                reg_name = reg_name.style(Style::new().fg(YELLOW).bold());
+            } else if changed {
+                reg_name = reg_name.style(Style::new().fg(RED));
+            }
+            if selected {
+                reg_name = reg_name.reversed();
+                selected_name = Some(name.clone());
             }
-            if let Some(reg_value) = &reg.value
-                && let Ok(val) = u64::from_str_radix(&reg_value[2..], 16)
-            {
-                let changed = state.register_changed.contains(&(i as u16));
-                let mut reg_name = Span::from(format!("  {name:longest_register_name$}"))
-                    .style(Style::new().fg(PURPLE));
-                let (is_stack, is_heap, is_text) = state.classify_val(val, &filepath);
-
-                let mut extra_derefs = Vec::new();
-                add_deref_to_span(
-                    deref,
-                    &mut extra_derefs,
-                    state,
-                    &filepath,
-                    &mut longest_extra_val,
-                    width,
-                );
-
-                let hex_string = reg.value.as_ref().unwrap().clone();
-                let hex_width = hex_string.len();
-                let padding_width = width.saturating_sub(hex_width);
-                let mut span =
-                    Span::from(format!("→ {hex_string}{:padding$}", "", padding = padding_width));
-                apply_val_color(&mut span, is_stack, is_heap, is_text);
-
-                // Apply color to reg name
-                if changed {
-                    reg_name = reg_name.style(Style::new().fg(RED));
-                }
-                let mut line = Line::from(vec![reg_name, span]);
-                line.spans.append(&mut extra_derefs);
-                lines.push(line);
+            let mut line = Line::from(vec![reg_name, span]);
+            if let Some(layout) = state.flags_layout(name) {
+                let previous = state.view.registers_previous.get(name).copied();
+                line.spans.extend(flag_spans(&layout.decode(val, previous)));
             }
+            line.spans.extend(label_span(state, val));
+            line.spans.append(&mut extra_derefs);
+            lines.push(line);
         }
     }
 
@@ -95,6 +145,9 @@ pub fn draw_registers(state: &mut State, f: &mut Frame, register: Rect) {
     // account for the top border
     let visible = (register.height as usize).saturating_sub(1);
     state.registers_scroll.set_max_scroll(len.saturating_sub(visible));
+    if selecting {
+        state.registers_scroll.keep_visible(state.registers_selected, visible);
+    }
     let skip = state.registers_scroll.scroll;
 
     // TODO: remove collect, juts skip before
@@ -109,4 +162,29 @@ pub fn draw_registers(state: &mut State, f: &mut Frame, register: Rect) {
         register,
         &mut state.registers_scroll.state,
     );
+
+    if mode == Mode::OnlyRegisterPopup {
+        let title = format!("Set ${}", selected_name.unwrap_or_default());
+        draw_input_popup(f, register, &title, state.registers_popup.value().to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flags::builtin_layout;
+
+    #[test]
+    fn test_flag_spans() {
+        let eflags = builtin_layout("eflags", true).unwrap();
+        let spans = flag_spans(&eflags.decode(0x207, Some(0x246)));
+        assert_eq!(Line::from(spans.clone()).to_string(), " [CF PF ZF IF] IOPL=0");
+        // CF was set and ZF cleared since the previous stop
+        assert_eq!(spans[1].style, Style::new().fg(RED).bold());
+        assert_eq!(spans[3].style, Style::new().fg(BLUE));
+        assert_eq!(spans[5].style, Style::new().fg(GRAY_FG).crossed_out());
+
+        let nzcv = builtin_layout("nzcv", true).unwrap();
+        assert!(flag_spans(&nzcv.decode(0, None)).is_empty());
+    }
 }
@@ -1,41 +1,45 @@
 use ratatui::Frame;
 use ratatui::layout::{Constraint, Rect};
 use ratatui::style::Style;
-use ratatui::text::Line;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Cell, Row, Table, TableState};
 
 use arborium::AnsiHighlighter;
 
-use super::{GREEN, effective_mode, pane_block};
+use super::{DARK_GRAY, GREEN, breakpoint_marker, effective_mode, pane_block};
 
 use crate::{Mode, State};
 
 pub fn draw_source(state: &mut State, f: &mut Frame, area: Rect) {
-    let language = state.source_language.clone().unwrap_or_else(|| "c".to_string());
-
-    let context =
-        if let (Some(file), Some(line)) = (&state.current_source_file, state.current_source_line) {
-            let filename =
-                std::path::Path::new(file).file_name().and_then(|n| n.to_str()).unwrap_or(file);
-            Some(format!("{filename}:{line} [{language}]"))
-        } else {
-            return;
-        };
+    let language = state.view.source_language.clone().unwrap_or_else(|| "c".to_string());
+
+    let context = if let (Some(file), Some(line)) =
+        (&state.view.current_source_file, state.view.current_source_line)
+    {
+        let filename =
+            std::path::Path::new(file).file_name().and_then(|n| n.to_str()).unwrap_or(file);
+        Some(format!("{filename}:{line} [{language}]"))
+    } else {
+        return;
+    };
     let active = matches!(effective_mode(state), Mode::OnlySource);
-    let block = pane_block("Source", context, "", active);
+    let hints = if active { "b break  u until" } else { "" };
+    let block = pane_block("Source", context, hints, active);
 
-    if state.source_lines.is_empty() || state.current_source_line.is_none() {
+    if state.view.source_lines.is_empty() || state.view.current_source_line.is_none() {
         f.render_widget(block, area);
         return;
     }
 
-    let current_line = state.current_source_line.unwrap() as usize;
-    let total_lines = state.source_lines.len();
+    let current_line = state.view.current_source_line.unwrap() as usize;
+    let total_lines = state.view.source_lines.len();
 
     // Calculate which lines to show based on scroll position
     // Account for border and title
     let lines_to_show = (area.height as usize).saturating_sub(1);
     state.source_scroll.set_max_scroll(total_lines.saturating_sub(lines_to_show));
+    state.source_cursor = state.source_cursor.min(total_lines.saturating_sub(1));
+    state.source_scroll.keep_visible(state.source_cursor, lines_to_show);
     let start_line = state.source_scroll.scroll;
     let end_line = (start_line + lines_to_show).min(total_lines);
 
@@ -43,6 +47,7 @@ pub fn draw_source(state: &mut State, f: &mut Frame, area: Rect) {
     let mut highlighter = AnsiHighlighter::new(theme);
 
     let lines_to_display: Vec<String> = state
+        .view
         .source_lines
         .clone()
         .into_iter()
@@ -70,11 +75,13 @@ pub fn draw_source(state: &mut State, f: &mut Frame, area: Rect) {
         .map(|(i, line_content)| {
             let line_num = start_line + i + 1;
             let is_current = line_num == current_line;
-            let marker = if is_current {
-                Cell::from(">").style(Style::default().fg(GREEN))
+            let bp_marker = breakpoint_marker(state.breakpoint_at_line(line_num as u32));
+            let pc_marker = if is_current {
+                Span::styled(">", Style::default().fg(GREEN))
             } else {
-                Cell::from(" ")
+                Span::raw(" ")
             };
+            let marker = Cell::from(Line::from(vec![bp_marker, pc_marker]));
 
             let line_num_cell = Cell::from(format!("{:>4}", line_num)).style(if is_current {
                 Style::default().fg(GREEN)
@@ -91,11 +98,16 @@ pub fn draw_source(state: &mut State, f: &mut Frame, area: Rect) {
 
             let content_cell = Cell::from(line);
 
-            Row::new(vec![marker, line_num_cell, content_cell])
+            let row = Row::new(vec![marker, line_num_cell, content_cell]);
+            if active && line_num == state.source_cursor + 1 {
+                row.style(Style::default().bg(DARK_GRAY))
+            } else {
+                row
+            }
         })
         .collect();
 
-    let widths = [Constraint::Length(1), Constraint::Length(4), Constraint::Fill(1)];
+    let widths = [Constraint::Length(2), Constraint::Length(4), Constraint::Fill(1)];
 
     let table = Table::new(rows, widths).block(block);
 
@@ -144,8 +156,8 @@ mod tests {
     #[test]
     fn test_draw_source_with_file_no_lines() {
         let mut state = create_test_state();
-        state.current_source_file = Some("test.c".to_string());
-        state.current_source_line = Some(10);
+        state.view.current_source_file = Some("test.c".to_string());
+        state.view.current_source_line = Some(10);
         // Empty source_lines
 
         let backend = TestBackend::new(80, 24);
@@ -162,9 +174,9 @@ mod tests {
     #[test]
     fn test_draw_source_with_file_and_lines() {
         let mut state = create_test_state();
-        state.current_source_file = Some("test.c".to_string());
-        state.current_source_line = Some(5);
-        state.source_lines = vec![
+        state.view.current_source_file = Some("test.c".to_string());
+        state.view.current_source_line = Some(5);
+        state.view.source_lines = vec![
             "int main() {".to_string(),
             "    int x = 0;".to_string(),
             "    int y = 1;".to_string(),
@@ -187,10 +199,10 @@ mod tests {
     #[test]
     fn test_draw_source_many_lines_centered() {
         let mut state = create_test_state();
-        state.current_source_file = Some("test.c".to_string());
-        state.current_source_line = Some(50);
+        state.view.current_source_file = Some("test.c".to_string());
+        state.view.current_source_line = Some(50);
         // Create 100 lines
-        state.source_lines = (1..=100).map(|i| format!("line {i}")).collect();
+        state.view.source_lines = (1..=100).map(|i| format!("line {i}")).collect();
 
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -206,9 +218,9 @@ mod tests {
     #[test]
     fn test_draw_source_first_line() {
         let mut state = create_test_state();
-        state.current_source_file = Some("test.c".to_string());
-        state.current_source_line = Some(1);
-        state.source_lines = vec!["first line".to_string(), "second line".to_string()];
+        state.view.current_source_file = Some("test.c".to_string());
+        state.view.current_source_line = Some(1);
+        state.view.source_lines = vec!["first line".to_string(), "second line".to_string()];
 
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -224,9 +236,9 @@ mod tests {
     #[test]
     fn test_draw_source_last_line() {
         let mut state = create_test_state();
-        state.current_source_file = Some("/path/to/long/directory/test.c".to_string());
-        state.current_source_line = Some(10);
-        state.source_lines = (1..=10).map(|i| format!("line {i}")).collect();
+        state.view.current_source_file = Some("/path/to/long/directory/test.c".to_string());
+        state.view.current_source_line = Some(10);
+        state.view.source_lines = (1..=10).map(|i| format!("line {i}")).collect();
 
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -242,9 +254,9 @@ mod tests {
     #[test]
     fn test_draw_source_no_line_number() {
         let mut state = create_test_state();
-        state.current_source_file = Some("test.c".to_string());
-        state.current_source_line = None;
-        state.source_lines = vec!["line 1".to_string()];
+        state.view.current_source_file = Some("test.c".to_string());
+        state.view.current_source_line = None;
+        state.view.source_lines = vec!["line 1".to_string()];
 
         let backend = TestBackend::new(80, 24);
         let mut terminal = Terminal::new(backend).unwrap();
@@ -1,13 +1,17 @@
 use std::collections::HashMap;
 
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::Paragraph;
+use ratatui::widgets::{Paragraph, Scrollbar, ScrollbarOrientation};
 use ratatui::{Frame, layout::Rect, style::Style};
 
-use super::{ORANGE, PURPLE, add_deref_to_span, effective_mode, pane_block};
+use super::{
+    BLUE, GRAY_FG, ORANGE, PURPLE, RED, YELLOW, add_deref_to_span, effective_mode, label_span,
+    pane_block,
+};
 
 use crate::register::RegisterStorage;
-use crate::{Mode, PtrSize, State};
+use crate::stack::{FrameLayout, Growth};
+use crate::{Bt, Mode, PtrSize, State};
 
 /// Build a map of stack address -> register names for any register whose value
 /// points directly at an address present in the stack view
@@ -29,40 +33,134 @@ fn addr_to_regs(
     map
 }
 
+/// Line above the first slot of frame `level`, where its callee's frame ends
+fn frame_separator<'a>(bt: &[Bt], level: usize) -> Line<'a> {
+    let func = bt.get(level).and_then(|b| b.frame.func.as_deref()).unwrap_or("??");
+    Line::from(Span::styled(format!("  ── #{level} {func} ──"), Style::new().fg(GRAY_FG)))
+}
+
+/// Tags of the slot at `addr` holding `val`: the canary, the frame a return address goes back
+/// to, or the register a frame saved there, then the variables living in the slot
+fn frame_annotations<'a>(
+    layouts: &[FrameLayout],
+    bt: &[Bt],
+    addr: u64,
+    val: Option<u64>,
+    slot_len: u64,
+    canary: Option<u64>,
+) -> Vec<Span<'a>> {
+    let mut spans = vec![];
+    if val.is_some() && val == canary {
+        spans.push(Span::styled(" (canary)", Style::new().fg(YELLOW)));
+    }
+    // the pc of the outer frames is where they return to
+    let ret = bt.iter().skip(1).find(|b| Some(b.frame.addr) == val);
+    let saved = layouts
+        .iter()
+        .find_map(|l| l.saved.iter().find(|(_, a)| *a == addr).map(|(reg, _)| (reg, l.level)));
+    if let Some(ret) = ret {
+        let func = ret.frame.func.as_deref().unwrap_or("??");
+        let tag = format!(" (ret #{} {func})", ret.frame.level);
+        spans.push(Span::styled(tag, Style::new().fg(YELLOW)));
+    } else if let Some((reg, level)) = saved {
+        spans.push(Span::styled(format!(" (saved {reg} #{level})"), Style::new().fg(GRAY_FG)));
+    }
+    let vars: Vec<&str> = layouts
+        .iter()
+        .flat_map(|l| &l.vars)
+        .filter(|(_, a)| (addr..addr + slot_len).contains(a))
+        .map(|(name, _)| name.as_str())
+        .collect();
+    if !vars.is_empty() {
+        spans.push(Span::styled(format!(" {}", vars.join(", ")), Style::new().fg(BLUE)));
+    }
+    spans
+}
+
 pub fn draw_stack(state: &mut State, f: &mut Frame, stack: Rect) {
     let active = matches!(effective_mode(state), Mode::OnlyStack);
-    let block = pane_block("Stack", None, "", active);
+    let block = pane_block("Stack", None, "j/k scroll  J/K page  g/G top/end  f frame", active);
     let mut lines = vec![];
+    // slot address of each line, none for the frame separators
+    let mut rows = vec![];
     let mut longest_cells = 0;
-    let width: usize = if state.ptr_size == PtrSize::Size32 { 11 } else { 19 };
+    let width: usize = if state.view.ptr_size == PtrSize::Size32 { 11 } else { 19 };
 
-    let stacks = state.stack.clone();
+    let stacks = state.view.stack.clone();
 
     // Build map of address -> register names
-    let addr_to_regs = addr_to_regs(&state.registers, &stacks);
+    let addr_to_regs = addr_to_regs(&state.view.registers, &stacks);
+    let layouts = state.view.frame_layouts.clone();
+    let slot_len = state.stack_slot_len();
+    let smashed = state.view.smashed.clone();
+    let mut frames = layouts.iter().peekable();
+    let growth = state.view.stack_model.growth;
+    // from the stack pointer towards the callers
+    let mut slots: Vec<_> = stacks.iter().collect();
+    if growth == Growth::Up {
+        slots.reverse();
+    }
 
-    for (addr, values) in &stacks {
-        let filepath = state.filepath.clone().unwrap_or_default();
+    for (addr, values) in slots {
+        // the caller's slots start at the CFA of its callee
+        while let Some(layout) = frames.next_if(|l| !growth.in_frame(*addr, l.cfa)) {
+            lines.push(frame_separator(&state.view.bt, layout.level + 1));
+            rows.push(None);
+        }
+        let filepath = state.view.filepath.clone().unwrap_or_default();
         let filepath = filepath.to_string_lossy();
 
         let hex_string = format!("0x{addr:02x}");
         let hex_width = hex_string.len();
         let padding_width = (width - 4).saturating_sub(hex_width);
+        let color = if smashed.contains_key(addr) { RED } else { PURPLE };
         let span = Span::from(format!("  {hex_string}{:padding$}", "", padding = padding_width))
-            .style(Style::new().fg(PURPLE));
+            .style(Style::new().fg(color));
         let mut spans = vec![span];
+        if let Some(reason) = smashed.get(addr) {
+            spans.push(Span::styled(format!(" ({reason})"), Style::new().fg(RED).bold()));
+        }
         if let Some(reg_names) = addr_to_regs.get(addr) {
             let annotation = format!(" ({})", reg_names.join(", "));
             spans.push(Span::from(annotation).style(Style::new().fg(ORANGE)));
         }
+        if let Some((_, level)) = state.stack_cfa.filter(|(cfa, _)| cfa == addr) {
+            spans.push(Span::from(format!(" (cfa #{level})")).style(Style::new().fg(ORANGE)));
+        }
+        let val = values.map.front().copied();
+        let canary = state.view.canary;
+        spans.extend(frame_annotations(&layouts, &state.view.bt, *addr, val, slot_len, canary));
+        spans.extend(label_span(state, *addr));
         add_deref_to_span(values, &mut spans, state, &filepath, &mut longest_cells, width);
         let line = Line::from(spans);
         lines.push(line);
+        rows.push(Some(*addr));
+    }
+
+    // account for the borders
+    let height = stack.height.saturating_sub(2) as usize;
+    state.stack_viewport_height = height as u16;
+    state.stack_scroll.set_max_scroll(lines.len().saturating_sub(height));
+    if let Some(goto) = state.stack_goto
+        && let Some(index) = rows.iter().position(|addr| *addr == Some(goto))
+    {
+        state.stack_scroll.set(index);
+        state.stack_goto = None;
+    }
+    // read ahead of the rows shown, once a stop read the first slots
+    if state.stack_slots != 0 && !state.executing {
+        state.read_stack_slots((state.stack_scroll.scroll + 2 * height) as u64);
     }
 
+    let lines: Vec<Line> = lines.into_iter().skip(state.stack_scroll.scroll).take(height).collect();
     let text = Text::from(lines);
     let paragraph = Paragraph::new(text).block(block);
     f.render_widget(paragraph, stack);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        stack,
+        &mut state.stack_scroll.state,
+    );
 }
 
 #[cfg(test)]
@@ -108,4 +206,38 @@ mod tests {
         assert_eq!(map.get(&0x7fffffffb6a0), Some(&vec!["rax".to_string(), "rbp".to_string()]));
         assert!(!map.contains_key(&0x401000));
     }
+
+    #[test]
+    fn test_frame_annotations() {
+        let frame = |level: u32, addr: u64, func: &str| Bt {
+            frame: crate::mi::Frame {
+                level,
+                addr,
+                func: Some(func.to_string()),
+                ..Default::default()
+            },
+            args: vec![],
+        };
+        let bt = vec![frame(0, 0x401136, "parse"), frame(1, 0x4011a0, "main")];
+        let layouts = vec![FrameLayout {
+            level: 0,
+            func: Some("parse".to_string()),
+            has_source: true,
+            cfa: 0x7fffffffe0f0,
+            saved: vec![("rbp".to_string(), 0x7fffffffe0e0), ("rip".to_string(), 0x7fffffffe0e8)],
+            vars: vec![("buf".to_string(), 0x7fffffffe0c0), ("i".to_string(), 0x7fffffffe0c4)],
+        }];
+        let text = |addr, val| {
+            let spans = frame_annotations(&layouts, &bt, addr, val, 8, Some(0x1122334455667700));
+            Line::from(spans).to_string()
+        };
+        assert_eq!(text(0x7fffffffe0e8, Some(0x4011a0)), " (ret #1 main)");
+        // a smashed return address is still the saved rip
+        assert_eq!(text(0x7fffffffe0e8, Some(0x4141414141414141)), " (saved rip #0)");
+        assert_eq!(text(0x7fffffffe0e0, Some(0x7fffffffe100)), " (saved rbp #0)");
+        assert_eq!(text(0x7fffffffe0c0, Some(0)), " buf, i");
+        assert_eq!(text(0x7fffffffe0d0, None), "");
+        assert_eq!(text(0x7fffffffe0d8, Some(0x1122334455667700)), " (canary)");
+        assert_eq!(frame_separator(&bt, 1).to_string(), "  ── #1 main ──");
+    }
 }
@@ -7,7 +7,7 @@ use ratatui::style::Style;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 
-use super::{BLUE, DARK_GRAY, GRAY_FG, GREEN, ORANGE, YELLOW};
+use super::{BLUE, DARK_GRAY, GRAY_FG, GREEN, ORANGE, RED, YELLOW};
 use crate::{InputMode, State};
 
 const SPINNER_FRAMES: [&str; 8] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧"];
@@ -25,19 +25,41 @@ pub fn draw_status_bar(state: &State, f: &mut Frame, area: Rect) {
     // execution state
     if state.executing {
         spans.push(Span::styled(format!("{} running", spinner_frame()), Style::new().fg(YELLOW)));
-    } else if !state.registers.is_empty() || state.current_pc != 0 {
+    } else if !state.view.registers.is_empty() || state.view.current_pc != 0 {
         spans.push(Span::styled("● stopped", Style::new().fg(GREEN)));
-        if let Some(function) = state.bt.first().and_then(|b| b.function.clone()) {
+        if let Some(function) =
+            state.view.bt.get(state.bt_selected).and_then(|b| b.frame.func.clone())
+        {
             spans.push(Span::styled(format!(" in {function}"), Style::new().fg(GREEN)));
         }
-        if let (Some(file), Some(line)) = (&state.current_source_file, state.current_source_line) {
+        if let (Some(file), Some(line)) =
+            (&state.view.current_source_file, state.view.current_source_line)
+        {
             let filename = Path::new(file).file_name().and_then(|n| n.to_str()).unwrap_or(file);
             spans.push(Span::styled(format!(" @ {filename}:{line}"), Style::new().fg(GRAY_FG)));
         }
+        // registers and locals are those of an outer frame
+        if state.bt_selected != 0 {
+            spans.push(Span::styled(
+                format!("  frame #{}", state.bt_selected),
+                Style::new().fg(YELLOW).bold(),
+            ));
+        }
     } else {
         spans.push(Span::styled("○ no program", Style::new().fg(GRAY_FG)));
     }
 
+    // stack slots that look smashed
+    let smashed = &state.view.smashed;
+    if let Some((addr, reason)) = smashed.iter().next() {
+        let more =
+            if smashed.len() > 1 { format!(" (+{})", smashed.len() - 1) } else { String::new() };
+        spans.push(Span::styled(
+            format!("  ⚠ stack smashed at 0x{addr:x}: {reason}{more}"),
+            Style::new().fg(RED).bold(),
+        ));
+    }
+
     // last async result from gdb
     let detail = state.async_result.strip_prefix("Status: ").unwrap_or(&state.async_result);
     if !detail.is_empty() {
@@ -53,6 +75,21 @@ pub fn draw_status_bar(state: &State, f: &mut Frame, area: Rect) {
         ));
     }
 
+    // file load into target memory
+    if let Some(load) = &state.view.memory_load
+        && let Some(written) = load.written
+    {
+        spans.push(Span::styled(
+            format!(
+                "  loading {} 0x{written:x}/0x{:x} ({}%)",
+                load.path,
+                load.bytes.len(),
+                written * 100 / load.bytes.len()
+            ),
+            Style::new().fg(YELLOW),
+        ));
+    }
+
     let input_hint = match state.input_mode {
         InputMode::Normal => "i input  ",
         InputMode::Editing => "Esc done  ⏎ send  ",
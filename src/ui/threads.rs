@@ -0,0 +1,126 @@
+use ratatui::layout::Constraint;
+use ratatui::widgets::{Scrollbar, ScrollbarOrientation, Table};
+use ratatui::{Frame, layout::Rect, style::Style, widgets::Row};
+
+use super::{BLUE, GRAY_FG, GREEN, ORANGE, effective_mode, pane_block};
+
+use crate::{Mode, State};
+
+pub fn draw_threads(state: &mut State, f: &mut Frame, area: Rect) {
+    let header = Row::new(["", "Id", "Target Id", "Name", "State", "Frame", "Core"])
+        .style(Style::new().fg(BLUE).bold());
+
+    let len = state.threads.len();
+    state.threads_selected = state.threads_selected.min(len.saturating_sub(1));
+    // Account for top border and pinned header row
+    let max = area.height.saturating_sub(2) as usize;
+    state.threads_scroll.set_max_scroll(len.saturating_sub(max));
+    state.threads_scroll.keep_visible(state.threads_selected, max);
+    let skip = state.threads_scroll.scroll;
+
+    let rows: Vec<Row> = state
+        .threads
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(max)
+        .map(|(index, t)| {
+            let is_current = state.current_thread_id.as_ref() == Some(&t.id);
+            let frame = t
+                .frame
+                .as_ref()
+                .map(|frame| {
+                    let func = frame.func.clone().unwrap_or_else(|| "??".to_string());
+                    match frame.location() {
+                        Some(location) => format!("0x{:02x} {func} at {location}", frame.addr),
+                        None => format!("0x{:02x} {func}", frame.addr),
+                    }
+                })
+                .unwrap_or_default();
+            let row = Row::new([
+                if is_current { "*" } else { "" }.to_string(),
+                t.id.clone(),
+                t.target_id.clone(),
+                t.name.clone().unwrap_or_default(),
+                t.state.clone(),
+                frame,
+                t.core.clone().unwrap_or_default(),
+            ]);
+            if index == state.threads_selected {
+                row.style(Style::new().fg(ORANGE).bold())
+            } else if is_current {
+                row.style(Style::new().fg(GREEN))
+            } else if t.state == "running" {
+                row.style(Style::new().fg(GRAY_FG))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(1),
+        Constraint::Length(4),
+        Constraint::Length(36),
+        Constraint::Length(16),
+        Constraint::Length(8),
+        Constraint::Fill(1),
+        Constraint::Length(4),
+    ];
+    let active = matches!(effective_mode(state), Mode::OnlyThreads);
+    let context = state.current_thread_id.as_ref().map(|id| format!("thread {id}"));
+    let block = pane_block("Threads", context, "⏎ select", active);
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area,
+        &mut state.threads_scroll.state,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mi::parse_threads;
+    use crate::{Args, PtrSize};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_draw_threads() {
+        let mut state = create_test_state();
+        state.threads = parse_threads(
+            r#"[{id="1",target-id="Thread 0x7ffff7d8a740 (LWP 4241)",name="a.out",frame={level="0",addr="0x0000000000401136",func="main",args=[],file="test.c",line="11"},state="stopped",core="3"},{id="2",target-id="Thread 0x7ffff7d89640 (LWP 4242)",state="running"}]"#,
+        );
+        state.current_thread_id = Some("1".to_string());
+        state.threads_selected = 5;
+
+        let backend = TestBackend::new(120, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_threads(&mut state, f, area);
+            })
+            .unwrap();
+
+        // selection is clamped to the thread list
+        assert_eq!(state.threads_selected, 1);
+        let buffer = terminal.backend().buffer();
+        let row: String = (0..120).map(|x| buffer[(x, 2)].symbol()).collect();
+        assert!(row.starts_with("* 1"));
+        assert!(row.contains("0x401136 main at test.c:11"));
+    }
+}
@@ -13,7 +13,7 @@ use crate::State;
 pub fn draw_title_area(state: &mut State, f: &mut Frame, title_area: Rect) {
     let vertical_title = Layout::vertical([Length(1), Length(1)]);
     let [first, second] = vertical_title.areas(title_area);
-    f.render_widget(
+    let mut block =
         Block::new().borders(Borders::TOP).border_style(Style::default().fg(GRAY)).title_top(
             Line::from(vec![
                 Span::raw(" "),
@@ -24,12 +24,20 @@ pub fn draw_title_area(state: &mut State, f: &mut Frame, title_area: Rect) {
                 ),
             ])
             .centered(),
-        ),
-        first,
-    );
+        );
+    if let Some(switcher) = inferior_switcher(state, first) {
+        block = block.title_top(switcher);
+    }
+    f.render_widget(block, first);
     let mode = &state.mode;
     // Use previous_mode's index when in an overlay to maintain selection
-    let selected_index = if matches!(mode, crate::Mode::QuitConfirmation | crate::Mode::Help) {
+    let selected_index = if matches!(
+        mode,
+        crate::Mode::QuitConfirmation
+            | crate::Mode::Help
+            | crate::Mode::Inspector
+            | crate::Mode::InspectorInput
+    ) {
         state.previous_mode.ui_index()
     } else {
         mode.ui_index()
@@ -44,10 +52,19 @@ pub fn draw_title_area(state: &mut State, f: &mut Frame, title_area: Rect) {
         "F7 Hexdump",
         "F8 Symbols",
         "F9 Source",
+        "F10 Watch",
+        "Alt+t Threads",
+        "F12 Backtrace",
+        "Alt+l Locals",
     ];
 
+    // Drop the padding around each title when they would not all fit
+    let titles_width = titles.iter().map(|t| t.chars().count() + 3).sum::<usize>() - 1;
+    let pad = u16::from(titles_width <= second.width as usize);
+    let padding = if pad == 1 { " " } else { "" };
+
     // Record clickable regions for mouse tab selection, mirroring ratatui's
-    // Tabs layout: [pad_left(1)][title][pad_right(1)]([divider(1)])... starting
+    // Tabs layout: [pad_left][title][pad_right]([divider(1)])... starting
     // at the left of `second` (the block has no borders, so inner == area)
     state.tab_regions.clear();
     let mut x = second.left();
@@ -55,12 +72,12 @@ pub fn draw_title_area(state: &mut State, f: &mut Frame, title_area: Rect) {
         if x >= second.right() {
             break;
         }
-        x += 1; // left padding
+        x += pad; // left padding
         let start = x;
         let title_width = title.chars().count() as u16;
         x = (x + title_width).min(second.right());
         state.tab_regions.push((second.top(), start, x));
-        x += 1; // right padding
+        x += pad; // right padding
         if i + 1 < titles.len() {
             x += 1; // divider
         }
@@ -71,7 +88,126 @@ pub fn draw_title_area(state: &mut State, f: &mut Frame, title_area: Rect) {
         .style(Style::default())
         .highlight_style(Style::default().fg(GREEN).add_modifier(Modifier::BOLD))
         .select(selected_index)
+        .padding(padding, padding)
         .divider("|".fg(GRAY_FG));
 
     f.render_widget(tab, second);
 }
+
+/// Inferiors on the right of the top row, only shown once there is more than one. Records
+/// their clickable regions like the tabs
+fn inferior_switcher(state: &mut State, area: Rect) -> Option<Line<'static>> {
+    state.inferior_regions.clear();
+    if state.inferiors.len() < 2 {
+        return None;
+    }
+
+    let labels: Vec<String> = state
+        .inferiors
+        .iter()
+        .map(|i| match (i.pid, &i.exit_code) {
+            (Some(pid), _) => format!("{} {pid}", i.id),
+            (None, Some(code)) => format!("{} exited {code}", i.id),
+            (None, None) => i.id.clone(),
+        })
+        .collect();
+    let width = labels.iter().map(|l| l.chars().count() + 1).sum::<usize>() + 1;
+    let mut x = area.right().saturating_sub(width as u16);
+
+    let mut spans = vec![Span::raw(" ")];
+    for (i, label) in labels.into_iter().enumerate() {
+        if i != 0 {
+            spans.push("|".fg(GRAY_FG));
+        }
+        x += 1; // leading space or divider
+        let label_width = label.chars().count() as u16;
+        state.inferior_regions.push((area.top(), x, x + label_width));
+        x += label_width;
+        let style = if i == state.inferior_selected {
+            Style::default().fg(GREEN).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        spans.push(Span::styled(label, style));
+    }
+    spans.push(Span::raw(" "));
+    Some(Line::from(spans).right_aligned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Args, PtrSize};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    #[test]
+    fn test_tab_regions_match_titles() {
+        for width in [160, 200] {
+            let mut state = create_test_state();
+            let backend = TestBackend::new(width, 2);
+            let mut terminal = Terminal::new(backend).unwrap();
+            terminal
+                .draw(|f| {
+                    let area = f.area();
+                    draw_title_area(&mut state, f, area);
+                })
+                .unwrap();
+
+            let buffer = terminal.backend().buffer();
+            let &(y, start, end) = state.tab_regions.last().unwrap();
+            let title: String = (start..end).map(|x| buffer[(x, y)].symbol()).collect();
+            assert_eq!(title, "Alt+l Locals", "width {width}");
+        }
+    }
+
+    #[test]
+    fn test_inferior_switcher() {
+        let mut state = create_test_state();
+        state.inferiors[0].pid = Some(4242);
+        state.inferiors.push(crate::Inferior::new("i2"));
+        state.inferiors[1].exit_code = Some("01".to_string());
+        state.inferior_selected = 1;
+
+        let backend = TestBackend::new(80, 2);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_title_area(&mut state, f, area);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let labels: Vec<String> = state
+            .inferior_regions
+            .iter()
+            .map(|&(y, start, end)| (start..end).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+        assert_eq!(labels, vec!["i1 4242", "i2 exited 01"]);
+        let (y, start, _) = state.inferior_regions[1];
+        assert_eq!(buffer[(start, y)].fg, GREEN);
+
+        // hidden with a single inferior
+        state.inferiors.pop();
+        state.inferior_selected = 0;
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_title_area(&mut state, f, area);
+            })
+            .unwrap();
+        assert!(state.inferior_regions.is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+use deku::ctx::Endian;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+};
+
+use super::data_inspector::{c_string, signed, unsigned};
+use super::{BLUE, GRAY_FG, pane_block};
+use crate::State;
+use crate::overlay::{TypeField, TypeOverlay};
+
+/// Backgrounds of the fields of the type overlay, in turn, dark enough for the byte colors
+const OVERLAY_COLORS: [Color; 4] = [
+    Color::Rgb(0x1f, 0x3a, 0x4f),
+    Color::Rgb(0x4a, 0x2f, 0x1f),
+    Color::Rgb(0x2a, 0x45, 0x24),
+    Color::Rgb(0x42, 0x2a, 0x4f),
+];
+
+/// Width of the field names, past their nesting
+const NAME_WIDTH: usize = 16;
+
+/// Bytes of an array shown before it is cut
+const ARRAY_PREVIEW: usize = 8;
+
+/// Background of field `index`, from its place among the fields that hold bytes
+fn field_color(overlay: &TypeOverlay, index: usize) -> Color {
+    let ordinal = overlay.fields[..index].iter().filter(|f| !f.aggregate).count();
+    OVERLAY_COLORS[ordinal % OVERLAY_COLORS.len()]
+}
+
+/// Background of the byte at `offset` of the hexdump, from the field of the type overlay
+/// covering it
+pub fn overlay_color(state: &State, offset: usize) -> Option<Color> {
+    let overlay = state.view.type_overlay.as_ref()?;
+    let memory = state.view.hexdump.as_ref()?;
+    let (_, offset) = overlay.element_at(memory.start + offset as u64)?;
+    let index = overlay.field_at(offset)?;
+    Some(field_color(overlay, index))
+}
+
+fn is_unsigned(ty: &str) -> bool {
+    ty.contains("unsigned")
+        || ty.starts_with('u')
+        || ty.starts_with("size_t")
+        || ty.starts_with("enum")
+        || ty == "_Bool"
+        || ty == "bool"
+}
+
+/// Integer with its hex, when that tells more
+fn integer(val: u64, ty: &str, size: usize) -> String {
+    if is_unsigned(ty) {
+        if val < 10 { val.to_string() } else { format!("{val} (0x{val:x})") }
+    } else {
+        signed(val, size).to_string()
+    }
+}
+
+/// Bytes in hex, cut after [`ARRAY_PREVIEW`]
+fn hex_bytes(bytes: &[u8]) -> String {
+    let hex: Vec<String> = bytes.iter().take(ARRAY_PREVIEW).map(|b| format!("{b:02x}")).collect();
+    format!("{}{}", hex.join(" "), if bytes.len() > ARRAY_PREVIEW { " …" } else { "" })
+}
+
+/// Value of `field` decoded from the `bytes` of its range, by its C type
+fn field_value(field: &TypeField, bytes: &[u8], big: bool) -> String {
+    let ty = field.ty.as_str();
+    if let Some((pos, width)) = field.bits {
+        let fold = |acc: u128, b: &u8| (acc << 8) | u128::from(*b);
+        let val = if big { bytes.iter().fold(0, fold) } else { bytes.iter().rev().fold(0, fold) };
+        let shift = if big { bytes.len() * 8 - pos - width } else { pos };
+        let val = ((val >> shift) & ((1u128 << width) - 1)) as u64;
+        let sign_bit = 1u64 << (width - 1);
+        return if is_unsigned(ty) || val & sign_bit == 0 {
+            val.to_string()
+        } else {
+            (val as i64 - (sign_bit << 1) as i64).to_string()
+        };
+    }
+    if let Some((element, _)) = ty.split_once(" [") {
+        return if element.ends_with("char") { c_string(bytes) } else { hex_bytes(bytes) };
+    }
+    let size = bytes.len();
+    let Some(val) = unsigned(bytes, size.min(8), big) else {
+        return String::new();
+    };
+    if ty.ends_with('*') || ty.contains("(*") {
+        format!("0x{val:x}")
+    } else if ty == "float" && size == 4 {
+        f32::from_bits(val as u32).to_string()
+    } else if ty == "double" && size == 8 {
+        f64::from_bits(val).to_string()
+    } else if ty == "_Bool" || ty == "bool" {
+        (val != 0).to_string()
+    } else if matches!(size, 1 | 2 | 4 | 8) {
+        integer(val, ty, size)
+    } else {
+        hex_bytes(bytes)
+    }
+}
+
+/// Fields of one element of the overlay from its `bytes`, `None` where not read. The names are on
+/// the colors of their bytes in the hexdump, and the field under the cursor is reversed with its
+/// type below
+fn overlay_lines<'a>(
+    overlay: &TypeOverlay,
+    bytes: &[Option<u8>],
+    selected: Option<usize>,
+    big: bool,
+) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{:>4} {:<NAME_WIDTH$} {}", "off", "field", "value"),
+        Style::new().fg(BLUE).bold(),
+    ))];
+    for (index, field) in overlay.fields.iter().enumerate() {
+        let indent = "  ".repeat(field.depth);
+        let offset = Span::styled(format!("{:>4x} ", field.offset), Style::new().fg(GRAY_FG));
+        if field.aggregate {
+            let name = format!("{indent}{} {}", field.ty, field.name);
+            lines.push(Line::from(vec![offset, Span::styled(name, Style::new().fg(BLUE))]));
+            continue;
+        }
+        let width = NAME_WIDTH.saturating_sub(indent.len());
+        let mut style = Style::new().fg(Color::White).bg(field_color(overlay, index));
+        if selected == Some(index) {
+            style = style.reversed();
+        }
+        let field_bytes: Option<Vec<u8>> =
+            field.range().map(|offset| bytes.get(offset).copied().flatten()).collect();
+        let value = field_bytes.map_or(String::new(), |b| field_value(field, &b, big));
+        lines.push(Line::from(vec![
+            offset,
+            Span::raw(indent),
+            Span::styled(format!("{:<width$}", field.name), style),
+            Span::raw(format!(" {value}")),
+        ]));
+    }
+    if let Some(field) = selected.and_then(|index| overlay.fields.get(index)) {
+        let place = match field.bits {
+            Some((pos, width)) => format!("bits {pos}..{} of +0x{:x}", pos + width, field.offset),
+            None => format!("{} bytes at +0x{:x}", field.size, field.offset),
+        };
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(
+            format!("{} {}, {place}", field.ty, field.name),
+            Style::new().fg(GRAY_FG),
+        )));
+    }
+    lines
+}
+
+/// Fields of the element of the type overlay under the hexdump cursor, or of the first one,
+/// next to the hexdump instead of the data inspector
+pub fn draw_type_overlay(state: &State, f: &mut Frame, area: Rect) {
+    let (Some(overlay), Some(memory)) = (&state.view.type_overlay, &state.view.hexdump) else {
+        return;
+    };
+    let cursor = memory.start + state.view.hexdump_cursor as u64;
+    let (index, selected) = match overlay.element_at(cursor) {
+        Some((index, offset)) => (index, overlay.field_at(offset)),
+        None => (0, None),
+    };
+    let element = overlay.addr + (index * overlay.size) as u64;
+    let bytes: Vec<Option<u8>> = (0..overlay.size)
+        .map(|i| {
+            let offset = usize::try_from(element.checked_sub(memory.start)?).ok()? + i;
+            state.hexdump_byte(offset)
+        })
+        .collect();
+    let big = state.view.endian == Some(Endian::Big);
+    let lines = overlay_lines(overlay, &bytes, selected, big);
+
+    // keep the field under the cursor in view, below the header
+    let height = (area.height as usize).saturating_sub(1);
+    let scroll = selected.map_or(0, |selected| (selected + 2).saturating_sub(height));
+    let name = if overlay.count == Some(1) {
+        overlay.name.clone()
+    } else {
+        format!("{}[{index}]", overlay.name)
+    };
+    let paragraph = Paragraph::new(lines)
+        .block(pane_block("Type", Some(format!("{name} 0x{element:x}")), "t type", false))
+        .scroll((scroll as u16, 0));
+    f.render_widget(paragraph, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overlay::parse_ptype_offsets;
+
+    #[test]
+    fn test_overlay_lines() {
+        let output = "/* offset      |    size */  type = struct hdr {
+/*      0      |       2 */    uint16_t magic;
+/*      2: 0   |       1 */    signed char level : 4;
+/* XXX  1-byte hole      */
+/*      4      |       4 */    float ratio;
+/*      8      |       8 */    char *name;
+/*     16      |       4 */    char tag[4];
+/*     20      |       4 */    struct {
+/*     20      |       4 */        int delta;
+
+                                   /* total size (bytes):    4 */
+                               } inner;
+
+                               /* total size (bytes):   24 */
+                             }
+";
+        let mut overlay = TypeOverlay::new("struct hdr", 0x1000).unwrap();
+        (overlay.size, overlay.fields) = parse_ptype_offsets(output).unwrap();
+        let mut bytes = vec![0x34, 0x12, 0x0e, 0, 0, 0, 0x80, 0x3f];
+        bytes.extend(0x401000u64.to_le_bytes());
+        bytes.extend(b"ab\0\0");
+        bytes.extend((-3i32).to_le_bytes());
+        let mut bytes: Vec<Option<u8>> = bytes.into_iter().map(Some).collect();
+        let lines = overlay_lines(&overlay, &bytes, Some(1), false);
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert!(text[1].ends_with("magic            4660 (0x1234)"), "{}", text[1]);
+        // the sign of a bitfield
+        assert!(text[2].ends_with(" -2"), "{}", text[2]);
+        assert!(text[3].ends_with(" 1"), "{}", text[3]);
+        assert!(text[4].ends_with(" 0x401000"), "{}", text[4]);
+        assert!(text[5].ends_with(" \"ab\""), "{}", text[5]);
+        assert!(text[6].ends_with("  14 struct inner"), "{}", text[6]);
+        assert!(text[7].ends_with("delta          -3"), "{}", text[7]);
+        assert_eq!(text.last().unwrap(), "signed char level, bits 0..4 of +0x2");
+        assert!(lines[2].spans[2].style.add_modifier.contains(ratatui::style::Modifier::REVERSED));
+        assert_eq!(lines[1].spans[2].style.bg, Some(OVERLAY_COLORS[0]));
+        assert_eq!(lines[3].spans[2].style.bg, Some(OVERLAY_COLORS[2]));
+
+        // not read yet
+        bytes[0] = None;
+        let lines = overlay_lines(&overlay, &bytes, None, false);
+        assert!(lines[1].to_string().ends_with("magic            "));
+    }
+}
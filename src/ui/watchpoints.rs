@@ -0,0 +1,219 @@
+use ratatui::layout::Constraint;
+use ratatui::prelude::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Cell, Scrollbar, ScrollbarOrientation, Table};
+use ratatui::{
+    Frame,
+    layout::{Layout, Rect},
+    style::Style,
+    widgets::Row,
+};
+
+use super::draw_input_popup;
+use super::{BLUE, GRAY_FG, GREEN, ORANGE, PURPLE, effective_mode, pane_block};
+use crate::{Mode, State};
+
+pub fn draw_watchpoints(state: &mut State, f: &mut Frame, area: Rect, popup: bool) {
+    // Room for up to 8 watchpoints, the rest goes to the history
+    let list_height = state.watchpoints().len().clamp(1, 8) as u16 + 2;
+    let vertical = Layout::vertical([Constraint::Length(list_height), Constraint::Fill(1)]);
+    let [list_area, history_area] = vertical.areas(area);
+
+    draw_watchpoint_list(state, f, list_area);
+    draw_watchpoint_history(state, f, history_area);
+
+    if popup {
+        let title = format!("{} expression", state.watchpoint_popup_kind.name());
+        draw_input_popup(f, area, &title, state.watchpoint_popup.value().to_string());
+    }
+}
+
+fn draw_watchpoint_list(state: &mut State, f: &mut Frame, area: Rect) {
+    let active =
+        matches!(effective_mode(state), Mode::OnlyWatchpoints | Mode::OnlyWatchpointsPopup);
+    let block =
+        pane_block("Watchpoints", None, "w watch  r rwatch  a awatch  e enable  d delete", active);
+
+    let header =
+        Row::new(["Num", "Type", "Enb", "Hits", "Expression"]).style(Style::new().fg(BLUE).bold());
+
+    let len = state.watchpoints().len();
+    state.watchpoints_selected = state.watchpoints_selected.min(len.saturating_sub(1));
+    let max = area.height.saturating_sub(2) as usize;
+    state.watchpoints_scroll.set_max_scroll(len.saturating_sub(max));
+    state.watchpoints_scroll.keep_visible(state.watchpoints_selected, max);
+    let skip = state.watchpoints_scroll.scroll;
+
+    let rows: Vec<Row> = state
+        .watchpoints()
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .take(max)
+        .map(|(index, w)| {
+            let row = Row::new([
+                w.number.clone(),
+                w.kind.clone(),
+                if w.enabled { "y" } else { "n" }.to_string(),
+                w.times.to_string(),
+                w.what.clone().unwrap_or_default(),
+            ]);
+            if index == state.watchpoints_selected {
+                row.style(Style::new().fg(ORANGE).bold())
+            } else if !w.enabled {
+                row.style(Style::new().fg(GRAY_FG))
+            } else {
+                row
+            }
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(5),
+        Constraint::Length(15),
+        Constraint::Length(4),
+        Constraint::Length(6),
+        Constraint::Fill(1),
+    ];
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+}
+
+fn draw_watchpoint_history(state: &mut State, f: &mut Frame, area: Rect) {
+    let context = state
+        .watchpoints()
+        .get(state.watchpoints_selected)
+        .map(|w| format!("#{} {}", w.number, w.what.clone().unwrap_or_default()));
+    let block = pane_block("History", context, "J/K scroll", false);
+
+    let header =
+        Row::new(["#", "Num", "Old", "New", "PC", "Function"]).style(Style::new().fg(BLUE).bold());
+
+    // Newest first, numbered in the order they were hit
+    let len = state.watchpoint_history().len();
+    let max = area.height.saturating_sub(2) as usize;
+    state.watchpoint_hits_scroll.set_max_scroll(len.saturating_sub(max));
+    let skip = state.watchpoint_hits_scroll.scroll;
+
+    let rows: Vec<Row> = state
+        .watchpoint_history()
+        .iter()
+        .enumerate()
+        .rev()
+        .skip(skip)
+        .take(max)
+        .map(|(index, hit)| {
+            let old = hit.old.clone().unwrap_or_else(|| "-".to_string());
+            let new = hit.new.clone().unwrap_or_else(|| "-".to_string());
+            let changed = hit.old.is_some() && hit.old != hit.new;
+            let new_cell = if changed {
+                Cell::from(Line::from(Span::styled(new, Style::new().fg(GREEN))))
+            } else {
+                Cell::from(new)
+            };
+            Row::new(vec![
+                Cell::from((index + 1).to_string()),
+                Cell::from(hit.number.clone()),
+                Cell::from(old).fg(ORANGE),
+                new_cell,
+                Cell::from(format!("0x{:02x}", hit.pc)).fg(PURPLE),
+                Cell::from(hit.func.clone().unwrap_or_default()).fg(PURPLE),
+            ])
+        })
+        .collect();
+
+    let widths = [
+        Constraint::Length(5),
+        Constraint::Length(5),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+        Constraint::Length(18),
+        Constraint::Fill(1),
+    ];
+    let table = Table::new(rows, widths).header(header).block(block);
+    f.render_widget(table, area);
+    f.render_stateful_widget(
+        Scrollbar::new(ScrollbarOrientation::VerticalRight),
+        area,
+        &mut state.watchpoint_hits_scroll.state,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mi::{WatchpointHit, parse_breakpoint};
+    use crate::{Args, PtrSize};
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    fn create_test_state() -> State {
+        let args = Args {
+            gdb_path: None,
+            remote: None,
+            ptr_size: PtrSize::Size64,
+            cmds: None,
+            log_path: None,
+        };
+        State::new(args)
+    }
+
+    fn hit(number: &str, old: &str, new: &str) -> WatchpointHit {
+        WatchpointHit {
+            number: number.to_string(),
+            exp: "counter".to_string(),
+            old: Some(old.to_string()),
+            new: Some(new.to_string()),
+            pc: 0x401136,
+            func: Some("bump".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_draw_watchpoints_empty() {
+        let mut state = create_test_state();
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_watchpoints(&mut state, f, area, false);
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn test_draw_watchpoints_history_of_selected() {
+        let mut state = create_test_state();
+        for number in ["2", "3"] {
+            state.upsert_breakpoint(
+                parse_breakpoint(&format!(
+                    r#"{{number="{number}",type="hw watchpoint",enabled="y",what="counter",times="1"}}"#
+                ))
+                .unwrap(),
+            );
+        }
+        state.watchpoint_hits = vec![hit("2", "0", "1"), hit("3", "5", "6"), hit("2", "1", "2")];
+        state.watchpoints_selected = 0;
+        assert_eq!(state.watchpoint_history().len(), 2);
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| {
+                let area = f.area();
+                draw_watchpoints(&mut state, f, area, true);
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y: u16| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        // the newest hit of #2 is listed first in the history
+        let first_hit = (0..24).map(row).find(|r| r.contains("0x401136")).unwrap();
+        assert_eq!(
+            first_hit.split_whitespace().collect::<Vec<_>>(),
+            vec!["2", "2", "1", "2", "0x401136", "bump"]
+        );
+    }
+}